@@ -2,13 +2,30 @@ package cash.z.ecc.android.sdk.internal
 
 import cash.z.ecc.android.sdk.internal.model.JniAccount
 import cash.z.ecc.android.sdk.internal.model.JniAccountUsk
+import cash.z.ecc.android.sdk.internal.model.JniAutoShieldingPolicy
+import cash.z.ecc.android.sdk.internal.model.JniBlockCacheReport
 import cash.z.ecc.android.sdk.internal.model.JniBlockMeta
+import cash.z.ecc.android.sdk.internal.model.JniDecodedMemo
+import cash.z.ecc.android.sdk.internal.model.JniHistoricalBalance
+import cash.z.ecc.android.sdk.internal.model.JniMigrationStep
+import cash.z.ecc.android.sdk.internal.model.JniMigrationStepProposal
+import cash.z.ecc.android.sdk.internal.model.JniPcztSummary
+import cash.z.ecc.android.sdk.internal.model.JniProvingListener
+import cash.z.ecc.android.sdk.internal.model.JniReceivedOutput
+import cash.z.ecc.android.sdk.internal.model.JniRewindPreview
 import cash.z.ecc.android.sdk.internal.model.JniRewindResult
+import cash.z.ecc.android.sdk.internal.model.JniSaplingParamStatus
 import cash.z.ecc.android.sdk.internal.model.JniScanRange
 import cash.z.ecc.android.sdk.internal.model.JniScanSummary
 import cash.z.ecc.android.sdk.internal.model.JniSingleUseTransparentAddress
+import cash.z.ecc.android.sdk.internal.model.JniSpendableInput
 import cash.z.ecc.android.sdk.internal.model.JniSubtreeRoot
 import cash.z.ecc.android.sdk.internal.model.JniTransactionDataRequest
+import cash.z.ecc.android.sdk.internal.model.JniTransactionHistoryPage
+import cash.z.ecc.android.sdk.internal.model.JniTransactionHistoryQuery
+import cash.z.ecc.android.sdk.internal.model.JniTransactionOutput
+import cash.z.ecc.android.sdk.internal.model.JniTransparentReceiverBalance
+import cash.z.ecc.android.sdk.internal.model.JniUnminedTransaction
 import cash.z.ecc.android.sdk.internal.model.JniWalletSummary
 import cash.z.ecc.android.sdk.internal.model.ProposalUnsafe
 import kotlinx.coroutines.withContext
@@ -35,6 +52,118 @@ interface Backend {
         memo: ByteArray? = null
     ): ProposalUnsafe
 
+    /**
+     * Returns a page of the notes and transparent outputs received by the given account, most recent
+     * first, along with their spend status.
+     *
+     * @param offset the number of outputs to skip
+     * @param limit the maximum number of outputs to return
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun listReceivedOutputs(
+        accountUuid: ByteArray,
+        offset: Long,
+        limit: Long
+    ): List<JniReceivedOutput>
+
+    /**
+     * Returns the funds held by each of the account's transparent receivers, including change and ephemeral
+     * receivers, so that funds can be attributed to the address that received them and shielded selectively.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun getTransparentReceiverBalances(accountUuid: ByteArray): List<JniTransparentReceiverBalance>
+
+    /**
+     * Returns the account's balance in each pool as of the given height, counting only mined transactions.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure, including when [height] is above
+     * the height up to which the wallet has scanned every block
+     */
+    @Throws(RuntimeException::class)
+    suspend fun getBalanceAtHeight(
+        accountUuid: ByteArray,
+        height: Long
+    ): JniHistoricalBalance
+
+    /**
+     * Returns the account's balance in each pool over the given range of heights, counting only mined
+     * transactions.
+     *
+     * @param intervalBlocks the number of blocks between samples, or -1 to sample the last scanned block of each
+     * UTC day. When sampling by blocks, the series also includes [toHeight].
+     *
+     * @throws RuntimeException as a common indicator of the operation failure, including when the series would
+     * have more than 10,000 samples, or when [toHeight] is above the height up to which the wallet has scanned every
+     * block
+     */
+    @Throws(RuntimeException::class)
+    suspend fun getBalanceHistory(
+        accountUuid: ByteArray,
+        fromHeight: Long,
+        toHeight: Long,
+        intervalBlocks: Int
+    ): List<JniHistoricalBalance>
+
+    /**
+     * Returns a page of the wallet's transaction history matching [query], most recent first.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun queryTransactionHistory(query: JniTransactionHistoryQuery): JniTransactionHistoryPage
+
+    /**
+     * Returns the notes and UTXOs that the given account could spend in a transaction created now.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun listSpendableInputs(accountUuid: ByteArray): List<JniSpendableInput>
+
+    /**
+     * Proposes a transfer that spends exactly the given [inputs], as returned by [listSpendableInputs].
+     *
+     * @throws RuntimeException as a common indicator of the operation failure, including when the
+     * selected inputs are insufficient to pay for the transfer and its fee
+     */
+    @Throws(RuntimeException::class)
+    suspend fun proposeTransferFromInputs(
+        accountUuid: ByteArray,
+        to: String,
+        value: Long,
+        memo: ByteArray? = null,
+        inputs: List<JniSpendableInput>
+    ): ProposalUnsafe
+
+    /**
+     * Returns the transactions created by the wallet that have not been mined and have not been abandoned.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun listUnminedTransactions(): List<JniUnminedTransaction>
+
+    /**
+     * Returns the raw bytes of the given transaction for resubmission, or null if the wallet does not have them.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun getRawTransaction(txId: ByteArray): ByteArray?
+
+    /**
+     * Marks an expired, unmined transaction as abandoned, so that it is no longer returned by
+     * [listUnminedTransactions].
+     *
+     * @throws RuntimeException as a common indicator of the operation failure, including when the transaction
+     * has been mined, or when the wallet has not yet scanned every block up to its expiry height
+     */
+    @Throws(RuntimeException::class)
+    suspend fun abandonTransaction(txId: ByteArray)
+
     /**
      * @throws RuntimeException as a common indicator of the operation failure
      */
@@ -51,9 +180,119 @@ interface Backend {
         transparentReceiver: String? = null
     ): ProposalUnsafe?
 
+    /**
+     * Proposes a transaction that consolidates up to [maxNotes] of the smallest notes in the given shielded pool
+     * into a single note in that pool. Notes worth no more than the ZIP 317 marginal fee are never selected, as
+     * spending them would lose value. The fee is available up front from [ProposalUnsafe.totalFeeRequired].
+     *
+     * @param poolType the shielded pool, as a [cash.z.ecc.android.sdk.internal.model.ZcashProtocol.poolCode]
+     *
+     * @throws RuntimeException as a common indicator of the operation failure, including when the pool has fewer
+     * than two notes to consolidate
+     */
+    @Throws(RuntimeException::class)
+    suspend fun proposeConsolidation(
+        accountUuid: ByteArray,
+        poolType: Int,
+        maxNotes: Int
+    ): ProposalUnsafe
+
+    /**
+     * Proposes a transaction that splits the account's balance in the given shielded pool into [noteCount] notes
+     * of roughly equal value in that pool, so that the account can make that many spends without waiting for
+     * change to be confirmed. The fee is available up front from [ProposalUnsafe.totalFeeRequired].
+     *
+     * @param poolType the shielded pool, as a [cash.z.ecc.android.sdk.internal.model.ZcashProtocol.poolCode]
+     *
+     * @throws RuntimeException as a common indicator of the operation failure, including when the balance is too
+     * small for each note to be worth more than the ZIP 317 marginal fee
+     */
+    @Throws(RuntimeException::class)
+    suspend fun proposeSplit(
+        accountUuid: ByteArray,
+        poolType: Int,
+        noteCount: Int
+    ): ProposalUnsafe
+
+    /**
+     * Evaluates the given auto-shielding policy against the account's transparent receivers.
+     *
+     * @return a shielding proposal for each group of receivers whose funds are due to be shielded now, or an
+     * empty list if nothing is due.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun proposeDueShielding(
+        accountUuid: ByteArray,
+        policy: JniAutoShieldingPolicy,
+        memo: ByteArray? = null
+    ): List<ProposalUnsafe>
+
+    /**
+     * Replaces any Sapling-to-Orchard migration plan for the account with a new plan that moves its spendable
+     * Sapling balance to Orchard in standard denominations, each at a randomized height. Value left over below the
+     * smallest denomination stays in Sapling.
+     *
+     * @param intervalBlocks the average number of blocks between steps
+     *
+     * @throws RuntimeException as a common indicator of the operation failure, including when the Sapling
+     * balance is too small to migrate, or when a step of the existing plan has a transaction that may still be mined
+     */
+    @Throws(RuntimeException::class)
+    suspend fun planPoolMigration(
+        accountUuid: ByteArray,
+        intervalBlocks: Int
+    ): List<JniMigrationStep>
+
+    /**
+     * @return the steps of the account's Sapling-to-Orchard migration plan, ordered by due height, or an empty
+     * list if no migration has been planned.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun getPoolMigrationPlan(accountUuid: ByteArray): List<JniMigrationStep>
+
+    /**
+     * Proposes the transaction for the next pending step of the account's Sapling-to-Orchard migration plan whose
+     * due height has been reached. Once the transaction has been created, it must be recorded with
+     * [recordPoolMigrationStep]; a step whose transaction expires unmined becomes pending again.
+     *
+     * @return the proposal, or null if no step is due.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun proposeNextPoolMigrationStep(accountUuid: ByteArray): JniMigrationStepProposal?
+
+    /**
+     * Records the transaction created for a step of the account's Sapling-to-Orchard migration plan. Only a pending
+     * step can be recorded: one whose transaction has been mined, or may still be mined, is rejected.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun recordPoolMigrationStep(
+        accountUuid: ByteArray,
+        stepIndex: Int,
+        txId: ByteArray
+    )
+
+    /**
+     * Creates the transactions for the given proposal, and stores them in the wallet.
+     *
+     * @param listener if not null, notified as each Sapling proof is created, and polled for cancellation. Once
+     *        cancelled, the remaining Sapling proofs are skipped and no transactions are stored. The Orchard proof of
+     *        each transaction is neither reported nor skipped.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure, including cancellation
+     */
+    @Throws(RuntimeException::class)
     suspend fun createProposedTransactions(
         proposal: ProposalUnsafe,
-        unifiedSpendingKey: ByteArray
+        unifiedSpendingKey: ByteArray,
+        listener: JniProvingListener? = null
     ): List<ByteArray>
 
     /**
@@ -72,6 +311,27 @@ interface Backend {
         proposal: ProposalUnsafe
     ): ByteArray
 
+    /**
+     * Creates one partially-created (unsigned without proofs) transaction for each step of the given
+     * proposal. Unlike [createPcztFromProposal], this supports multi-step proposals, such as those that
+     * pay a TEX address.
+     *
+     * Each PCZT after the first records the txid of the step before it. The PCZTs must be proven and
+     * signed individually, and then passed together to [extractAndStoreTxsFromPczts].
+     *
+     * Do not call this multiple times in parallel, or you will generate PCZT instances that, if
+     * finalized, would double-spend the same notes.
+     *
+     * @return the partially created transactions in their serialized format, in step order.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun createPcztsFromProposal(
+        accountUuid: ByteArray,
+        proposal: ProposalUnsafe
+    ): List<ByteArray>
+
     /**
      * Redacts information from the given PCZT that is unnecessary for the Signer role.
      *
@@ -82,6 +342,35 @@ interface Backend {
     @Throws(RuntimeException::class)
     suspend fun redactPcztForSigner(pczt: ByteArray): ByteArray
 
+    /**
+     * Describes what the given PCZT does, for confirmation before it is signed. This includes
+     * which inputs still lack signatures or proofs.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun describePczt(pczt: ByteArray): JniPcztSummary
+
+    /**
+     * Encodes the given PCZT as a sequence of `ur:zcash-pczt` parts, for display as an animated QR code to an
+     * air-gapped signer. Use [cash.z.ecc.android.sdk.internal.model.PcztUrDecoder] to decode the parts it
+     * displays in return.
+     *
+     * @param maxFragmentLength the maximum number of bytes of the PCZT in each part
+     * @param partCount the minimum number of parts to return, if the PCZT does not fit in a single part. The
+     *        parts are fountain-coded, so displaying more parts than the PCZT was split into allows the signer to
+     *        recover from missed frames.
+     * @return the parts to display in order, looping back to the first part after the last.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun encodePcztAsUr(
+        pczt: ByteArray,
+        maxFragmentLength: Int,
+        partCount: Int
+    ): List<String>
+
     /**
      * Checks whether the caller needs to have downloaded the Sapling parameters.
      *
@@ -92,30 +381,101 @@ interface Backend {
     @Throws(RuntimeException::class)
     suspend fun pcztRequiresSaplingProofs(pczt: ByteArray): Boolean
 
+    /**
+     * Checks the size and hash of the Sapling parameter files used for proving, without modifying them.
+     *
+     * @return the state of each parameter file.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun verifySaplingParams(): List<JniSaplingParamStatus>
+
+    /**
+     * Signs every transparent, Sapling and Orchard input of the given PCZT that can be spent with the given
+     * unified spending key. This allows PCZTs to be signed in-app, in place of an external signer.
+     *
+     * @return the updated PCZT in its serialized format.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure, including when the PCZT has no
+     * inputs that can be signed with the key
+     */
+    @Throws(RuntimeException::class)
+    suspend fun signPczt(
+        pczt: ByteArray,
+        unifiedSpendingKey: ByteArray
+    ): ByteArray
+
     /**
      * Adds proofs to the given PCZT.
      *
+     * @param listener if not null, notified as each proof is created, with the Orchard proof counted as one proof
+     *        per action, and polled for cancellation
      * @return the updated PCZT in its serialized format.
      *
+     * @throws RuntimeException as a common indicator of the operation failure, including cancellation
+     */
+    @Throws(RuntimeException::class)
+    suspend fun addProofsToPczt(
+        pczt: ByteArray,
+        listener: JniProvingListener? = null
+    ): ByteArray
+
+    /**
+     * Checks that combining the given proven and signed PCZTs does not alter any effect of the transaction
+     * described by [originalPczt], as returned by [createPcztFromProposal]. This protects against an external
+     * signer changing fields that were redacted from the PCZT it was given.
+     *
+     * [extractAndStoreTxFromPczt] performs the same check before storing the transaction.
+     *
+     * @return a description of each altered field, which is empty if the PCZTs can be safely passed to
+     * [extractAndStoreTxFromPczt].
+     *
      * @throws RuntimeException as a common indicator of the operation failure
      */
     @Throws(RuntimeException::class)
-    suspend fun addProofsToPczt(pczt: ByteArray): ByteArray
+    suspend fun verifyPczt(
+        originalPczt: ByteArray,
+        pcztWithProofs: ByteArray,
+        pcztWithSignatures: ByteArray
+    ): List<String>
 
     /**
      * Takes a PCZT that has been separately proven and signed, finalizes it, and stores
      * it in the wallet.
      *
+     * @param originalPczt the PCZT returned by [createPcztFromProposal]
      * @return the txid of the completed transaction.
      *
-     * @throws RuntimeException as a common indicator of the operation failure
+     * @throws RuntimeException as a common indicator of the operation failure, including when combining
+     * the proven and signed PCZTs alters any effect of the transaction described by [originalPczt]
      */
     @Throws(RuntimeException::class)
     suspend fun extractAndStoreTxFromPczt(
+        originalPczt: ByteArray,
         pcztWithProofs: ByteArray,
         pcztWithSignatures: ByteArray,
     ): ByteArray
 
+    /**
+     * Takes the chain of PCZTs returned by [createPcztsFromProposal], each of which has been separately
+     * proven and signed, finalizes them, and stores them in the wallet. Every PCZT is finalized and
+     * verified before any transaction is stored.
+     *
+     * @param originalPczts the PCZTs returned by [createPcztsFromProposal], in step order
+     * @return the txids of the completed transactions, in step order.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure, including when combining
+     * the proven and signed PCZTs of any step alters an effect of the transaction described by its
+     * original PCZT
+     */
+    @Throws(RuntimeException::class)
+    suspend fun extractAndStoreTxsFromPczts(
+        originalPczts: List<ByteArray>,
+        pcztsWithProofs: List<ByteArray>,
+        pcztsWithSignatures: List<ByteArray>,
+    ): List<ByteArray>
+
     /**
      * @throws RuntimeException as a common indicator of the operation failure
      */
@@ -139,6 +499,37 @@ interface Backend {
     @Throws(RuntimeException::class)
     suspend fun initDataDb(seed: ByteArray?): Int
 
+    /**
+     * Writes a consistent snapshot of the data database to [backupFile], which may be taken while the SDK is
+     * running.
+     *
+     * @param compress whether to compress the backup
+     * @param seed if not null, the backup is encrypted with a key derived from this seed's metadata key
+     * @return the size of the backup in bytes.
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun backupDataDb(
+        backupFile: File,
+        compress: Boolean,
+        seed: ByteArray?
+    ): Long
+
+    /**
+     * Replaces the data database with the backup in [backupFile], after checking that the backup is intact, belongs
+     * to this network (and to [seed], if it is not null), and can be migrated to the current schema. No other
+     * operation may access the database while it is restored.
+     *
+     * @param seed the wallet's seed, which is required to restore an encrypted backup
+     * @throws RuntimeException as a common indicator of the operation failure, including when the backup is not a
+     * SQLite database
+     */
+    @Throws(RuntimeException::class)
+    suspend fun restoreDataDb(
+        backupFile: File,
+        seed: ByteArray?
+    )
+
     /**
      * @throws RuntimeException as a common indicator of the operation failure
      */
@@ -227,12 +618,56 @@ interface Backend {
         outputIndex: Int
     ): String?
 
+    /**
+     * Returns the raw 512-byte memo of the given output, of any kind, or null if the output has no memo.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun getMemoBytes(
+        txId: ByteArray,
+        protocol: Int,
+        outputIndex: Int
+    ): ByteArray?
+
+    /**
+     * Decodes a memo of up to 512 bytes according to ZIP 302, recognising any trailing reply-to address.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    fun decodeMemo(memo: ByteArray): JniDecodedMemo
+
+    /**
+     * Encodes up to 511 bytes of [data] as a ZIP 302 arbitrary-data memo, returning the 512-byte memo.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    fun encodeArbitraryMemo(data: ByteArray): ByteArray
+
+    /**
+     * Returns every output of the given transaction that was sent or received by the wallet, with its raw memo.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun getTransactionDetail(txId: ByteArray): List<JniTransactionOutput>
+
     /**
      * @throws RuntimeException as a common indicator of the operation failure
      */
     @Throws(RuntimeException::class)
     suspend fun rewindToHeight(height: Long): JniRewindResult
 
+    /**
+     * Reports the effect [rewindToHeight] would have for the given height, without modifying the wallet.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun previewRewindToHeight(height: Long): JniRewindPreview
+
     /**
      * @throws RuntimeException as a common indicator of the operation failure
      */
@@ -297,6 +732,20 @@ interface Backend {
         limit: Long
     ): JniScanSummary
 
+    /**
+     * Scans the given serialized `CompactBlock`s, starting at [fromHeight], without writing them to the block cache.
+     * The blocks may be supplied in any order.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun scanBlocksFromMemory(
+        blocks: List<ByteArray>,
+        fromHeight: Long,
+        fromState: ByteArray,
+        limit: Long
+    ): JniScanSummary
+
     /**
      * @throws RuntimeException as a common indicator of the operation failure
      */
@@ -334,6 +783,25 @@ interface Backend {
 
     suspend fun rewindBlockMetadataToHeight(height: Long)
 
+    /**
+     * Removes the cached compact blocks below the wallet's fully-scanned height, which are no longer needed for
+     * scanning.
+     *
+     * @return the number of blocks removed.
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun pruneBlockCache(): Long
+
+    /**
+     * Checks that every block in the compact block cache has a readable file holding the block its metadata
+     * describes, and reports gaps in the cached heights and the size of the cache.
+     *
+     * @throws RuntimeException as a common indicator of the operation failure
+     */
+    @Throws(RuntimeException::class)
+    suspend fun verifyBlockCache(): JniBlockCacheReport
+
     suspend fun getTotalTransparentBalance(address: String): Long
 
     /**
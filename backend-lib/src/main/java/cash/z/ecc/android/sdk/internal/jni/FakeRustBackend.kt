@@ -3,12 +3,30 @@ package cash.z.ecc.android.sdk.internal.jni
 import cash.z.ecc.android.sdk.internal.Backend
 import cash.z.ecc.android.sdk.internal.model.JniAccount
 import cash.z.ecc.android.sdk.internal.model.JniAccountUsk
+import cash.z.ecc.android.sdk.internal.model.JniAutoShieldingPolicy
+import cash.z.ecc.android.sdk.internal.model.JniBlockCacheReport
 import cash.z.ecc.android.sdk.internal.model.JniBlockMeta
+import cash.z.ecc.android.sdk.internal.model.JniDecodedMemo
+import cash.z.ecc.android.sdk.internal.model.JniHistoricalBalance
+import cash.z.ecc.android.sdk.internal.model.JniMigrationStep
+import cash.z.ecc.android.sdk.internal.model.JniMigrationStepProposal
+import cash.z.ecc.android.sdk.internal.model.JniPcztSummary
+import cash.z.ecc.android.sdk.internal.model.JniProvingListener
+import cash.z.ecc.android.sdk.internal.model.JniReceivedOutput
+import cash.z.ecc.android.sdk.internal.model.JniRewindPreview
 import cash.z.ecc.android.sdk.internal.model.JniRewindResult
+import cash.z.ecc.android.sdk.internal.model.JniSaplingParamStatus
 import cash.z.ecc.android.sdk.internal.model.JniScanRange
+import cash.z.ecc.android.sdk.internal.model.JniScanSummary
 import cash.z.ecc.android.sdk.internal.model.JniSingleUseTransparentAddress
+import cash.z.ecc.android.sdk.internal.model.JniSpendableInput
 import cash.z.ecc.android.sdk.internal.model.JniSubtreeRoot
 import cash.z.ecc.android.sdk.internal.model.JniTransactionDataRequest
+import cash.z.ecc.android.sdk.internal.model.JniTransactionHistoryPage
+import cash.z.ecc.android.sdk.internal.model.JniTransactionHistoryQuery
+import cash.z.ecc.android.sdk.internal.model.JniTransactionOutput
+import cash.z.ecc.android.sdk.internal.model.JniTransparentReceiverBalance
+import cash.z.ecc.android.sdk.internal.model.JniUnminedTransaction
 import cash.z.ecc.android.sdk.internal.model.JniWalletSummary
 import cash.z.ecc.android.sdk.internal.model.ProposalUnsafe
 import java.io.File
@@ -30,6 +48,10 @@ class FakeRustBackend(
         return JniRewindResult.Success(height)
     }
 
+    override suspend fun previewRewindToHeight(height: Long): JniRewindPreview {
+        error("Intentionally not implemented yet.")
+    }
+
     override suspend fun putSubtreeRoots(
         saplingStartIndex: Long,
         saplingRoots: List<JniSubtreeRoot>,
@@ -88,6 +110,14 @@ class FakeRustBackend(
         metadata.removeAll { it.height > height }
     }
 
+    override suspend fun pruneBlockCache(): Long {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun verifyBlockCache(): JniBlockCacheReport {
+        error("Intentionally not implemented yet.")
+    }
+
     override suspend fun initBlockMetaDb(): Int {
         error("Intentionally not implemented yet.")
     }
@@ -99,6 +129,18 @@ class FakeRustBackend(
         error("Intentionally not implemented yet.")
     }
 
+    override suspend fun listUnminedTransactions(): List<JniUnminedTransaction> {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun getRawTransaction(txId: ByteArray): ByteArray? {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun abandonTransaction(txId: ByteArray) {
+        error("Intentionally not implemented yet.")
+    }
+
     override suspend fun proposeTransfer(
         accountUuid: ByteArray,
         to: String,
@@ -108,6 +150,52 @@ class FakeRustBackend(
         error("Intentionally not implemented yet.")
     }
 
+    override suspend fun listReceivedOutputs(
+        accountUuid: ByteArray,
+        offset: Long,
+        limit: Long
+    ): List<JniReceivedOutput> {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun getTransparentReceiverBalances(accountUuid: ByteArray): List<JniTransparentReceiverBalance> {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun getBalanceAtHeight(
+        accountUuid: ByteArray,
+        height: Long
+    ): JniHistoricalBalance {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun getBalanceHistory(
+        accountUuid: ByteArray,
+        fromHeight: Long,
+        toHeight: Long,
+        intervalBlocks: Int
+    ): List<JniHistoricalBalance> {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun queryTransactionHistory(query: JniTransactionHistoryQuery): JniTransactionHistoryPage {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun listSpendableInputs(accountUuid: ByteArray): List<JniSpendableInput> {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun proposeTransferFromInputs(
+        accountUuid: ByteArray,
+        to: String,
+        value: Long,
+        memo: ByteArray?,
+        inputs: List<JniSpendableInput>
+    ): ProposalUnsafe {
+        error("Intentionally not implemented yet.")
+    }
+
     override suspend fun proposeShielding(
         accountUuid: ByteArray,
         shieldingThreshold: Long,
@@ -117,9 +205,57 @@ class FakeRustBackend(
         error("Intentionally not implemented yet.")
     }
 
+    override suspend fun proposeConsolidation(
+        accountUuid: ByteArray,
+        poolType: Int,
+        maxNotes: Int
+    ): ProposalUnsafe {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun proposeSplit(
+        accountUuid: ByteArray,
+        poolType: Int,
+        noteCount: Int
+    ): ProposalUnsafe {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun proposeDueShielding(
+        accountUuid: ByteArray,
+        policy: JniAutoShieldingPolicy,
+        memo: ByteArray?
+    ): List<ProposalUnsafe> {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun planPoolMigration(
+        accountUuid: ByteArray,
+        intervalBlocks: Int
+    ): List<JniMigrationStep> {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun getPoolMigrationPlan(accountUuid: ByteArray): List<JniMigrationStep> {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun proposeNextPoolMigrationStep(accountUuid: ByteArray): JniMigrationStepProposal? {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun recordPoolMigrationStep(
+        accountUuid: ByteArray,
+        stepIndex: Int,
+        txId: ByteArray
+    ) {
+        error("Intentionally not implemented yet.")
+    }
+
     override suspend fun createProposedTransactions(
         proposal: ProposalUnsafe,
-        unifiedSpendingKey: ByteArray
+        unifiedSpendingKey: ByteArray,
+        listener: JniProvingListener?
     ): List<ByteArray> {
         error("Intentionally not implemented yet.")
     }
@@ -131,25 +267,75 @@ class FakeRustBackend(
         error("Intentionally not implemented yet.")
     }
 
+    override suspend fun createPcztsFromProposal(
+        accountUuid: ByteArray,
+        proposal: ProposalUnsafe
+    ): List<ByteArray> {
+        error("Intentionally not implemented yet.")
+    }
+
     override suspend fun redactPcztForSigner(pczt: ByteArray): ByteArray {
         error("Intentionally not implemented yet.")
     }
 
+    override suspend fun describePczt(pczt: ByteArray): JniPcztSummary {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun encodePcztAsUr(
+        pczt: ByteArray,
+        maxFragmentLength: Int,
+        partCount: Int
+    ): List<String> {
+        error("Intentionally not implemented yet.")
+    }
+
     override suspend fun pcztRequiresSaplingProofs(pczt: ByteArray): Boolean {
         error("Intentionally not implemented yet.")
     }
 
-    override suspend fun addProofsToPczt(pczt: ByteArray): ByteArray {
+    override suspend fun verifySaplingParams(): List<JniSaplingParamStatus> {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun signPczt(
+        pczt: ByteArray,
+        unifiedSpendingKey: ByteArray
+    ): ByteArray {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun addProofsToPczt(
+        pczt: ByteArray,
+        listener: JniProvingListener?
+    ): ByteArray {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun verifyPczt(
+        originalPczt: ByteArray,
+        pcztWithProofs: ByteArray,
+        pcztWithSignatures: ByteArray
+    ): List<String> {
         error("Intentionally not implemented yet.")
     }
 
     override suspend fun extractAndStoreTxFromPczt(
+        originalPczt: ByteArray,
         pcztWithProofs: ByteArray,
         pcztWithSignatures: ByteArray
     ): ByteArray {
         error("Intentionally not implemented yet.")
     }
 
+    override suspend fun extractAndStoreTxsFromPczts(
+        originalPczts: List<ByteArray>,
+        pcztsWithProofs: List<ByteArray>,
+        pcztsWithSignatures: List<ByteArray>
+    ): List<ByteArray> {
+        error("Intentionally not implemented yet.")
+    }
+
     override suspend fun decryptAndStoreTransaction(tx: ByteArray, minedHeight: Long?): ByteArray {
         error("Intentionally not implemented yet.")
     }
@@ -158,6 +344,21 @@ class FakeRustBackend(
         error("Intentionally not implemented yet.")
     }
 
+    override suspend fun backupDataDb(
+        backupFile: File,
+        compress: Boolean,
+        seed: ByteArray?
+    ): Long {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun restoreDataDb(
+        backupFile: File,
+        seed: ByteArray?
+    ) {
+        error("Intentionally not implemented yet.")
+    }
+
     override suspend fun getAccounts(): List<JniAccount> {
         error("Intentionally not implemented yet.")
     }
@@ -244,12 +445,41 @@ class FakeRustBackend(
         error("Intentionally not implemented yet.")
     }
 
+    override suspend fun getMemoBytes(
+        txId: ByteArray,
+        protocol: Int,
+        outputIndex: Int
+    ): ByteArray? {
+        error("Intentionally not implemented yet.")
+    }
+
+    override fun decodeMemo(memo: ByteArray): JniDecodedMemo {
+        error("Intentionally not implemented yet.")
+    }
+
+    override fun encodeArbitraryMemo(data: ByteArray): ByteArray {
+        error("Intentionally not implemented yet.")
+    }
+
+    override suspend fun getTransactionDetail(txId: ByteArray): List<JniTransactionOutput> {
+        error("Intentionally not implemented yet.")
+    }
+
     override suspend fun scanBlocks(
         fromHeight: Long,
         fromState: ByteArray,
         limit: Long
     ) = error("Intentionally not implemented in mocked FakeRustBackend implementation.")
 
+    override suspend fun scanBlocksFromMemory(
+        blocks: List<ByteArray>,
+        fromHeight: Long,
+        fromState: ByteArray,
+        limit: Long
+    ): JniScanSummary {
+        error("Intentionally not implemented yet.")
+    }
+
     override suspend fun transactionDataRequests(): List<JniTransactionDataRequest> {
         error("Intentionally not implemented yet.")
     }
@@ -22,3 +22,13 @@ const val JNI_METADATA_KEY_SK_SIZE = 32
  * The number of bytes in a chain code. It's used e.g. in [JniMetadataKey.chainCode]
  */
 const val JNI_METADATA_KEY_CHAIN_CODE_SIZE = 32
+
+/**
+ * The number of bytes in a transaction ID. It's used e.g. in [JniSpendableInput.txid]
+ */
+const val JNI_TXID_BYTES_SIZE = 32
+
+/**
+ * The number of bytes in a memo. It's used e.g. in [JniTransactionOutput.memo]
+ */
+const val JNI_MEMO_BYTES_SIZE = 512
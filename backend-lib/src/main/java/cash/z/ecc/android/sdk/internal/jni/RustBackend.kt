@@ -6,17 +6,35 @@ import cash.z.ecc.android.sdk.internal.ext.deleteRecursivelySuspend
 import cash.z.ecc.android.sdk.internal.ext.deleteSuspend
 import cash.z.ecc.android.sdk.internal.model.JniAccount
 import cash.z.ecc.android.sdk.internal.model.JniAccountUsk
+import cash.z.ecc.android.sdk.internal.model.JniAutoShieldingPolicy
+import cash.z.ecc.android.sdk.internal.model.JniBlockCacheReport
 import cash.z.ecc.android.sdk.internal.model.JniBlockMeta
+import cash.z.ecc.android.sdk.internal.model.JniDecodedMemo
+import cash.z.ecc.android.sdk.internal.model.JniHistoricalBalance
+import cash.z.ecc.android.sdk.internal.model.JniMigrationStep
+import cash.z.ecc.android.sdk.internal.model.JniMigrationStepProposal
+import cash.z.ecc.android.sdk.internal.model.JniPcztSummary
+import cash.z.ecc.android.sdk.internal.model.JniProvingListener
+import cash.z.ecc.android.sdk.internal.model.JniReceivedOutput
+import cash.z.ecc.android.sdk.internal.model.JniRewindPreview
 import cash.z.ecc.android.sdk.internal.model.JniRewindResult
+import cash.z.ecc.android.sdk.internal.model.JniSaplingParamStatus
 import cash.z.ecc.android.sdk.internal.model.JniScanRange
 import cash.z.ecc.android.sdk.internal.model.JniScanSummary
 import cash.z.ecc.android.sdk.internal.model.JniSingleUseTransparentAddress
+import cash.z.ecc.android.sdk.internal.model.JniSpendableInput
 import cash.z.ecc.android.sdk.internal.model.JniSubtreeRoot
 import cash.z.ecc.android.sdk.internal.model.JniTransactionDataRequest
+import cash.z.ecc.android.sdk.internal.model.JniTransactionHistoryPage
+import cash.z.ecc.android.sdk.internal.model.JniTransactionHistoryQuery
+import cash.z.ecc.android.sdk.internal.model.JniTransactionOutput
+import cash.z.ecc.android.sdk.internal.model.JniTransparentReceiverBalance
+import cash.z.ecc.android.sdk.internal.model.JniUnminedTransaction
 import cash.z.ecc.android.sdk.internal.model.JniWalletSummary
 import cash.z.ecc.android.sdk.internal.model.ProposalUnsafe
 import cash.z.ecc.android.sdk.internal.model.RustLogging
 import cash.z.ecc.android.sdk.internal.model.isNotLoggingInProduction
+import kotlinx.coroutines.Dispatchers
 import kotlinx.coroutines.withContext
 import java.io.File
 
@@ -81,6 +99,33 @@ class RustBackend private constructor(
             )
         }
 
+    override suspend fun backupDataDb(
+        backupFile: File,
+        compress: Boolean,
+        seed: ByteArray?
+    ): Long =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            backupDataDb(
+                dataDbFile.absolutePath,
+                backupFile.absolutePath,
+                compress,
+                seed,
+                networkId = networkId
+            )
+        }
+
+    override suspend fun restoreDataDb(
+        backupFile: File,
+        seed: ByteArray?
+    ) = withContext(SdkDispatchers.DATABASE_IO) {
+        restoreDataDb(
+            dataDbFile.absolutePath,
+            backupFile.absolutePath,
+            seed,
+            networkId = networkId
+        )
+    }
+
     override suspend fun getAccounts(): List<JniAccount> =
         withContext(SdkDispatchers.DATABASE_IO) {
             getAccounts(
@@ -208,6 +253,31 @@ class RustBackend private constructor(
         )
     }
 
+    override suspend fun getMemoBytes(
+        txId: ByteArray,
+        protocol: Int,
+        outputIndex: Int
+    ) = withContext(SdkDispatchers.DATABASE_IO) {
+        getMemoBytes(
+            dataDbFile.absolutePath,
+            txId,
+            protocol,
+            outputIndex
+        )
+    }
+
+    override fun decodeMemo(memo: ByteArray): JniDecodedMemo = decodeMemo(memo, networkId = networkId)
+
+    override fun encodeArbitraryMemo(data: ByteArray): ByteArray = encodeArbitraryMemoBytes(data)
+
+    override suspend fun getTransactionDetail(txId: ByteArray): List<JniTransactionOutput> =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            getTransactionDetail(
+                dataDbFile.absolutePath,
+                txId
+            ).asList()
+        }
+
     override suspend fun writeBlockMetadata(blockMetadata: List<JniBlockMeta>) =
         withContext(SdkDispatchers.DATABASE_IO) {
             writeBlockMetadata(
@@ -243,6 +313,20 @@ class RustBackend private constructor(
             )
         }
 
+    override suspend fun pruneBlockCache(): Long =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            pruneBlockCache(
+                fsBlockDbRoot.absolutePath,
+                dataDbFile.absolutePath,
+                networkId = networkId
+            )
+        }
+
+    override suspend fun verifyBlockCache(): JniBlockCacheReport =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            verifyBlockCache(fsBlockDbRoot.absolutePath)
+        }
+
     override suspend fun getTotalTransparentBalance(address: String): Long =
         withContext(SdkDispatchers.DATABASE_IO) {
             getTotalTransparentBalance(
@@ -264,6 +348,14 @@ class RustBackend private constructor(
             )
         }
 
+    override suspend fun previewRewindToHeight(height: Long): JniRewindPreview =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            previewRewindToHeight(
+                dataDbFile.absolutePath,
+                height
+            )
+        }
+
     override suspend fun putSubtreeRoots(
         saplingStartIndex: Long,
         saplingRoots: List<JniSubtreeRoot>,
@@ -351,6 +443,23 @@ class RustBackend private constructor(
             )
         }
 
+    override suspend fun scanBlocksFromMemory(
+        blocks: List<ByteArray>,
+        fromHeight: Long,
+        fromState: ByteArray,
+        limit: Long
+    ): JniScanSummary =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            scanBlocksFromMemory(
+                dataDbFile.absolutePath,
+                blocks.toTypedArray(),
+                fromHeight,
+                fromState,
+                limit,
+                networkId = networkId
+            )
+        }
+
     override suspend fun transactionDataRequests(): List<JniTransactionDataRequest> =
         withContext(SdkDispatchers.DATABASE_IO) {
             transactionDataRequests(
@@ -380,6 +489,31 @@ class RustBackend private constructor(
             )
         }
 
+    override suspend fun listUnminedTransactions(): List<JniUnminedTransaction> =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            listUnminedTransactions(
+                dataDbFile.absolutePath,
+                networkId = networkId
+            ).asList()
+        }
+
+    override suspend fun getRawTransaction(txId: ByteArray): ByteArray? =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            getRawTransaction(
+                dataDbFile.absolutePath,
+                txId
+            )
+        }
+
+    override suspend fun abandonTransaction(txId: ByteArray) =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            abandonTransaction(
+                dataDbFile.absolutePath,
+                txId,
+                networkId = networkId
+            )
+        }
+
     override suspend fun proposeTransferFromUri(
         accountUuid: ByteArray,
         uri: String
@@ -414,6 +548,98 @@ class RustBackend private constructor(
             )
         }
 
+    override suspend fun listReceivedOutputs(
+        accountUuid: ByteArray,
+        offset: Long,
+        limit: Long
+    ): List<JniReceivedOutput> =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            listReceivedOutputs(
+                dataDbFile.absolutePath,
+                accountUuid,
+                offset,
+                limit,
+                networkId = networkId
+            ).asList()
+        }
+
+    override suspend fun getTransparentReceiverBalances(accountUuid: ByteArray): List<JniTransparentReceiverBalance> =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            getTransparentReceiverBalances(
+                dataDbFile.absolutePath,
+                accountUuid,
+                networkId = networkId
+            ).asList()
+        }
+
+    override suspend fun getBalanceAtHeight(
+        accountUuid: ByteArray,
+        height: Long
+    ): JniHistoricalBalance =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            getBalanceAtHeight(
+                dataDbFile.absolutePath,
+                accountUuid,
+                height,
+                networkId = networkId
+            )
+        }
+
+    override suspend fun getBalanceHistory(
+        accountUuid: ByteArray,
+        fromHeight: Long,
+        toHeight: Long,
+        intervalBlocks: Int
+    ): List<JniHistoricalBalance> =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            getBalanceHistory(
+                dataDbFile.absolutePath,
+                accountUuid,
+                fromHeight,
+                toHeight,
+                intervalBlocks,
+                networkId = networkId
+            ).asList()
+        }
+
+    override suspend fun queryTransactionHistory(query: JniTransactionHistoryQuery): JniTransactionHistoryPage =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            queryTransactionHistory(
+                dataDbFile.absolutePath,
+                query
+            )
+        }
+
+    override suspend fun listSpendableInputs(accountUuid: ByteArray): List<JniSpendableInput> =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            listSpendableInputs(
+                dataDbFile.absolutePath,
+                accountUuid,
+                networkId = networkId
+            ).asList()
+        }
+
+    override suspend fun proposeTransferFromInputs(
+        accountUuid: ByteArray,
+        to: String,
+        value: Long,
+        memo: ByteArray?,
+        inputs: List<JniSpendableInput>
+    ): ProposalUnsafe =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            ProposalUnsafe.parse(
+                proposeTransferFromInputs(
+                    dataDbFile.absolutePath,
+                    accountUuid,
+                    to,
+                    value,
+                    memo,
+                    inputs.toTypedArray(),
+                    networkId = networkId,
+                )
+            )
+        }
+
     override suspend fun proposeShielding(
         accountUuid: ByteArray,
         shieldingThreshold: Long,
@@ -435,9 +661,104 @@ class RustBackend private constructor(
             }
         }
 
+    override suspend fun proposeConsolidation(
+        accountUuid: ByteArray,
+        poolType: Int,
+        maxNotes: Int
+    ): ProposalUnsafe =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            ProposalUnsafe.parse(
+                proposeConsolidation(
+                    dataDbFile.absolutePath,
+                    accountUuid,
+                    poolType,
+                    maxNotes,
+                    networkId = networkId
+                )
+            )
+        }
+
+    override suspend fun proposeSplit(
+        accountUuid: ByteArray,
+        poolType: Int,
+        noteCount: Int
+    ): ProposalUnsafe =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            ProposalUnsafe.parse(
+                proposeSplit(
+                    dataDbFile.absolutePath,
+                    accountUuid,
+                    poolType,
+                    noteCount,
+                    networkId = networkId
+                )
+            )
+        }
+
+    override suspend fun proposeDueShielding(
+        accountUuid: ByteArray,
+        policy: JniAutoShieldingPolicy,
+        memo: ByteArray?
+    ): List<ProposalUnsafe> =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            proposeDueShielding(
+                dataDbFile.absolutePath,
+                accountUuid,
+                policy,
+                memo,
+                networkId = networkId,
+            ).map { ProposalUnsafe.parse(it) }
+        }
+
+    override suspend fun planPoolMigration(
+        accountUuid: ByteArray,
+        intervalBlocks: Int
+    ): List<JniMigrationStep> =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            planPoolMigration(
+                dataDbFile.absolutePath,
+                accountUuid,
+                intervalBlocks,
+                networkId = networkId
+            ).asList()
+        }
+
+    override suspend fun getPoolMigrationPlan(accountUuid: ByteArray): List<JniMigrationStep> =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            getPoolMigrationPlan(
+                dataDbFile.absolutePath,
+                accountUuid,
+                networkId = networkId
+            ).asList()
+        }
+
+    override suspend fun proposeNextPoolMigrationStep(accountUuid: ByteArray): JniMigrationStepProposal? =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            proposeNextPoolMigrationStep(
+                dataDbFile.absolutePath,
+                accountUuid,
+                networkId = networkId
+            )
+        }
+
+    override suspend fun recordPoolMigrationStep(
+        accountUuid: ByteArray,
+        stepIndex: Int,
+        txId: ByteArray
+    ) = withContext(SdkDispatchers.DATABASE_IO) {
+        recordPoolMigrationStep(
+            dataDbFile.absolutePath,
+            accountUuid,
+            stepIndex,
+            txId,
+            networkId = networkId
+        )
+    }
+
     override suspend fun createProposedTransactions(
         proposal: ProposalUnsafe,
-        unifiedSpendingKey: ByteArray
+        unifiedSpendingKey: ByteArray,
+        listener: JniProvingListener?
     ): List<ByteArray> =
         withContext(SdkDispatchers.DATABASE_IO) {
             createProposedTransactions(
@@ -446,7 +767,8 @@ class RustBackend private constructor(
                 unifiedSpendingKey,
                 spendParamsPath = saplingSpendFile.absolutePath,
                 outputParamsPath = saplingOutputFile.absolutePath,
-                networkId = networkId
+                networkId = networkId,
+                listener = listener
             ).asList()
         }
 
@@ -463,24 +785,81 @@ class RustBackend private constructor(
             )
         }
 
+    override suspend fun createPcztsFromProposal(
+        accountUuid: ByteArray,
+        proposal: ProposalUnsafe
+    ): List<ByteArray> =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            createPcztsFromProposal(
+                dbDataPath = dataDbFile.absolutePath,
+                accountUuid = accountUuid,
+                proposal = proposal.toByteArray(),
+                networkId = networkId
+            ).asList()
+        }
+
     override suspend fun redactPcztForSigner(pczt: ByteArray): ByteArray = redactPcztForSignerRole(pczt = pczt)
 
+    override suspend fun describePczt(pczt: ByteArray): JniPcztSummary =
+        describePczt(pczt = pczt, networkId = networkId)
+
+    override suspend fun encodePcztAsUr(
+        pczt: ByteArray,
+        maxFragmentLength: Int,
+        partCount: Int
+    ): List<String> =
+        encodePcztAsUr(
+            pczt = pczt,
+            maxFragmentLength = maxFragmentLength,
+            partCount = partCount
+        ).asList()
+
     override suspend fun pcztRequiresSaplingProofs(pczt: ByteArray): Boolean = requiresSaplingProofs(pczt = pczt)
 
-    override suspend fun addProofsToPczt(pczt: ByteArray): ByteArray =
+    override suspend fun verifySaplingParams(): List<JniSaplingParamStatus> =
+        withContext(Dispatchers.IO) {
+            verifySaplingParams(
+                spendParamsPath = saplingSpendFile.absolutePath,
+                outputParamsPath = saplingOutputFile.absolutePath
+            ).asList()
+        }
+
+    override suspend fun signPczt(
+        pczt: ByteArray,
+        unifiedSpendingKey: ByteArray
+    ): ByteArray = signPczt(pczt = pczt, usk = unifiedSpendingKey)
+
+    override suspend fun addProofsToPczt(
+        pczt: ByteArray,
+        listener: JniProvingListener?
+    ): ByteArray =
         addProofsToPczt(
             pczt = pczt,
             spendParamsPath = saplingSpendFile.absolutePath,
-            outputParamsPath = saplingOutputFile.absolutePath
+            outputParamsPath = saplingOutputFile.absolutePath,
+            listener = listener
         )
 
+    override suspend fun verifyPczt(
+        originalPczt: ByteArray,
+        pcztWithProofs: ByteArray,
+        pcztWithSignatures: ByteArray
+    ): List<String> =
+        verifyPczt(
+            originalPczt = originalPczt,
+            pcztWithProofs = pcztWithProofs,
+            pcztWithSignatures = pcztWithSignatures
+        ).asList()
+
     override suspend fun extractAndStoreTxFromPczt(
+        originalPczt: ByteArray,
         pcztWithProofs: ByteArray,
         pcztWithSignatures: ByteArray
     ): ByteArray =
         withContext(SdkDispatchers.DATABASE_IO) {
             extractAndStoreTxFromPczt(
                 dbDataPath = dataDbFile.absolutePath,
+                originalPczt = originalPczt,
                 pcztWithProofs = pcztWithProofs,
                 pcztWithSignatures = pcztWithSignatures,
                 spendParamsPath = saplingSpendFile.absolutePath,
@@ -489,6 +868,23 @@ class RustBackend private constructor(
             )
         }
 
+    override suspend fun extractAndStoreTxsFromPczts(
+        originalPczts: List<ByteArray>,
+        pcztsWithProofs: List<ByteArray>,
+        pcztsWithSignatures: List<ByteArray>
+    ): List<ByteArray> =
+        withContext(SdkDispatchers.DATABASE_IO) {
+            extractAndStoreTxsFromPczts(
+                dbDataPath = dataDbFile.absolutePath,
+                originalPczts = originalPczts.toTypedArray(),
+                pcztsWithProofs = pcztsWithProofs.toTypedArray(),
+                pcztsWithSignatures = pcztsWithSignatures.toTypedArray(),
+                spendParamsPath = saplingSpendFile.absolutePath,
+                outputParamsPath = saplingOutputFile.absolutePath,
+                networkId = networkId
+            ).asList()
+        }
+
     override suspend fun putUtxo(
         txId: ByteArray,
         index: Int,
@@ -537,13 +933,20 @@ class RustBackend private constructor(
 
         private val rustLogging: RustLogging = RustLogging.Off
 
-        suspend fun loadLibrary() {
+        /**
+         * Loads the library, if it has not already been loaded.
+         *
+         * @param warmProvingKeys whether to build the Orchard proving and verifying keys in the background once
+         *        the library is loaded, so that the first transaction isn't delayed by building them. This has no
+         *        effect if the library has already been loaded.
+         */
+        suspend fun loadLibrary(warmProvingKeys: Boolean = false) {
             rustLibraryLoader.load {
                 require(rustLogging.isNotLoggingInProduction()) {
                     "Rust layer logging must be turned off in production build"
                 }
 
-                initOnLoad(rustLogging.identifier)
+                initOnLoad(rustLogging.identifier, warmProvingKeys)
             }
         }
 
@@ -551,14 +954,16 @@ class RustBackend private constructor(
          * Loads the library and initializes path variables. Although it is best to only call this
          * function once, it is idempotent.
          */
+        @Suppress("LongParameterList")
         suspend fun new(
             fsBlockDbRoot: File,
             dataDbFile: File,
             saplingSpendFile: File,
             saplingOutputFile: File,
             zcashNetworkId: Int,
+            warmProvingKeys: Boolean = false,
         ): RustBackend {
-            loadLibrary()
+            loadLibrary(warmProvingKeys)
 
             return RustBackend(
                 zcashNetworkId,
@@ -574,7 +979,10 @@ class RustBackend private constructor(
         //
 
         @JvmStatic
-        private external fun initOnLoad(logLevel: String)
+        private external fun initOnLoad(
+            logLevel: String,
+            warmProvingKeys: Boolean
+        )
 
         @JvmStatic
         private external fun initBlockMetaDb(fsBlockDbRoot: String): Int
@@ -586,6 +994,24 @@ class RustBackend private constructor(
             networkId: Int
         ): Int
 
+        @JvmStatic
+        @Suppress("LongParameterList")
+        private external fun backupDataDb(
+            dbDataPath: String,
+            backupPath: String,
+            compress: Boolean,
+            seed: ByteArray?,
+            networkId: Int
+        ): Long
+
+        @JvmStatic
+        private external fun restoreDataDb(
+            dbDataPath: String,
+            backupPath: String,
+            seed: ByteArray?,
+            networkId: Int
+        )
+
         @JvmStatic
         private external fun getAccounts(
             dbDataPath: String,
@@ -713,6 +1139,29 @@ class RustBackend private constructor(
             networkId: Int
         ): String?
 
+        @JvmStatic
+        private external fun getMemoBytes(
+            dbDataPath: String,
+            txId: ByteArray,
+            poolType: Int,
+            outputIndex: Int
+        ): ByteArray?
+
+        @JvmStatic
+        private external fun decodeMemo(
+            memo: ByteArray,
+            networkId: Int
+        ): JniDecodedMemo
+
+        @JvmStatic
+        private external fun encodeArbitraryMemoBytes(data: ByteArray): ByteArray
+
+        @JvmStatic
+        private external fun getTransactionDetail(
+            dbDataPath: String,
+            txId: ByteArray
+        ): Array<JniTransactionOutput>
+
         @JvmStatic
         private external fun writeBlockMetadata(
             dbCachePath: String,
@@ -734,6 +1183,16 @@ class RustBackend private constructor(
             height: Long
         )
 
+        @JvmStatic
+        private external fun pruneBlockCache(
+            dbCachePath: String,
+            dbDataPath: String,
+            networkId: Int
+        ): Long
+
+        @JvmStatic
+        private external fun verifyBlockCache(dbCachePath: String): JniBlockCacheReport
+
         @JvmStatic
         private external fun rewindToHeight(
             dbDataPath: String,
@@ -741,6 +1200,12 @@ class RustBackend private constructor(
             networkId: Int
         ): JniRewindResult
 
+        @JvmStatic
+        private external fun previewRewindToHeight(
+            dbDataPath: String,
+            height: Long
+        ): JniRewindPreview
+
         @JvmStatic
         @Suppress("LongParameterList")
         private external fun putSubtreeRoots(
@@ -794,6 +1259,17 @@ class RustBackend private constructor(
             networkId: Int
         ): JniScanSummary
 
+        @JvmStatic
+        @Suppress("LongParameterList")
+        private external fun scanBlocksFromMemory(
+            dbDataPath: String,
+            blocks: Array<ByteArray>,
+            fromHeight: Long,
+            fromState: ByteArray,
+            limit: Long,
+            networkId: Int
+        ): JniScanSummary
+
         @JvmStatic
         private external fun transactionDataRequests(
             dbDataPath: String,
@@ -834,6 +1310,25 @@ class RustBackend private constructor(
             networkId: Int
         )
 
+        @JvmStatic
+        private external fun listUnminedTransactions(
+            dbDataPath: String,
+            networkId: Int
+        ): Array<JniUnminedTransaction>
+
+        @JvmStatic
+        private external fun getRawTransaction(
+            dbDataPath: String,
+            txId: ByteArray
+        ): ByteArray?
+
+        @JvmStatic
+        private external fun abandonTransaction(
+            dbDataPath: String,
+            txId: ByteArray,
+            networkId: Int
+        )
+
         @JvmStatic
         private external fun proposeTransferFromUri(
             dbDataPath: String,
@@ -853,6 +1348,65 @@ class RustBackend private constructor(
             networkId: Int,
         ): ByteArray
 
+        @JvmStatic
+        private external fun listReceivedOutputs(
+            dbDataPath: String,
+            accountUuid: ByteArray,
+            offset: Long,
+            limit: Long,
+            networkId: Int,
+        ): Array<JniReceivedOutput>
+
+        @JvmStatic
+        private external fun getTransparentReceiverBalances(
+            dbDataPath: String,
+            accountUuid: ByteArray,
+            networkId: Int,
+        ): Array<JniTransparentReceiverBalance>
+
+        @JvmStatic
+        private external fun getBalanceAtHeight(
+            dbDataPath: String,
+            accountUuid: ByteArray,
+            height: Long,
+            networkId: Int,
+        ): JniHistoricalBalance
+
+        @JvmStatic
+        private external fun getBalanceHistory(
+            dbDataPath: String,
+            accountUuid: ByteArray,
+            fromHeight: Long,
+            toHeight: Long,
+            intervalBlocks: Int,
+            networkId: Int,
+        ): Array<JniHistoricalBalance>
+
+        @JvmStatic
+        private external fun queryTransactionHistory(
+            dbDataPath: String,
+            query: JniTransactionHistoryQuery,
+        ): JniTransactionHistoryPage
+
+        @JvmStatic
+        private external fun listSpendableInputs(
+            dbDataPath: String,
+            accountUuid: ByteArray,
+            networkId: Int,
+        ): Array<JniSpendableInput>
+
+        @JvmStatic
+        @Suppress("LongParameterList")
+        private external fun proposeTransferFromInputs(
+            dbDataPath: String,
+            accountUuid: ByteArray,
+            to: String,
+            value: Long,
+            memo: ByteArray?,
+            inputs: Array<JniSpendableInput>,
+            networkId: Int,
+        ): ByteArray
+
         @JvmStatic
         @Suppress("LongParameterList")
         private external fun proposeShielding(
@@ -864,6 +1418,64 @@ class RustBackend private constructor(
             networkId: Int,
         ): ByteArray?
 
+        @JvmStatic
+        private external fun proposeConsolidation(
+            dbDataPath: String,
+            accountUuid: ByteArray,
+            poolType: Int,
+            maxNotes: Int,
+            networkId: Int,
+        ): ByteArray
+
+        @JvmStatic
+        private external fun proposeSplit(
+            dbDataPath: String,
+            accountUuid: ByteArray,
+            poolType: Int,
+            noteCount: Int,
+            networkId: Int,
+        ): ByteArray
+
+        @JvmStatic
+        private external fun proposeDueShielding(
+            dbDataPath: String,
+            accountUuid: ByteArray,
+            policy: JniAutoShieldingPolicy,
+            memo: ByteArray?,
+            networkId: Int,
+        ): Array<ByteArray>
+
+        @JvmStatic
+        private external fun planPoolMigration(
+            dbDataPath: String,
+            accountUuid: ByteArray,
+            intervalBlocks: Int,
+            networkId: Int,
+        ): Array<JniMigrationStep>
+
+        @JvmStatic
+        private external fun getPoolMigrationPlan(
+            dbDataPath: String,
+            accountUuid: ByteArray,
+            networkId: Int,
+        ): Array<JniMigrationStep>
+
+        @JvmStatic
+        private external fun proposeNextPoolMigrationStep(
+            dbDataPath: String,
+            accountUuid: ByteArray,
+            networkId: Int,
+        ): JniMigrationStepProposal?
+
+        @JvmStatic
+        private external fun recordPoolMigrationStep(
+            dbDataPath: String,
+            accountUuid: ByteArray,
+            stepIndex: Int,
+            txId: ByteArray,
+            networkId: Int,
+        )
+
         @JvmStatic
         @Suppress("LongParameterList")
         private external fun createProposedTransactions(
@@ -872,7 +1484,8 @@ class RustBackend private constructor(
             usk: ByteArray,
             spendParamsPath: String,
             outputParamsPath: String,
-            networkId: Int
+            networkId: Int,
+            listener: JniProvingListener?
         ): Array<ByteArray>
 
         @JvmStatic
@@ -883,23 +1496,77 @@ class RustBackend private constructor(
             networkId: Int,
         ): ByteArray
 
+        @JvmStatic
+        private external fun createPcztsFromProposal(
+            dbDataPath: String,
+            accountUuid: ByteArray,
+            proposal: ByteArray,
+            networkId: Int,
+        ): Array<ByteArray>
+
         @JvmStatic
         private external fun redactPcztForSignerRole(pczt: ByteArray): ByteArray
 
+        @JvmStatic
+        private external fun describePczt(
+            pczt: ByteArray,
+            networkId: Int
+        ): JniPcztSummary
+
+        @JvmStatic
+        private external fun encodePcztAsUr(
+            pczt: ByteArray,
+            maxFragmentLength: Int,
+            partCount: Int
+        ): Array<String>
+
         @JvmStatic
         private external fun requiresSaplingProofs(pczt: ByteArray): Boolean
 
+        @JvmStatic
+        private external fun signPczt(
+            pczt: ByteArray,
+            usk: ByteArray,
+        ): ByteArray
+
         @JvmStatic
         private external fun addProofsToPczt(
             pczt: ByteArray,
             spendParamsPath: String,
             outputParamsPath: String,
+            listener: JniProvingListener?,
         ): ByteArray
 
+        @JvmStatic
+        private external fun verifySaplingParams(
+            spendParamsPath: String,
+            outputParamsPath: String,
+        ): Array<JniSaplingParamStatus>
+
+        @JvmStatic
+        @Suppress("LongParameterList")
+        private external fun extractAndStoreTxsFromPczts(
+            dbDataPath: String,
+            originalPczts: Array<ByteArray>,
+            pcztsWithProofs: Array<ByteArray>,
+            pcztsWithSignatures: Array<ByteArray>,
+            spendParamsPath: String,
+            outputParamsPath: String,
+            networkId: Int,
+        ): Array<ByteArray>
+
+        @JvmStatic
+        private external fun verifyPczt(
+            originalPczt: ByteArray,
+            pcztWithProofs: ByteArray,
+            pcztWithSignatures: ByteArray,
+        ): Array<String>
+
         @JvmStatic
         @Suppress("LongParameterList")
         private external fun extractAndStoreTxFromPczt(
             dbDataPath: String,
+            originalPczt: ByteArray,
             pcztWithProofs: ByteArray,
             pcztWithSignatures: ByteArray,
             spendParamsPath: String,
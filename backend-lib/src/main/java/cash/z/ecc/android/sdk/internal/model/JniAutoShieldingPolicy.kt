@@ -0,0 +1,44 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * Decides when the funds held by each transparent receiver of an account are shielded. A receiver is due once its
+ * spendable value reaches [minValue] and a randomized delay of between [minDelayBlocks] and [maxDelayBlocks] has
+ * passed since its most recent receipt, or once its oldest UTXO is [maxUtxoAge] blocks old. Receivers whose funds
+ * would not cover the shielding fee are never due.
+ *
+ * @param minValue the spendable value in zatoshis at which a receiver's funds are shielded
+ * @param maxUtxoAge the age in blocks at which a receiver's funds are shielded regardless of value or delay, or -1
+ *        for no limit
+ * @param oneAddressPerTransaction whether each receiver is shielded in its own transaction, so that receivers are
+ *        not linked on-chain. Ephemeral receivers are always shielded separately.
+ * @param minDelayBlocks the minimum number of blocks to wait after a receiver's most recent receipt
+ * @param maxDelayBlocks the maximum number of blocks to wait after a receiver's most recent receipt
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+class JniAutoShieldingPolicy(
+    val minValue: Long,
+    val maxUtxoAge: Int = -1,
+    val oneAddressPerTransaction: Boolean = true,
+    val minDelayBlocks: Int = 0,
+    val maxDelayBlocks: Int = 0,
+) {
+    init {
+        require(minValue >= 0) {
+            "Minimum value $minValue must be equal or above 0"
+        }
+        require(maxUtxoAge == -1 || maxUtxoAge >= 0) {
+            "Maximum UTXO age $maxUtxoAge must be -1 or equal or above 0"
+        }
+        require(minDelayBlocks >= 0) {
+            "Minimum delay $minDelayBlocks must be equal or above 0"
+        }
+        require(maxDelayBlocks >= minDelayBlocks) {
+            "Maximum delay $maxDelayBlocks must be equal or above minimum delay $minDelayBlocks"
+        }
+    }
+}
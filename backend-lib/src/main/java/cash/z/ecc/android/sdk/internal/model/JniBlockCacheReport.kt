@@ -0,0 +1,59 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+import cash.z.ecc.android.sdk.internal.ext.isInUIntRange
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * The result of checking every block in the compact block cache.
+ *
+ * @param blockCount the number of blocks in the cache
+ * @param minHeight the lowest cached height, or -1 if the cache is empty.
+ *        Although it's type Long, it needs to be in UInt range.
+ * @param maxHeight the highest cached height, or -1 if the cache is empty.
+ *        Although it's type Long, it needs to be in UInt range.
+ * @param missingHeights the heights of blocks whose files are missing or unreadable
+ * @param corruptHeights the heights of blocks whose files do not hold the block their metadata describes
+ * @param holeStartHeights the start heights (inclusive) of the gaps between [minHeight] and [maxHeight] that have
+ *        no cached block
+ * @param holeEndHeights the end heights (exclusive) of the gaps, in the same order as [holeStartHeights]
+ * @param totalBytes the size on disk of the block files and the block metadata database
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+@Suppress("LongParameterList")
+class JniBlockCacheReport(
+    val blockCount: Long,
+    val minHeight: Long,
+    val maxHeight: Long,
+    val missingHeights: LongArray,
+    val corruptHeights: LongArray,
+    val holeStartHeights: LongArray,
+    val holeEndHeights: LongArray,
+    val totalBytes: Long,
+) {
+    init {
+        require(blockCount >= 0) {
+            "Block count $blockCount must be equal or above 0"
+        }
+        require(minHeight == -1L || minHeight.isInUIntRange()) {
+            "Height $minHeight is outside of allowed UInt range"
+        }
+        require(maxHeight == -1L || maxHeight.isInUIntRange()) {
+            "Height $maxHeight is outside of allowed UInt range"
+        }
+        require(maxHeight >= minHeight) {
+            "Max height $maxHeight must be equal or above min height $minHeight"
+        }
+        require(holeStartHeights.size == holeEndHeights.size) {
+            "Hole start and end heights must have the same size"
+        }
+        require(totalBytes >= 0) {
+            "Total bytes $totalBytes must be equal or above 0"
+        }
+    }
+
+    val isIntact: Boolean
+        get() = missingHeights.isEmpty() && corruptHeights.isEmpty()
+}
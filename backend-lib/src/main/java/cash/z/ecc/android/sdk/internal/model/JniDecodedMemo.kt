@@ -0,0 +1,38 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * A memo interpreted according to ZIP 302.
+ *
+ * @param kind one of [JniTransactionOutput.MEMO_KIND_EMPTY], [JniTransactionOutput.MEMO_KIND_TEXT],
+ *        [JniTransactionOutput.MEMO_KIND_ARBITRARY] or [JniTransactionOutput.MEMO_KIND_FUTURE]
+ * @param text the text of a text memo, without any reply-to address
+ * @param data the 511 bytes following the first byte of an arbitrary-data memo
+ * @param replyTo the reply-to address embedded at the end of a text memo, if any
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+class JniDecodedMemo(
+    val kind: Int,
+    val text: String?,
+    val data: ByteArray?,
+    val replyTo: String?,
+) {
+    init {
+        require(kind in JniTransactionOutput.MEMO_KIND_EMPTY..JniTransactionOutput.MEMO_KIND_FUTURE) {
+            "Memo kind $kind is not supported"
+        }
+        require((text != null) == (kind == JniTransactionOutput.MEMO_KIND_TEXT)) {
+            "Only text memos have text"
+        }
+        require((data != null) == (kind == JniTransactionOutput.MEMO_KIND_ARBITRARY)) {
+            "Only arbitrary-data memos have data"
+        }
+        require(replyTo == null || text != null) {
+            "Only text memos have a reply-to address"
+        }
+    }
+}
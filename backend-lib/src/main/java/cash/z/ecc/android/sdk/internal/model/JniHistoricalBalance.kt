@@ -0,0 +1,45 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+import cash.z.ecc.android.sdk.internal.ext.isInUIntRange
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * An account's balance in each pool as of a block height, counting only mined transactions.
+ *
+ * @param height the height the balance is as of.
+ *        Although it's type Long, it needs to be in UInt range.
+ * @param blockTime the time of the block at [height] in seconds since the epoch, or -1 if the block has not been
+ *        scanned. Although it's type Long, it needs to be in UInt range.
+ * @param transparent the transparent balance in zatoshis
+ * @param sapling the Sapling balance in zatoshis
+ * @param orchard the Orchard balance in zatoshis
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+class JniHistoricalBalance(
+    val height: Long,
+    val blockTime: Long,
+    val transparent: Long,
+    val sapling: Long,
+    val orchard: Long,
+) {
+    init {
+        require(height.isInUIntRange()) {
+            "Height $height is outside of allowed UInt range"
+        }
+        require(blockTime == -1L || blockTime.isInUIntRange()) {
+            "Block time $blockTime is outside of allowed UInt range"
+        }
+        require(transparent >= 0) {
+            "Transparent balance $transparent must be equal or above 0"
+        }
+        require(sapling >= 0) {
+            "Sapling balance $sapling must be equal or above 0"
+        }
+        require(orchard >= 0) {
+            "Orchard balance $orchard must be equal or above 0"
+        }
+    }
+}
@@ -0,0 +1,62 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+import cash.z.ecc.android.sdk.internal.ext.isInUIntRange
+import cash.z.ecc.android.sdk.internal.jni.JNI_TXID_BYTES_SIZE
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * A step of a Sapling-to-Orchard migration plan, which moves a standard denomination of the account's Sapling
+ * balance to Orchard.
+ *
+ * @param stepIndex identifies the step within the plan
+ * @param value the value moved to Orchard, in zatoshis, excluding the fee
+ * @param dueHeight the height from which the step may be carried out.
+ *        Although it's type Long, it needs to be in UInt range.
+ * @param txid the ID of the transaction created for the step, if any
+ * @param status one of [STATUS_PENDING], [STATUS_SUBMITTED] or [STATUS_MINED]
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+class JniMigrationStep(
+    val stepIndex: Int,
+    val value: Long,
+    val dueHeight: Long,
+    val txid: ByteArray?,
+    val status: Int,
+) {
+    init {
+        require(stepIndex >= 0) {
+            "Step index must not be negative"
+        }
+        require(value > 0) {
+            "Value must be positive"
+        }
+        require(dueHeight.isInUIntRange()) {
+            "Height $dueHeight is outside of allowed UInt range"
+        }
+        require(txid == null || txid.size == JNI_TXID_BYTES_SIZE) {
+            "Transaction ID must be $JNI_TXID_BYTES_SIZE bytes"
+        }
+        require(status in STATUS_PENDING..STATUS_MINED) {
+            "Status $status must be one of the STATUS_* constants"
+        }
+        require(status == STATUS_PENDING || txid != null) {
+            "A submitted or mined step must have a transaction ID"
+        }
+    }
+
+    companion object {
+        /**
+         * No transaction has been created for the step, or its transaction expired without being mined.
+         */
+        const val STATUS_PENDING = 0
+
+        /**
+         * The step's transaction has been created but not yet mined.
+         */
+        const val STATUS_SUBMITTED = 1
+        const val STATUS_MINED = 2
+    }
+}
@@ -0,0 +1,25 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * The proposed transaction for a step of a Sapling-to-Orchard migration plan.
+ *
+ * @param stepIndex the step the proposal carries out, to be passed to `recordPoolMigrationStep` once its
+ *        transaction has been created
+ * @param proposal the serialized proposal
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+class JniMigrationStepProposal(
+    val stepIndex: Int,
+    val proposal: ByteArray,
+) {
+    init {
+        require(stepIndex >= 0) {
+            "Step index must not be negative"
+        }
+    }
+}
@@ -0,0 +1,58 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+import cash.z.ecc.android.sdk.internal.jni.JNI_MEMO_BYTES_SIZE
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * An output created by a PCZT. Zero-valued padding outputs are not included.
+ *
+ * @param poolType the pool of the output, as a [ZcashProtocol.poolCode]
+ * @param index the index of the output within its pool in the PCZT
+ * @param kind the kind of recipient, one of [KIND_EXTERNAL], [KIND_EPHEMERAL], [KIND_INTERNAL] or
+ *        [KIND_UNKNOWN] if the PCZT no longer records it
+ * @param address the recipient's address, if it could be determined
+ * @param value the value of the output in zatoshis, or -1 if it has been redacted
+ * @param memo the raw 512-byte memo of a shielded output, or null if it could not be recovered
+ * @param missingProof whether the proof for the output has not been created yet
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+@Suppress("LongParameterList")
+class JniPcztOutput(
+    val poolType: Int,
+    val index: Int,
+    val kind: Int,
+    val address: String?,
+    val value: Long,
+    val memo: ByteArray?,
+    val missingProof: Boolean,
+) {
+    init {
+        require(ZcashProtocol.validate(poolType)) {
+            "Pool type $poolType is not supported"
+        }
+        require(index >= 0) {
+            "Index $index must be equal or above 0"
+        }
+        require(kind in KIND_EXTERNAL..KIND_UNKNOWN) {
+            "Output kind $kind is not supported"
+        }
+        require(value >= -1) {
+            "Value $value must be equal or above -1"
+        }
+        memo?.let {
+            require(memo.size == JNI_MEMO_BYTES_SIZE) {
+                "Memo must be $JNI_MEMO_BYTES_SIZE bytes"
+            }
+        }
+    }
+
+    companion object {
+        const val KIND_EXTERNAL = 0
+        const val KIND_EPHEMERAL = 1
+        const val KIND_INTERNAL = 2
+        const val KIND_UNKNOWN = 3
+    }
+}
@@ -0,0 +1,36 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * An input spent by a PCZT.
+ *
+ * @param poolType the pool of the input, as a [ZcashProtocol.poolCode]
+ * @param index the index of the input within its pool in the PCZT
+ * @param value the value of the input in zatoshis, or -1 if it has been redacted
+ * @param missingSignature whether the input has not been signed yet
+ * @param missingProof whether the proof for the input has not been created yet
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+class JniPcztSpend(
+    val poolType: Int,
+    val index: Int,
+    val value: Long,
+    val missingSignature: Boolean,
+    val missingProof: Boolean,
+) {
+    init {
+        require(ZcashProtocol.validate(poolType)) {
+            "Pool type $poolType is not supported"
+        }
+        require(index >= 0) {
+            "Index $index must be equal or above 0"
+        }
+        require(value >= -1) {
+            "Value $value must be equal or above -1"
+        }
+    }
+}
@@ -0,0 +1,42 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * A summary of what a PCZT does, for confirmation before it is signed.
+ *
+ * @param spends the inputs spent by the PCZT
+ * @param outputs the outputs created by the PCZT
+ * @param fee the fee paid by the transaction in zatoshis, or -1 if it cannot be determined yet
+ * @param expiryHeight the height after which the transaction can no longer be mined, or 0 if it does not expire
+ * @param consensusBranchId the consensus branch ID the transaction will be valid under
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+class JniPcztSummary(
+    val spends: Array<JniPcztSpend>,
+    val outputs: Array<JniPcztOutput>,
+    val fee: Long,
+    val expiryHeight: Long,
+    val consensusBranchId: Long,
+) {
+    init {
+        require(fee >= -1) {
+            "Fee $fee must be equal or above -1"
+        }
+        require(expiryHeight >= 0) {
+            "Expiry height $expiryHeight must be equal or above 0"
+        }
+        require(consensusBranchId >= 0) {
+            "Consensus branch ID $consensusBranchId must be equal or above 0"
+        }
+    }
+
+    /**
+     * Whether any input or output still lacks a signature or proof.
+     */
+    val isIncomplete: Boolean
+        get() = spends.any { it.missingSignature || it.missingProof } || outputs.any { it.missingProof }
+}
@@ -0,0 +1,40 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * The progress of reassembling a PCZT from scanned `ur:zcash-pczt` parts.
+ *
+ * @param receivedParts the number of fragments of the PCZT recovered so far, which reaches [expectedParts] once
+ *        the PCZT has been reassembled
+ * @param expectedParts the number of fragments the PCZT was split into, or -1 if no part has been received yet.
+ *        Because the parts are fountain-coded, the PCZT may need more than this number of parts to be reassembled.
+ * @param pczt the reassembled PCZT in its serialized format, or null if more parts are needed
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+class JniPcztUrProgress(
+    val receivedParts: Int,
+    val expectedParts: Int,
+    val pczt: ByteArray?,
+) {
+    init {
+        require(receivedParts >= 0) {
+            "Received parts $receivedParts must be equal or above 0"
+        }
+        require(expectedParts == -1 || expectedParts > 0) {
+            "Expected parts $expectedParts must be -1 or above 0"
+        }
+        require(expectedParts == -1 || receivedParts <= expectedParts) {
+            "Received parts $receivedParts must be equal or below expected parts $expectedParts"
+        }
+    }
+
+    /**
+     * Whether the PCZT has been reassembled.
+     */
+    val isComplete: Boolean
+        get() = pczt != null
+}
@@ -0,0 +1,34 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication interface.
+ *
+ * Receives progress updates while transaction proofs are created, and allows proving to be cancelled. Both
+ * methods are called on the thread that started proving.
+ */
+@Keep
+interface JniProvingListener {
+    /**
+     * Called each time a proof has been created. A single proof covers every action of an Orchard bundle, and
+     * takes longer the more actions it covers, so where it is reported it counts as one proof per action.
+     *
+     * @param completedProofs the number of proofs created so far
+     * @param totalProofs the expected total number of proofs, which is never less than [completedProofs]
+     * @param step the index of the transaction whose proofs are being created
+     * @param stepCount the number of transactions being created
+     */
+    fun onProgress(
+        completedProofs: Int,
+        totalProofs: Int,
+        step: Int,
+        stepCount: Int
+    )
+
+    /**
+     * Polled while proofs are being created. Once this returns `true`, the remaining proofs are skipped where
+     * possible, and the operation fails without storing any transactions.
+     */
+    fun isCancelled(): Boolean
+}
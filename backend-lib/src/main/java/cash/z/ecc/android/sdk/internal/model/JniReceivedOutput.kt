@@ -0,0 +1,68 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+import cash.z.ecc.android.sdk.internal.ext.isInUIntRange
+import cash.z.ecc.android.sdk.internal.jni.JNI_TXID_BYTES_SIZE
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * Describes a Sapling or Orchard note, or a transparent output, received by an account.
+ *
+ * @param txid the ID of the transaction that created the output
+ * @param poolType the pool containing the output, as a [ZcashProtocol.poolCode]
+ * @param outputIndex the index of the output within its pool in the transaction
+ * @param value the value of the output in zatoshis
+ * @param minedHeight the height at which the output was mined, or -1 if it is not yet
+ *        known. Although it's type Long, it needs to be in UInt range.
+ * @param confirmations the number of confirmations the output has, or 0 if it is unmined
+ * @param spendStatus one of [SPEND_STATUS_UNSPENT], [SPEND_STATUS_PENDING_SPEND], or [SPEND_STATUS_SPENT]
+ * @param hasMemo whether the output has a non-empty memo
+ * @param isChange whether the output is change sent back to the wallet
+ * @param isSpendable whether the output could be spent in a transaction created now
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+@Suppress("LongParameterList")
+class JniReceivedOutput(
+    val txid: ByteArray,
+    val poolType: Int,
+    val outputIndex: Int,
+    val value: Long,
+    val minedHeight: Long,
+    val confirmations: Long,
+    val spendStatus: Int,
+    val hasMemo: Boolean,
+    val isChange: Boolean,
+    val isSpendable: Boolean,
+) {
+    init {
+        require(txid.size == JNI_TXID_BYTES_SIZE) {
+            "Transaction ID must be $JNI_TXID_BYTES_SIZE bytes"
+        }
+        require(ZcashProtocol.validate(poolType)) {
+            "Pool type $poolType is not supported"
+        }
+        require(outputIndex >= 0) {
+            "Output index $outputIndex must be equal or above 0"
+        }
+        require(value >= 0) {
+            "Value $value must be equal or above 0"
+        }
+        require(minedHeight == -1L || minedHeight.isInUIntRange()) {
+            "Height $minedHeight is outside of allowed UInt range"
+        }
+        require(confirmations.isInUIntRange()) {
+            "Confirmations $confirmations is outside of allowed UInt range"
+        }
+        require(spendStatus in SPEND_STATUS_UNSPENT..SPEND_STATUS_SPENT) {
+            "Spend status $spendStatus is not supported"
+        }
+    }
+
+    companion object {
+        const val SPEND_STATUS_UNSPENT = 0
+        const val SPEND_STATUS_PENDING_SPEND = 1
+        const val SPEND_STATUS_SPENT = 2
+    }
+}
@@ -0,0 +1,53 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+import cash.z.ecc.android.sdk.internal.ext.isInUIntRange
+import cash.z.ecc.android.sdk.internal.jni.JNI_ACCOUNT_UUID_BYTES_SIZE
+import cash.z.ecc.android.sdk.internal.jni.JNI_TXID_BYTES_SIZE
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * An unspent output that a rewind would make unmined, and so unspendable until the blocks above the rewind height
+ * are rescanned.
+ *
+ * @param accountUuid the UUID of the account that received the output
+ * @param txid the ID of the transaction that created the output
+ * @param poolType the pool containing the output, as a [ZcashProtocol.poolCode]
+ * @param outputIndex the index of the output within its pool in the transaction
+ * @param value the value of the output in zatoshis
+ * @param minedHeight the height at which the output is currently mined.
+ *        Although it's type Long, it needs to be in UInt range.
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+@Suppress("LongParameterList")
+class JniRewindAffectedOutput(
+    val accountUuid: ByteArray,
+    val txid: ByteArray,
+    val poolType: Int,
+    val outputIndex: Int,
+    val value: Long,
+    val minedHeight: Long,
+) {
+    init {
+        require(accountUuid.size == JNI_ACCOUNT_UUID_BYTES_SIZE) {
+            "Account UUID must be $JNI_ACCOUNT_UUID_BYTES_SIZE bytes"
+        }
+        require(txid.size == JNI_TXID_BYTES_SIZE) {
+            "Transaction ID must be $JNI_TXID_BYTES_SIZE bytes"
+        }
+        require(ZcashProtocol.validate(poolType)) {
+            "Pool type $poolType is not supported"
+        }
+        require(outputIndex >= 0) {
+            "Output index $outputIndex must be equal or above 0"
+        }
+        require(value >= 0) {
+            "Value $value must be equal or above 0"
+        }
+        require(minedHeight.isInUIntRange()) {
+            "Height $minedHeight is outside of allowed UInt range"
+        }
+    }
+}
@@ -0,0 +1,57 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+import cash.z.ecc.android.sdk.internal.ext.isInUIntRange
+import cash.z.ecc.android.sdk.internal.jni.JNI_TXID_BYTES_SIZE
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * The effect that rewinding the wallet to [requestedHeight] would have, computed without modifying the wallet.
+ *
+ * @param requestedHeight the height the rewind was requested to.
+ *        Although it's type Long, it needs to be in UInt range.
+ * @param rewindHeight the height the wallet would actually be rewound to, or -1 if the requested rewind is invalid.
+ *        Although it's type Long, it needs to be in UInt range.
+ * @param safeRewindHeight if the requested rewind is invalid, the lowest height the wallet can be rewound to, or -1
+ *        if none can be determined. Although it's type Long, it needs to be in UInt range.
+ * @param unminedTxids the IDs of the transactions mined above [rewindHeight], which would become unmined
+ * @param affectedOutputs the unspent outputs mined above [rewindHeight], which would become unspendable until they
+ *        are rescanned
+ * @param blocksToRescan the number of scanned blocks above [rewindHeight], which would need to be rescanned
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+@Suppress("LongParameterList")
+class JniRewindPreview(
+    val requestedHeight: Long,
+    val rewindHeight: Long,
+    val safeRewindHeight: Long,
+    val unminedTxids: Array<ByteArray>,
+    val affectedOutputs: Array<JniRewindAffectedOutput>,
+    val blocksToRescan: Long,
+) {
+    init {
+        require(requestedHeight.isInUIntRange()) {
+            "Height $requestedHeight is outside of allowed UInt range"
+        }
+        require(rewindHeight == -1L || rewindHeight.isInUIntRange()) {
+            "Height $rewindHeight is outside of allowed UInt range"
+        }
+        require(rewindHeight <= requestedHeight) {
+            "Rewind height $rewindHeight must not be above requested height $requestedHeight"
+        }
+        require(safeRewindHeight == -1L || safeRewindHeight.isInUIntRange()) {
+            "Height $safeRewindHeight is outside of allowed UInt range"
+        }
+        require(unminedTxids.all { it.size == JNI_TXID_BYTES_SIZE }) {
+            "Transaction IDs must be $JNI_TXID_BYTES_SIZE bytes"
+        }
+        require(blocksToRescan >= 0) {
+            "Blocks to rescan $blocksToRescan must be equal or above 0"
+        }
+    }
+
+    val isValid: Boolean
+        get() = rewindHeight != -1L
+}
@@ -0,0 +1,36 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * The state of a Sapling parameter file on disk.
+ *
+ * @param fileName the name of the parameter file, e.g. `sapling-spend.params`
+ * @param status one of [STATUS_VALID], [STATUS_MISSING] or [STATUS_CORRUPT]
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+class JniSaplingParamStatus(
+    val fileName: String,
+    val status: Int,
+) {
+    init {
+        require(status in STATUS_VALID..STATUS_CORRUPT) {
+            "Status $status must be one of the STATUS_* constants"
+        }
+    }
+
+    /**
+     * Whether the file exists but has the wrong size or hash, and so must be downloaded again.
+     */
+    val isCorrupt: Boolean
+        get() = status == STATUS_CORRUPT
+
+    companion object {
+        const val STATUS_VALID = 0
+        const val STATUS_MISSING = 1
+        const val STATUS_CORRUPT = 2
+    }
+}
@@ -6,6 +6,11 @@ import cash.z.ecc.android.sdk.internal.ext.isInUIntRange
 /**
  * Serves as cross layer (Kotlin, Rust) communication class.
  *
+ * Transparent outputs are not counted, as the compact blocks that are scanned carry no transparent inputs or
+ * outputs. Trial decryption and note commitment tree updates happen in the same pass over the blocks, so they
+ * are not timed separately; the time is instead split between reading and scanning the blocks, and writing the
+ * results to the wallet database.
+ *
  * @param startHeight the minimum height in the scanned range (inclusive).
  *        Although it's type Long, it needs to be a UInt.
  * @param endHeight the maximum height in the scanned range (exclusive).
@@ -14,15 +19,30 @@ import cash.z.ecc.android.sdk.internal.ext.isInUIntRange
  *        the scanned range.
  * @param receivedSaplingNoteCount the number of Sapling notes detected as
  *        received in the scanned range.
+ * @param spentOrchardNoteCount the number of Orchard notes detected as spent in
+ *        the scanned range.
+ * @param receivedOrchardNoteCount the number of Orchard notes detected as
+ *        received in the scanned range.
+ * @param blocksProcessed the number of blocks read from the block cache.
+ * @param readAndScanMillis the wall-clock time spent reading the wallet state and
+ *        the blocks, and scanning the blocks, in milliseconds.
+ * @param dbWriteMillis the wall-clock time spent writing the results of the scan
+ *        to the wallet database, in milliseconds.
  * @throws IllegalArgumentException unless (startHeight and endHeight are UInts,
  *         and startHeight is not less than endHeight).
  */
 @Keep
+@Suppress("LongParameterList")
 class JniScanSummary(
     val startHeight: Long,
     val endHeight: Long,
     val spentSaplingNoteCount: Long,
-    val receivedSaplingNoteCount: Long
+    val receivedSaplingNoteCount: Long,
+    val spentOrchardNoteCount: Long,
+    val receivedOrchardNoteCount: Long,
+    val blocksProcessed: Long,
+    val readAndScanMillis: Long,
+    val dbWriteMillis: Long
 ) {
     init {
         // We require some of the parameters below to be in the range of
@@ -36,5 +56,8 @@ class JniScanSummary(
         require(endHeight >= startHeight) {
             "End height $endHeight must be greater than start height $startHeight."
         }
+        require(readAndScanMillis >= 0 && dbWriteMillis >= 0) {
+            "Durations must be equal or above 0"
+        }
     }
 }
@@ -0,0 +1,45 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+import cash.z.ecc.android.sdk.internal.ext.isInUIntRange
+import cash.z.ecc.android.sdk.internal.jni.JNI_TXID_BYTES_SIZE
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * Identifies a note or UTXO that can be spent, for use with coin control.
+ *
+ * @param txid the ID of the transaction that created the output
+ * @param poolType the pool containing the output, as a [ZcashProtocol.poolCode]
+ * @param outputIndex the index of the output within its pool in the transaction
+ * @param value the value of the output in zatoshis
+ * @param minedHeight the height at which the output was mined, or -1 if it is not yet
+ *        known. Although it's type Long, it needs to be in UInt range.
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+class JniSpendableInput(
+    val txid: ByteArray,
+    val poolType: Int,
+    val outputIndex: Int,
+    val value: Long,
+    val minedHeight: Long,
+) {
+    init {
+        require(txid.size == JNI_TXID_BYTES_SIZE) {
+            "Transaction ID must be $JNI_TXID_BYTES_SIZE bytes"
+        }
+        require(ZcashProtocol.validate(poolType)) {
+            "Pool type $poolType is not supported"
+        }
+        require(outputIndex >= 0) {
+            "Output index $outputIndex must be equal or above 0"
+        }
+        require(value >= 0) {
+            "Value $value must be equal or above 0"
+        }
+        require(minedHeight == -1L || minedHeight.isInUIntRange()) {
+            "Height $minedHeight is outside of allowed UInt range"
+        }
+    }
+}
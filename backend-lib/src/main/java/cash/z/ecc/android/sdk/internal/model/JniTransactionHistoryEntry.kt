@@ -0,0 +1,73 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+import cash.z.ecc.android.sdk.internal.ext.isInUIntRange
+import cash.z.ecc.android.sdk.internal.jni.JNI_ACCOUNT_UUID_BYTES_SIZE
+import cash.z.ecc.android.sdk.internal.jni.JNI_TXID_BYTES_SIZE
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * A transaction in the history of an account.
+ *
+ * @param txid the transaction ID
+ * @param accountUuid the account from whose perspective the transaction is described
+ * @param minedHeight the height at which the transaction was mined, or -1 if it is unmined
+ * @param expiryHeight the transaction's expiry height, or -1 if it is unknown or the transaction does not expire
+ * @param blockTime the time of the block containing the transaction in seconds since the Unix epoch, or -1 if
+ *        it is unmined
+ * @param netValue the net change in the account's balance caused by the transaction, in zatoshis
+ * @param fee the fee paid by the transaction in zatoshis, or -1 if it is unknown
+ * @param direction one of [DIRECTION_SENT], [DIRECTION_RECEIVED] or [DIRECTION_SHIELDING]
+ * @param status one of [STATUS_PENDING], [STATUS_MINED] or [STATUS_EXPIRED]
+ * @param recipients the non-change outputs sent by the account in this transaction
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+@Suppress("LongParameterList")
+class JniTransactionHistoryEntry(
+    val txid: ByteArray,
+    val accountUuid: ByteArray,
+    val minedHeight: Long,
+    val expiryHeight: Long,
+    val blockTime: Long,
+    val netValue: Long,
+    val fee: Long,
+    val direction: Int,
+    val status: Int,
+    val recipients: Array<JniTransactionRecipient>,
+) {
+    init {
+        require(txid.size == JNI_TXID_BYTES_SIZE) {
+            "Transaction ID must be $JNI_TXID_BYTES_SIZE bytes"
+        }
+        require(accountUuid.size == JNI_ACCOUNT_UUID_BYTES_SIZE) {
+            "Account UUID must be 16 bytes"
+        }
+        require(minedHeight == -1L || minedHeight.isInUIntRange()) {
+            "Height $minedHeight is outside of allowed UInt range"
+        }
+        require(expiryHeight == -1L || expiryHeight.isInUIntRange()) {
+            "Height $expiryHeight is outside of allowed UInt range"
+        }
+        require(fee >= -1) {
+            "Fee $fee must be equal or above -1"
+        }
+        require(direction in DIRECTION_SENT..DIRECTION_SHIELDING) {
+            "Direction $direction is not supported"
+        }
+        require(status in STATUS_PENDING..STATUS_EXPIRED) {
+            "Status $status is not supported"
+        }
+    }
+
+    companion object {
+        const val DIRECTION_SENT = 0
+        const val DIRECTION_RECEIVED = 1
+        const val DIRECTION_SHIELDING = 2
+
+        const val STATUS_PENDING = 0
+        const val STATUS_MINED = 1
+        const val STATUS_EXPIRED = 2
+    }
+}
@@ -0,0 +1,15 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * @param entries the transactions in this page, most recent first
+ * @param nextCursor the cursor with which to request the following page, or null if this is the last page
+ */
+@Keep
+class JniTransactionHistoryPage(
+    val entries: Array<JniTransactionHistoryEntry>,
+    val nextCursor: ByteArray?,
+)
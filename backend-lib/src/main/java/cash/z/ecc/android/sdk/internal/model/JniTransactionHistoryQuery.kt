@@ -0,0 +1,76 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+import cash.z.ecc.android.sdk.internal.ext.isInUIntRange
+import cash.z.ecc.android.sdk.internal.jni.JNI_ACCOUNT_UUID_BYTES_SIZE
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * Selects a page of the wallet's transaction history. Every criterion that is set must match; numeric
+ * criteria use -1 to mean "not set".
+ *
+ * @param accountUuid the account whose history to return, or null for all accounts
+ * @param fromHeight the lowest mined height to include. Although it's type Long, it needs to be in UInt range.
+ * @param toHeight the highest mined height to include. Although it's type Long, it needs to be in UInt range.
+ * @param fromTime the earliest block time to include, in seconds since the Unix epoch
+ * @param toTime the latest block time to include, in seconds since the Unix epoch
+ * @param direction one of [JniTransactionHistoryEntry.DIRECTION_SENT],
+ *        [JniTransactionHistoryEntry.DIRECTION_RECEIVED] or [JniTransactionHistoryEntry.DIRECTION_SHIELDING]
+ * @param poolType only include transactions in which the account sent or received an output in this pool, as a
+ *        [ZcashProtocol.poolCode]
+ * @param memoText only include transactions with a text memo containing this string
+ * @param status one of [JniTransactionHistoryEntry.STATUS_PENDING], [JniTransactionHistoryEntry.STATUS_MINED] or
+ *        [JniTransactionHistoryEntry.STATUS_EXPIRED]
+ * @param cursor the [JniTransactionHistoryPage.nextCursor] of the previous page, or null for the first page
+ * @param limit the maximum number of transactions to return
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+@Suppress("LongParameterList")
+class JniTransactionHistoryQuery(
+    val accountUuid: ByteArray? = null,
+    val fromHeight: Long = -1,
+    val toHeight: Long = -1,
+    val fromTime: Long = -1,
+    val toTime: Long = -1,
+    val direction: Int = -1,
+    val poolType: Int = -1,
+    val memoText: String? = null,
+    val status: Int = -1,
+    val cursor: ByteArray? = null,
+    val limit: Int,
+) {
+    init {
+        accountUuid?.let {
+            require(accountUuid.size == JNI_ACCOUNT_UUID_BYTES_SIZE) {
+                "Account UUID must be 16 bytes"
+            }
+        }
+        require(fromHeight == -1L || fromHeight.isInUIntRange()) {
+            "Height $fromHeight is outside of allowed UInt range"
+        }
+        require(toHeight == -1L || toHeight.isInUIntRange()) {
+            "Height $toHeight is outside of allowed UInt range"
+        }
+        require(direction == -1 || direction in DIRECTION_RANGE) {
+            "Direction $direction is not supported"
+        }
+        require(poolType == -1 || ZcashProtocol.validate(poolType)) {
+            "Pool type $poolType is not supported"
+        }
+        require(status == -1 || status in STATUS_RANGE) {
+            "Status $status is not supported"
+        }
+        require(limit > 0) {
+            "Limit $limit must be above 0"
+        }
+    }
+
+    companion object {
+        private val DIRECTION_RANGE =
+            JniTransactionHistoryEntry.DIRECTION_SENT..JniTransactionHistoryEntry.DIRECTION_SHIELDING
+        private val STATUS_RANGE =
+            JniTransactionHistoryEntry.STATUS_PENDING..JniTransactionHistoryEntry.STATUS_EXPIRED
+    }
+}
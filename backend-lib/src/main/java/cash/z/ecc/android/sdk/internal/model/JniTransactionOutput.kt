@@ -0,0 +1,78 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+import cash.z.ecc.android.sdk.internal.jni.JNI_ACCOUNT_UUID_BYTES_SIZE
+import cash.z.ecc.android.sdk.internal.jni.JNI_MEMO_BYTES_SIZE
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * An output of a transaction that was sent or received by the wallet.
+ *
+ * @param poolType the pool of the output, as a [ZcashProtocol.poolCode]
+ * @param outputIndex the index of the output within its pool in the transaction
+ * @param fromAccountUuid the account that sent the output, if it was sent by this wallet
+ * @param toAccountUuid the account that received the output, if it was received by this wallet
+ * @param recipient the recipient's address. For outputs sent by this wallet, this is the address the payment was
+ *        made to, which may be a Unified Address.
+ * @param value the value of the output in zatoshis
+ * @param isChange whether the output is change sent back to the wallet
+ * @param memo the raw 512-byte memo, or null if the output has no memo
+ * @param memoKind the ZIP 302 classification of [memo], one of [MEMO_KIND_EMPTY], [MEMO_KIND_TEXT],
+ *        [MEMO_KIND_ARBITRARY] or [MEMO_KIND_FUTURE], or -1 if the output has no memo
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+@Suppress("LongParameterList")
+class JniTransactionOutput(
+    val poolType: Int,
+    val outputIndex: Int,
+    val fromAccountUuid: ByteArray?,
+    val toAccountUuid: ByteArray?,
+    val recipient: String?,
+    val value: Long,
+    val isChange: Boolean,
+    val memo: ByteArray?,
+    val memoKind: Int,
+) {
+    init {
+        require(ZcashProtocol.validate(poolType)) {
+            "Pool type $poolType is not supported"
+        }
+        require(outputIndex >= 0) {
+            "Output index $outputIndex must be equal or above 0"
+        }
+        fromAccountUuid?.let {
+            require(fromAccountUuid.size == JNI_ACCOUNT_UUID_BYTES_SIZE) {
+                "Account UUID must be 16 bytes"
+            }
+        }
+        toAccountUuid?.let {
+            require(toAccountUuid.size == JNI_ACCOUNT_UUID_BYTES_SIZE) {
+                "Account UUID must be 16 bytes"
+            }
+        }
+        require(value >= 0) {
+            "Value $value must be equal or above 0"
+        }
+        if (memo == null) {
+            require(memoKind == -1) {
+                "Memo kind must be -1 when there is no memo"
+            }
+        } else {
+            require(memo.size == JNI_MEMO_BYTES_SIZE) {
+                "Memo must be $JNI_MEMO_BYTES_SIZE bytes"
+            }
+            require(memoKind in MEMO_KIND_EMPTY..MEMO_KIND_FUTURE) {
+                "Memo kind $memoKind is not supported"
+            }
+        }
+    }
+
+    companion object {
+        const val MEMO_KIND_EMPTY = 0
+        const val MEMO_KIND_TEXT = 1
+        const val MEMO_KIND_ARBITRARY = 2
+        const val MEMO_KIND_FUTURE = 3
+    }
+}
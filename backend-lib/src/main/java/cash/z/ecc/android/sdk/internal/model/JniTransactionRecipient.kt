@@ -0,0 +1,35 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+import cash.z.ecc.android.sdk.internal.jni.JNI_ACCOUNT_UUID_BYTES_SIZE
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * @param poolType the pool of the output, as a [ZcashProtocol.poolCode]
+ * @param address the recipient's address, if known
+ * @param accountUuid the receiving account, if the output was sent to an account in this wallet
+ * @param value the value of the output in zatoshis
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+class JniTransactionRecipient(
+    val poolType: Int,
+    val address: String?,
+    val accountUuid: ByteArray?,
+    val value: Long,
+) {
+    init {
+        require(ZcashProtocol.validate(poolType)) {
+            "Pool type $poolType is not supported"
+        }
+        accountUuid?.let {
+            require(accountUuid.size == JNI_ACCOUNT_UUID_BYTES_SIZE) {
+                "Account UUID must be 16 bytes"
+            }
+        }
+        require(value >= 0) {
+            "Value $value must be equal or above 0"
+        }
+    }
+}
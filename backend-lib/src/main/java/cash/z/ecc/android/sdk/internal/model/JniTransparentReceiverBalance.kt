@@ -0,0 +1,52 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * The funds held by one of an account's transparent receivers.
+ *
+ * @param address the transparent address
+ * @param scope one of [SCOPE_EXTERNAL], [SCOPE_INTERNAL] or [SCOPE_EPHEMERAL], or -1 for a scope not known to the
+ *        SDK
+ * @param spendable the value that could be spent in a transaction created now, in zatoshis
+ * @param pending the value awaiting confirmation, in zatoshis
+ * @param immature the value held back as change awaiting confirmation, in zatoshis
+ * @param utxoCount the number of unspent outputs held by the receiver
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+@Suppress("LongParameterList")
+class JniTransparentReceiverBalance(
+    val address: String,
+    val scope: Int,
+    val spendable: Long,
+    val pending: Long,
+    val immature: Long,
+    val utxoCount: Int,
+) {
+    init {
+        require(scope == -1 || scope in SCOPE_EXTERNAL..SCOPE_EPHEMERAL) {
+            "Scope $scope is not supported"
+        }
+        require(spendable >= 0) {
+            "Spendable value $spendable must be equal or above 0"
+        }
+        require(pending >= 0) {
+            "Pending value $pending must be equal or above 0"
+        }
+        require(immature >= 0) {
+            "Immature value $immature must be equal or above 0"
+        }
+        require(utxoCount >= 0) {
+            "UTXO count $utxoCount must be equal or above 0"
+        }
+    }
+
+    companion object {
+        const val SCOPE_EXTERNAL = 0
+        const val SCOPE_INTERNAL = 1
+        const val SCOPE_EPHEMERAL = 2
+    }
+}
@@ -0,0 +1,38 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import androidx.annotation.Keep
+import cash.z.ecc.android.sdk.internal.ext.isInUIntRange
+import cash.z.ecc.android.sdk.internal.jni.JNI_TXID_BYTES_SIZE
+
+/**
+ * Serves as cross layer (Kotlin, Rust) communication class.
+ *
+ * A transaction created by the wallet that has not been mined.
+ *
+ * @param txid the transaction ID
+ * @param expiryHeight the last height at which the transaction can be mined, or -1 if it does not expire.
+ *        Although it's type Long, it needs to be in UInt range.
+ * @param isExpired whether the wallet has scanned every block up to [expiryHeight] without finding the
+ *        transaction, so that it can never be mined and the notes it spends are spendable again
+ * @param hasRawTransaction whether the raw transaction is available for resubmission
+ * @throws IllegalArgumentException if the values are inconsistent.
+ */
+@Keep
+class JniUnminedTransaction(
+    val txid: ByteArray,
+    val expiryHeight: Long,
+    val isExpired: Boolean,
+    val hasRawTransaction: Boolean,
+) {
+    init {
+        require(txid.size == JNI_TXID_BYTES_SIZE) {
+            "Transaction ID must be $JNI_TXID_BYTES_SIZE bytes"
+        }
+        require(expiryHeight == -1L || expiryHeight.isInUIntRange()) {
+            "Height $expiryHeight is outside of allowed UInt range"
+        }
+        require(!isExpired || expiryHeight != -1L) {
+            "A transaction without an expiry height cannot be expired"
+        }
+    }
+}
@@ -0,0 +1,75 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import cash.z.ecc.android.sdk.internal.jni.RustBackend
+import co.electriccoin.lightwallet.client.util.Disposable
+import kotlinx.coroutines.Dispatchers
+import kotlinx.coroutines.sync.Mutex
+import kotlinx.coroutines.sync.withLock
+import kotlinx.coroutines.withContext
+
+/**
+ * Reassembles a PCZT from the `ur:zcash-pczt` parts of an animated QR code, such as one displayed by an
+ * air-gapped signer. Parts can be received in any order, and repeated parts are ignored.
+ */
+class PcztUrDecoder private constructor(
+    private var nativeHandle: Long?,
+) : Disposable {
+    private val accessMutex = Mutex()
+
+    override suspend fun dispose() =
+        accessMutex.withLock {
+            withContext(Dispatchers.IO) {
+                nativeHandle?.let { freePcztUrDecoder(it) }
+                nativeHandle = null
+            }
+        }
+
+    /**
+     * Adds a scanned part to the decoder.
+     *
+     * @return the decoder's progress, including the reassembled PCZT once enough parts have been received.
+     *
+     * @throws RuntimeException if the part is not a valid `ur:zcash-pczt` part, or belongs to a different PCZT
+     */
+    @Throws(RuntimeException::class)
+    suspend fun receive(part: String): JniPcztUrProgress =
+        accessMutex.withLock {
+            withContext(Dispatchers.IO) {
+                checkNotNull(nativeHandle) { "PcztUrDecoder is disposed" }
+                receivePcztUrPart(nativeHandle!!, part)
+            }
+        }
+
+    companion object {
+        suspend fun new(): PcztUrDecoder =
+            withContext(Dispatchers.IO) {
+                RustBackend.loadLibrary()
+
+                PcztUrDecoder(createPcztUrDecoder())
+            }
+
+        //
+        // External Functions
+        //
+
+        /**
+         * @throws RuntimeException as a common indicator of the operation failure
+         */
+        @JvmStatic
+        @Throws(RuntimeException::class)
+        private external fun createPcztUrDecoder(): Long
+
+        @JvmStatic
+        private external fun freePcztUrDecoder(nativeHandle: Long)
+
+        /**
+         * @throws RuntimeException as a common indicator of the operation failure
+         */
+        @JvmStatic
+        @Throws(RuntimeException::class)
+        private external fun receivePcztUrPart(
+            nativeHandle: Long,
+            part: String
+        ): JniPcztUrProgress
+    }
+}
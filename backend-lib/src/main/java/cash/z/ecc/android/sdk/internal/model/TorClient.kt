@@ -100,6 +100,25 @@ class TorClient private constructor(
             }
         }
 
+    /**
+     * Downloads any of the given Sapling parameter files that are missing or corrupt over Tor. An interrupted
+     * download is resumed by the next call. Each file is verified against its known hash before being moved
+     * into place, so a partial or corrupt file is never used for proving.
+     */
+    suspend fun downloadSaplingParams(
+        spendParamsFile: File,
+        outputParamsFile: File
+    ) = accessMutex.withLock {
+        withContext(Dispatchers.IO) {
+            checkNotNull(nativeHandle) { "TorClient is disposed" }
+            downloadSaplingParams(
+                nativeHandle!!,
+                spendParamsFile.absolutePath,
+                outputParamsFile.absolutePath
+            )
+        }
+    }
+
     suspend fun getExchangeRateUsd(): BigDecimal =
         accessMutex.withLock {
             withContext(Dispatchers.IO) {
@@ -219,6 +238,17 @@ class TorClient private constructor(
         @Throws(RuntimeException::class)
         private external fun getExchangeRateUsd(nativeHandle: Long): BigDecimal
 
+        /**
+         * @throws RuntimeException as a common indicator of the operation failure
+         */
+        @JvmStatic
+        @Throws(RuntimeException::class)
+        private external fun downloadSaplingParams(
+            nativeHandle: Long,
+            spendParamsPath: String,
+            outputParamsPath: String
+        )
+
         /**
          * @throws RuntimeException as a common indicator of the operation failure
          */
@@ -117,6 +117,32 @@ class TorWalletClient private constructor(
             }
         }
 
+    /**
+     * Fetches up to [limit] compact blocks starting at [fromHeight] over this connection, and scans them without
+     * writing them to the block cache. Blocks above the server's chain tip are not fetched, so fewer than [limit]
+     * blocks are scanned when the range extends past it; [fromHeight] itself must not be above the tip.
+     *
+     * The blocks are held in memory while they are scanned, so [limit] must be between 1 and 1,000. Larger ranges
+     * should be scanned in batches.
+     */
+    suspend fun scanBlocks(
+        fromHeight: BlockHeightUnsafe,
+        fromState: ByteArray,
+        limit: Long
+    ): Response<JniScanSummary> =
+        backend.withWallet { dataDbFile, networkId ->
+            execute {
+                scanBlocks(
+                    it,
+                    dataDbFile.absolutePath,
+                    fromHeight.value,
+                    fromState,
+                    limit,
+                    networkId = networkId
+                )
+            }
+        }
+
     @Suppress("TooGenericExceptionCaught")
     private suspend fun <T> execute(
         block: (handle: Long) -> T
@@ -217,5 +243,20 @@ class TorWalletClient private constructor(
             accountUuid: ByteArray,
             address: String,
         ): JniAddressCheckResult
+
+        /**
+         * @throws RuntimeException as a common indicator of the operation failure
+         */
+        @JvmStatic
+        @Throws(RuntimeException::class)
+        @Suppress("LongParameterList")
+        private external fun scanBlocks(
+            nativeHandle: Long,
+            dbDataPath: String,
+            fromHeight: Long,
+            fromState: ByteArray,
+            limit: Long,
+            networkId: Int,
+        ): JniScanSummary
     }
 }
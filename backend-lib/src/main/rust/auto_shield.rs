@@ -0,0 +1,372 @@
+//! Automatic shielding policy.
+//!
+//! `proposeShielding` shields whatever is above a threshold when it is called, leaving the
+//! decision of when to call it to each app. This module instead decides, per transparent
+//! receiver, whether its funds are due to be shielded, so that every wallet built on the
+//! SDK shields the same way.
+//!
+//! Shielding funds from several receivers in one transaction links those receivers
+//! on-chain, and shielding as soon as funds arrive links the shielding transaction to the
+//! payment. The policy therefore supports shielding each receiver separately, and waiting
+//! a randomized number of blocks after the most recent receipt. The delay is derived from
+//! the account's viewing key, so that it is stable across evaluations but cannot be
+//! predicted by a chain observer.
+
+use anyhow::anyhow;
+use transparent::{address::TransparentAddress, bundle::OutPoint, keys::TransparentKeyScope};
+use zcash_client_backend::{
+    data_api::{Account, InputSource, WalletRead, WalletUtxo, wallet::ConfirmationsPolicy},
+    encoding::AddressCodec,
+};
+use zcash_client_sqlite::AccountUuid;
+use zcash_primitives::transaction::fees::zip317;
+use zcash_protocol::{
+    consensus::{BlockHeight, Network},
+    value::Zatoshis,
+};
+
+use crate::coin_control::Db;
+
+/// The personalization for deriving shielding delays.
+const DELAY_PERSONALIZATION: &[u8; 16] = b"Zcash_ShieldWait";
+
+/// When the funds held by a transparent receiver should be shielded.
+pub(crate) struct Policy {
+    /// The spendable value at which a receiver's funds are shielded.
+    pub(crate) min_value: Zatoshis,
+    /// The age in blocks at which a receiver's oldest UTXO is shielded regardless of
+    /// value or delay, as long as it is worth more than the fee to shield it.
+    pub(crate) max_utxo_age: Option<u32>,
+    /// Whether each receiver is shielded in its own transaction. Ephemeral receivers
+    /// are always shielded separately.
+    pub(crate) one_address_per_tx: bool,
+    /// The range from which the number of blocks to wait after the most recent receipt
+    /// is drawn.
+    pub(crate) min_delay: u32,
+    pub(crate) max_delay: u32,
+}
+
+/// A transparent receiver holding funds that could be shielded.
+struct Candidate {
+    address: TransparentAddress,
+    scope: TransparentKeyScope,
+    oldest: BlockHeight,
+}
+
+/// Returns the fee to shield the given number of UTXOs into a single Orchard output
+/// under ZIP 317.
+fn shielding_fee(utxo_count: usize) -> Option<Zatoshis> {
+    // An Orchard bundle with a single output is padded to two actions.
+    zip317::MARGINAL_FEE * (utxo_count + 2).max(zip317::GRACE_ACTIONS)
+}
+
+/// Returns the number of blocks to wait after a receiver's most recent receipt, which is
+/// identified by the outpoint of the UTXO it created.
+fn delay(policy: &Policy, viewing_key: &str, address: &str, receipt: &OutPoint) -> u32 {
+    if policy.max_delay <= policy.min_delay {
+        return policy.min_delay;
+    }
+
+    let hash = blake2b_simd::Params::new()
+        .hash_length(8)
+        .personal(DELAY_PERSONALIZATION)
+        .to_state()
+        .update(viewing_key.as_bytes())
+        .update(address.as_bytes())
+        .update(receipt.hash())
+        .update(&receipt.n().to_le_bytes())
+        .finalize();
+    let r = u64::from_le_bytes(hash.as_bytes().try_into().expect("hash length is 8"));
+    let range = u64::from(policy.max_delay - policy.min_delay) + 1;
+    policy.min_delay + u32::try_from(r % range).expect("less than max_delay")
+}
+
+/// Returns the groups of transparent receivers whose funds are due to be shielded now,
+/// where each group should be shielded in a separate transaction.
+pub(crate) fn due_receivers(
+    db_data: &Db,
+    network: &Network,
+    account_uuid: AccountUuid,
+    policy: &Policy,
+) -> anyhow::Result<Vec<Vec<TransparentAddress>>> {
+    let confirmations_policy = ConfirmationsPolicy::MIN;
+
+    let viewing_key = db_data
+        .get_account(account_uuid)?
+        .ok_or_else(|| anyhow!("Unknown account"))?
+        .ufvk()
+        .ok_or_else(|| anyhow!("Account has no viewing key"))?
+        .encode(network);
+    let target_height = db_data
+        .get_target_and_anchor_heights(confirmations_policy.untrusted())
+        .map_err(|e| anyhow!("Error while fetching anchor height: {}", e))?
+        .map(|(target, _)| target)
+        .ok_or_else(|| anyhow!("height not available; scan required."))?;
+    let tip = BlockHeight::from(target_height) - 1;
+
+    let mut due = vec![];
+    for (address, (scope, balance)) in db_data
+        .get_transparent_balances(account_uuid, target_height, confirmations_policy)
+        .map_err(|e| anyhow!("Error while fetching transparent balances: {}", e))?
+    {
+        let value = balance.spendable_value();
+        let utxos = db_data
+            .get_spendable_transparent_outputs(&address, target_height, confirmations_policy)
+            .map_err(|e| anyhow!("Error while fetching spendable UTXOs: {}", e))?;
+        if utxos.is_empty() || shielding_fee(utxos.len()).is_none_or(|fee| value <= fee) {
+            continue;
+        }
+
+        // Unmined UTXOs are treated as received at the chain tip.
+        let height = |utxo: &WalletUtxo| utxo.mined_height().unwrap_or(tip).min(tip);
+        let oldest = utxos.iter().map(height).min().expect("utxos is nonempty");
+        let newest = utxos
+            .iter()
+            .max_by_key(|utxo| height(utxo))
+            .expect("utxos is nonempty");
+
+        let aged_out = policy
+            .max_utxo_age
+            .is_some_and(|max_age| tip - oldest >= max_age);
+        let wait = delay(
+            policy,
+            &viewing_key,
+            &address.encode(network),
+            newest.outpoint(),
+        );
+        let waited = tip - height(newest) >= wait;
+
+        if aged_out || (value >= policy.min_value && waited) {
+            due.push(Candidate {
+                address,
+                scope,
+                oldest,
+            });
+        }
+    }
+
+    // Shield the receivers that have waited longest first.
+    due.sort_by_key(|c| c.oldest);
+
+    let (ephemeral, non_ephemeral): (Vec<_>, Vec<_>) = due
+        .into_iter()
+        .partition(|c| c.scope == TransparentKeyScope::EPHEMERAL);
+
+    let mut groups = vec![];
+    if policy.one_address_per_tx {
+        groups.extend(non_ephemeral.into_iter().map(|c| vec![c.address]));
+    } else if !non_ephemeral.is_empty() {
+        groups.push(non_ephemeral.into_iter().map(|c| c.address).collect());
+    }
+    groups.extend(ephemeral.into_iter().map(|c| vec![c.address]));
+
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use rusqlite::{Connection, named_params};
+    use zcash_client_backend::data_api::WalletWrite;
+    use zcash_client_sqlite::{WalletDb, util::SystemClock};
+
+    use super::*;
+    use crate::testing::{NETWORK, add_account, insert_tx, insert_utxo, wallet_db};
+
+    /// The external and ephemeral key scopes, as stored in the `addresses` table.
+    const EXTERNAL: u32 = 0;
+    const EPHEMERAL: u32 = 2;
+
+    /// The chain tip of the wallets returned by [`scanned`].
+    const TIP: u32 = 3_000_100;
+
+    fn policy(min_delay: u32, max_delay: u32) -> Policy {
+        Policy {
+            min_value: Zatoshis::ZERO,
+            max_utxo_age: None,
+            one_address_per_tx: true,
+            min_delay,
+            max_delay,
+        }
+    }
+
+    #[test]
+    fn delay_is_stable_and_within_window() {
+        let policy = policy(10, 20);
+        for n in 0..100 {
+            let receipt = OutPoint::new([7; 32], n);
+            let d = delay(&policy, "uview", "t1address", &receipt);
+            assert!((10..=20).contains(&d));
+            assert_eq!(d, delay(&policy, "uview", "t1address", &receipt));
+        }
+    }
+
+    #[test]
+    fn empty_window_uses_min_delay() {
+        let receipt = OutPoint::new([0; 32], 0);
+        assert_eq!(delay(&policy(5, 5), "uview", "t1address", &receipt), 5);
+    }
+
+    #[test]
+    fn shielding_fee_covers_padded_orchard_actions() {
+        assert_eq!(shielding_fee(1), Some(Zatoshis::const_from_u64(15_000)));
+        assert_eq!(shielding_fee(3), Some(Zatoshis::const_from_u64(25_000)));
+    }
+
+    /// Returns the `n`th transparent address of the account in the given key scope.
+    fn address(conn: &Connection, key_scope: u32, n: u32) -> String {
+        conn.query_row(
+            "SELECT cached_transparent_receiver_address FROM addresses
+             WHERE key_scope = :key_scope AND cached_transparent_receiver_address IS NOT NULL
+             ORDER BY id LIMIT 1 OFFSET :n",
+            named_params! {":key_scope": key_scope, ":n": n},
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    /// Adds a UTXO of the given value received at `address` in the transaction with ID
+    /// `txid(txid_byte)`, mined at `height`.
+    fn receive(
+        conn: &Connection,
+        account: AccountUuid,
+        address: &str,
+        txid_byte: u8,
+        height: u32,
+        value: u64,
+    ) {
+        let tx = insert_tx(conn, txid_byte, Some(height), 0);
+        insert_utxo(conn, tx, 0, account, address, value);
+    }
+
+    /// Returns a wallet with an account and no funds, that has an anchor once it is
+    /// [`scanned`].
+    fn account_db() -> (Connection, AccountUuid) {
+        let mut conn = wallet_db();
+        let account = add_account(&mut conn, 1, 3_000_000);
+        conn.execute(
+            "INSERT INTO sapling_tree_checkpoints (checkpoint_id) VALUES (:height)",
+            named_params! {":height": TIP - 1},
+        )
+        .unwrap();
+        (conn, account)
+    }
+
+    fn scanned(conn: Connection) -> Db {
+        let mut db_data = WalletDb::from_connection(conn, NETWORK, SystemClock, OsRng);
+        db_data
+            .update_chain_tip(BlockHeight::from_u32(TIP))
+            .unwrap();
+        db_data
+    }
+
+    fn due(db_data: &Db, account: AccountUuid, policy: &Policy) -> Vec<Vec<String>> {
+        due_receivers(db_data, &NETWORK, account, policy)
+            .unwrap()
+            .into_iter()
+            .map(|group| group.iter().map(|a| a.encode(&NETWORK)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn receivers_above_min_value_wait_for_the_delay() {
+        let (conn, account) = account_db();
+        let (waiting, waited, small) = (
+            address(&conn, EXTERNAL, 0),
+            address(&conn, EXTERNAL, 1),
+            address(&conn, EXTERNAL, 2),
+        );
+        receive(&conn, account, &waiting, 1, TIP - 5, 200_000);
+        receive(&conn, account, &waited, 2, TIP - 10, 200_000);
+        receive(&conn, account, &small, 3, TIP - 50, 50_000);
+
+        let policy = Policy {
+            min_value: Zatoshis::const_from_u64(100_000),
+            ..policy(10, 10)
+        };
+        assert_eq!(due(&scanned(conn), account, &policy), vec![vec![waited]]);
+    }
+
+    #[test]
+    fn old_utxos_are_shielded_regardless_of_value_and_delay() {
+        let (conn, account) = account_db();
+        let (recent, small, young) = (
+            address(&conn, EXTERNAL, 0),
+            address(&conn, EXTERNAL, 1),
+            address(&conn, EXTERNAL, 2),
+        );
+        // A recent receipt does not delay the shielding of an old UTXO.
+        receive(&conn, account, &recent, 1, TIP - 60, 100_000);
+        receive(&conn, account, &recent, 2, TIP - 1, 100_000);
+        receive(&conn, account, &small, 3, TIP - 50, 50_000);
+        receive(&conn, account, &young, 4, TIP - 49, 50_000);
+
+        let policy = Policy {
+            min_value: Zatoshis::const_from_u64(1_000_000),
+            max_utxo_age: Some(50),
+            ..policy(10, 10)
+        };
+        // Receivers are ordered by the height of their oldest UTXO.
+        assert_eq!(
+            due(&scanned(conn), account, &policy),
+            vec![vec![recent], vec![small]]
+        );
+    }
+
+    #[test]
+    fn receivers_not_worth_the_fee_are_skipped() {
+        let (conn, account) = account_db();
+        let (single, split, enough) = (
+            address(&conn, EXTERNAL, 0),
+            address(&conn, EXTERNAL, 1),
+            address(&conn, EXTERNAL, 2),
+        );
+        // Shielding one UTXO costs 15000 zatoshis, and each further UTXO 5000 more.
+        receive(&conn, account, &single, 1, TIP - 50, 15_000);
+        receive(&conn, account, &split, 2, TIP - 50, 10_000);
+        receive(&conn, account, &split, 3, TIP - 50, 10_000);
+        receive(&conn, account, &enough, 4, TIP - 50, 15_001);
+
+        let policy = Policy {
+            max_utxo_age: Some(50),
+            ..policy(0, 0)
+        };
+        assert_eq!(due(&scanned(conn), account, &policy), vec![vec![enough]]);
+    }
+
+    #[test]
+    fn receivers_are_grouped_by_policy_and_ephemeral_receivers_alone() {
+        let (conn, account) = account_db();
+        let (older, newer, ephemeral_a, ephemeral_b) = (
+            address(&conn, EXTERNAL, 0),
+            address(&conn, EXTERNAL, 1),
+            address(&conn, EPHEMERAL, 0),
+            address(&conn, EPHEMERAL, 1),
+        );
+        receive(&conn, account, &newer, 1, TIP - 20, 100_000);
+        receive(&conn, account, &ephemeral_a, 2, TIP - 40, 100_000);
+        receive(&conn, account, &older, 3, TIP - 30, 100_000);
+        receive(&conn, account, &ephemeral_b, 4, TIP - 10, 100_000);
+
+        let db_data = scanned(conn);
+
+        assert_eq!(
+            due(&db_data, account, &policy(0, 0)),
+            vec![
+                vec![older.clone()],
+                vec![newer.clone()],
+                vec![ephemeral_a.clone()],
+                vec![ephemeral_b.clone()],
+            ]
+        );
+
+        let single_group = Policy {
+            one_address_per_tx: false,
+            ..policy(0, 0)
+        };
+        assert_eq!(
+            due(&db_data, account, &single_group),
+            vec![vec![older, newer], vec![ephemeral_a], vec![ephemeral_b]]
+        );
+    }
+}
@@ -0,0 +1,424 @@
+//! Historical balances.
+//!
+//! `get_wallet_summary` only reports the current balance. This module reconstructs an
+//! account's balance in each pool as of past heights, by replaying the outputs it received
+//! and spent in mined transactions. Unmined transactions are ignored, so the balances
+//! reflect only what the chain had confirmed at each height.
+
+use anyhow::anyhow;
+use rusqlite::{Connection, OptionalExtension, named_params};
+use zcash_client_sqlite::AccountUuid;
+use zcash_protocol::{PoolType, ShieldedProtocol, consensus::BlockHeight, value::Zatoshis};
+
+use crate::parse_pool;
+
+/// The maximum number of samples in a balance series.
+const MAX_SAMPLES: usize = 10_000;
+
+const SECONDS_PER_DAY: u32 = 86_400;
+
+/// How a balance series is sampled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Interval {
+    /// Every given number of blocks, and at the end of the range.
+    Blocks(u32),
+    /// At the last scanned block of each UTC day.
+    Days,
+}
+
+/// Running balances of each pool, which may be transiently negative while the changes in
+/// a block are applied.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct PoolBalances {
+    transparent: i64,
+    sapling: i64,
+    orchard: i64,
+}
+
+impl PoolBalances {
+    fn apply(&mut self, pool: PoolType, delta: i64) {
+        let balance = match pool {
+            PoolType::Transparent => &mut self.transparent,
+            PoolType::Shielded(ShieldedProtocol::Sapling) => &mut self.sapling,
+            PoolType::Shielded(ShieldedProtocol::Orchard) => &mut self.orchard,
+        };
+        *balance += delta;
+    }
+}
+
+/// An account's balance in each pool as of a height.
+pub(crate) struct HistoricalBalance {
+    pub(crate) height: BlockHeight,
+    /// The time of the block at `height`, if it has been scanned.
+    pub(crate) block_time: Option<u32>,
+    pub(crate) transparent: Zatoshis,
+    pub(crate) sapling: Zatoshis,
+    pub(crate) orchard: Zatoshis,
+}
+
+impl HistoricalBalance {
+    fn new(
+        height: BlockHeight,
+        block_time: Option<u32>,
+        balances: PoolBalances,
+    ) -> anyhow::Result<Self> {
+        let zatoshis = |value: i64| {
+            Zatoshis::from_nonnegative_i64(value)
+                .map_err(|_| anyhow!("Invalid balance {} at height {}", value, height))
+        };
+        Ok(HistoricalBalance {
+            height,
+            block_time,
+            transparent: zatoshis(balances.transparent)?,
+            sapling: zatoshis(balances.sapling)?,
+            orchard: zatoshis(balances.orchard)?,
+        })
+    }
+}
+
+/// A change to the balance of a pool in a mined block.
+struct Delta {
+    height: BlockHeight,
+    pool: PoolType,
+    value: i64,
+}
+
+/// Returns the changes to the account's balances in blocks up to and including
+/// `to_height`, in height order.
+fn deltas(
+    conn: &Connection,
+    account_uuid: AccountUuid,
+    to_height: BlockHeight,
+) -> anyhow::Result<Vec<Delta>> {
+    let mut stmt = conn.prepare(
+        "SELECT t.mined_height AS height, ro.pool, ro.value AS delta
+         FROM v_received_outputs ro
+         JOIN transactions t ON t.id_tx = ro.transaction_id
+         JOIN accounts a ON a.id = ro.account_id
+         WHERE a.uuid = :account_uuid
+         AND t.mined_height <= :to_height
+         UNION ALL
+         SELECT st.mined_height AS height, ro.pool, -ro.value AS delta
+         FROM v_received_outputs ro
+         JOIN transactions t ON t.id_tx = ro.transaction_id
+         JOIN accounts a ON a.id = ro.account_id
+         JOIN v_received_output_spends s
+            ON s.pool = ro.pool AND s.received_output_id = ro.id_within_pool_table
+         JOIN transactions st ON st.id_tx = s.transaction_id
+         WHERE a.uuid = :account_uuid
+         AND t.mined_height IS NOT NULL
+         AND st.mined_height <= :to_height
+         ORDER BY height",
+    )?;
+
+    stmt.query_and_then(
+        named_params! {
+            ":account_uuid": account_uuid.expose_uuid().as_bytes(),
+            ":to_height": u32::from(to_height),
+        },
+        |row| -> anyhow::Result<_> {
+            Ok(Delta {
+                height: BlockHeight::from_u32(row.get("height")?),
+                pool: parse_pool(row.get("pool")?)?,
+                value: row.get("delta")?,
+            })
+        },
+    )?
+    .collect()
+}
+
+/// Returns the account's balances as of each of the given heights, which must be in
+/// ascending order.
+fn balances_at(deltas: &[Delta], heights: &[BlockHeight]) -> Vec<PoolBalances> {
+    let mut balances = PoolBalances::default();
+    let mut deltas = deltas.iter().peekable();
+    heights
+        .iter()
+        .map(|height| {
+            while let Some(delta) = deltas.next_if(|d| d.height <= *height) {
+                balances.apply(delta.pool, delta.value);
+            }
+            balances
+        })
+        .collect()
+}
+
+/// Returns the heights at which a series over the given range is sampled every
+/// `interval` blocks.
+fn block_samples(
+    from_height: BlockHeight,
+    to_height: BlockHeight,
+    interval: u32,
+) -> anyhow::Result<Vec<BlockHeight>> {
+    if interval == 0 {
+        return Err(anyhow!("Sampling interval must be at least one block"));
+    }
+    let (from, to) = (u32::from(from_height), u32::from(to_height));
+    let count = (to - from) / interval + 1;
+    if !usize::try_from(count).is_ok_and(|c| c <= MAX_SAMPLES) {
+        return Err(anyhow!(
+            "A balance series is limited to {} samples",
+            MAX_SAMPLES
+        ));
+    }
+
+    let mut heights = (from..=to)
+        .step_by(interval as usize)
+        .map(BlockHeight::from_u32)
+        .collect::<Vec<_>>();
+    if heights.last() != Some(&to_height) {
+        heights.push(to_height);
+    }
+    Ok(heights)
+}
+
+/// Returns the heights and times of the last scanned block of each UTC day within the
+/// given range.
+fn day_samples(
+    conn: &Connection,
+    from_height: BlockHeight,
+    to_height: BlockHeight,
+) -> anyhow::Result<Vec<(BlockHeight, Option<u32>)>> {
+    // SQLite takes the bare `time` column from the row with the maximum height.
+    let mut stmt = conn.prepare(
+        "SELECT MAX(height) AS height, time
+         FROM blocks
+         WHERE height BETWEEN :from_height AND :to_height
+         GROUP BY time / :seconds_per_day
+         ORDER BY height
+         LIMIT :limit",
+    )?;
+
+    stmt.query_and_then(
+        named_params! {
+            ":from_height": u32::from(from_height),
+            ":to_height": u32::from(to_height),
+            ":seconds_per_day": SECONDS_PER_DAY,
+            ":limit": MAX_SAMPLES + 1,
+        },
+        |row| -> anyhow::Result<_> {
+            Ok((BlockHeight::from_u32(row.get("height")?), row.get("time")?))
+        },
+    )?
+    .collect::<anyhow::Result<Vec<_>>>()
+    .and_then(|samples| {
+        if samples.len() > MAX_SAMPLES {
+            Err(anyhow!(
+                "A balance series is limited to {} samples",
+                MAX_SAMPLES
+            ))
+        } else {
+            Ok(samples)
+        }
+    })
+}
+
+/// Returns the time of the block at the given height, if it has been scanned.
+fn block_time(conn: &Connection, height: BlockHeight) -> anyhow::Result<Option<u32>> {
+    Ok(conn
+        .query_row(
+            "SELECT time FROM blocks WHERE height = :height",
+            named_params! {":height": u32::from(height)},
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// Returns an error if the wallet has not scanned every block up to and including
+/// `height`, as balances at such a height would miss outputs in the unscanned blocks.
+fn ensure_scanned(height: BlockHeight, fully_scanned: Option<BlockHeight>) -> anyhow::Result<()> {
+    match fully_scanned {
+        Some(fully_scanned) if height <= fully_scanned => Ok(()),
+        Some(fully_scanned) => Err(anyhow!(
+            "Height {} is above the fully scanned height {}",
+            height,
+            fully_scanned
+        )),
+        None => Err(anyhow!("No blocks have been fully scanned")),
+    }
+}
+
+/// Returns the account's balance in each pool as of the given height, which must not be
+/// above `fully_scanned`, the height up to which the wallet has scanned every block.
+pub(crate) fn balance_at(
+    conn: &Connection,
+    account_uuid: AccountUuid,
+    height: BlockHeight,
+    fully_scanned: Option<BlockHeight>,
+) -> anyhow::Result<HistoricalBalance> {
+    ensure_scanned(height, fully_scanned)?;
+    let deltas = deltas(conn, account_uuid, height)?;
+    let balances = balances_at(&deltas, &[height])[0];
+    HistoricalBalance::new(height, block_time(conn, height)?, balances)
+}
+
+/// Returns the account's balance in each pool sampled over the given range of heights,
+/// which must not extend above `fully_scanned`, the height up to which the wallet has
+/// scanned every block.
+pub(crate) fn balance_series(
+    conn: &Connection,
+    account_uuid: AccountUuid,
+    from_height: BlockHeight,
+    to_height: BlockHeight,
+    interval: Interval,
+    fully_scanned: Option<BlockHeight>,
+) -> anyhow::Result<Vec<HistoricalBalance>> {
+    if from_height > to_height {
+        return Err(anyhow!(
+            "Invalid height range {}..={}",
+            from_height,
+            to_height
+        ));
+    }
+    ensure_scanned(to_height, fully_scanned)?;
+
+    let samples = match interval {
+        Interval::Blocks(n) => block_samples(from_height, to_height, n)?
+            .into_iter()
+            .map(|height| Ok((height, block_time(conn, height)?)))
+            .collect::<anyhow::Result<Vec<_>>>()?,
+        Interval::Days => day_samples(conn, from_height, to_height)?,
+    };
+    let heights = samples.iter().map(|(h, _)| *h).collect::<Vec<_>>();
+
+    let deltas = deltas(conn, account_uuid, to_height)?;
+    samples
+        .into_iter()
+        .zip(balances_at(&deltas, &heights))
+        .map(|((height, block_time), balances)| {
+            HistoricalBalance::new(height, block_time, balances)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        add_account, insert_block, insert_sapling_note, insert_tx, insert_utxo, spend_sapling_note,
+        wallet_db,
+    };
+
+    fn delta(height: u32, pool: PoolType, value: i64) -> Delta {
+        Delta {
+            height: BlockHeight::from_u32(height),
+            pool,
+            value,
+        }
+    }
+
+    #[test]
+    fn balances_include_deltas_up_to_each_height() {
+        let deltas = [
+            delta(10, PoolType::SAPLING, 500),
+            delta(12, PoolType::ORCHARD, 300),
+            delta(12, PoolType::SAPLING, -500),
+            delta(20, PoolType::TRANSPARENT, 7),
+        ];
+        let heights = [9, 10, 12, 19, 20].map(BlockHeight::from_u32);
+
+        let balances = balances_at(&deltas, &heights);
+        let sapling = balances.iter().map(|b| b.sapling).collect::<Vec<_>>();
+        let orchard = balances.iter().map(|b| b.orchard).collect::<Vec<_>>();
+        let transparent = balances.iter().map(|b| b.transparent).collect::<Vec<_>>();
+        assert_eq!(sapling, [0, 500, 0, 0, 0]);
+        assert_eq!(orchard, [0, 0, 300, 300, 300]);
+        assert_eq!(transparent, [0, 0, 0, 0, 7]);
+    }
+
+    #[test]
+    fn block_samples_end_at_range_end() {
+        let samples = block_samples(BlockHeight::from_u32(100), BlockHeight::from_u32(125), 10)
+            .unwrap()
+            .into_iter()
+            .map(u32::from)
+            .collect::<Vec<_>>();
+        assert_eq!(samples, [100, 110, 120, 125]);
+    }
+
+    #[test]
+    fn block_samples_are_limited() {
+        let from = BlockHeight::from_u32(0);
+        assert!(block_samples(from, BlockHeight::from_u32(1_000_000), 1).is_err());
+        assert!(block_samples(from, BlockHeight::from_u32(1_000_000), 0).is_err());
+    }
+
+    #[test]
+    fn deltas_replay_mined_receipts_and_spends() {
+        let mut conn = wallet_db();
+        let account = add_account(&mut conn, 1, 3_000_000);
+        let other = add_account(&mut conn, 2, 3_000_000);
+
+        let received = insert_tx(&conn, 1, Some(3_000_010), 3_000_050);
+        let note = insert_sapling_note(&conn, received, 0, account, 50_000, false, None);
+        insert_sapling_note(&conn, received, 1, other, 70_000, false, None);
+        insert_utxo(&conn, received, 2, account, "tA", 30_000);
+
+        let spend = insert_tx(&conn, 2, Some(3_000_020), 3_000_060);
+        spend_sapling_note(&conn, note, spend);
+        insert_sapling_note(&conn, spend, 0, account, 40_000, true, None);
+
+        // Neither unmined receipts nor unmined spends count.
+        let unmined = insert_tx(&conn, 3, None, 3_000_200);
+        insert_sapling_note(&conn, unmined, 0, account, 90_000, false, None);
+        let spent_unmined = insert_sapling_note(&conn, received, 3, account, 20_000, false, None);
+        spend_sapling_note(&conn, spent_unmined, unmined);
+
+        let summarize = |to_height| {
+            deltas(&conn, account, BlockHeight::from_u32(to_height))
+                .unwrap()
+                .into_iter()
+                .map(|d| (u32::from(d.height), d.pool, d.value))
+                .collect::<Vec<_>>()
+        };
+        let mut all = summarize(3_000_100);
+        all.sort();
+        assert_eq!(
+            all,
+            [
+                (3_000_010, PoolType::TRANSPARENT, 30_000),
+                (3_000_010, PoolType::SAPLING, 20_000),
+                (3_000_010, PoolType::SAPLING, 50_000),
+                (3_000_020, PoolType::SAPLING, -50_000),
+                (3_000_020, PoolType::SAPLING, 40_000),
+            ]
+        );
+        assert_eq!(summarize(3_000_019).len(), 3);
+        assert!(summarize(3_000_009).is_empty());
+    }
+
+    #[test]
+    fn balances_require_scanned_heights() {
+        let mut conn = wallet_db();
+        let account = add_account(&mut conn, 1, 3_000_000);
+        let received = insert_tx(&conn, 1, Some(3_000_010), 3_000_050);
+        insert_sapling_note(&conn, received, 0, account, 50_000, false, None);
+        insert_block(&conn, 3_000_010, 1_700_000_000);
+        let height = |h| BlockHeight::from_u32(h);
+
+        let balance =
+            balance_at(&conn, account, height(3_000_010), Some(height(3_000_010))).unwrap();
+        assert_eq!(u64::from(balance.sapling), 50_000);
+        assert_eq!(balance.block_time, Some(1_700_000_000));
+
+        let err = balance_at(&conn, account, height(3_000_011), Some(height(3_000_010)))
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Height 3000011 is above the fully scanned height 3000010"
+        );
+        assert!(balance_at(&conn, account, height(3_000_010), None).is_err());
+        assert!(
+            balance_series(
+                &conn,
+                account,
+                height(3_000_000),
+                height(3_000_011),
+                Interval::Blocks(1),
+                Some(height(3_000_010)),
+            )
+            .is_err()
+        );
+    }
+}
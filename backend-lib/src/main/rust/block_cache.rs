@@ -0,0 +1,367 @@
+//! Maintenance of the `FsBlockDb` block cache.
+//!
+//! `FsBlockDb` stores each compact block in its own file under `blocks/`, indexed by the
+//! `compactblocks_meta` table in `blockmeta.sqlite`. It can add and truncate metadata,
+//! but never removes block files that have been scanned, and does not check that the
+//! files it indexes are still present and intact. This module reads the same layout
+//! directly to do so.
+
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use prost::Message;
+use rusqlite::{Connection, OpenFlags, named_params};
+use tracing::debug;
+use zcash_client_backend::proto::compact_formats::CompactBlock;
+use zcash_client_sqlite::chain::BlockMeta;
+use zcash_primitives::block::BlockHash;
+use zcash_protocol::consensus::BlockHeight;
+
+/// The metadata database within an `FsBlockDb` root, as named by `FsBlockDb::for_path`.
+const BLOCKMETA_DB: &str = "blockmeta.sqlite";
+
+/// The directory of block files within an `FsBlockDb` root.
+const BLOCKS_DIR: &str = "blocks";
+
+/// The result of checking every block in the cache.
+#[derive(Debug, Default)]
+pub(crate) struct Report {
+    pub(crate) block_count: u64,
+    /// The range of cached heights, if the cache is not empty.
+    pub(crate) heights: Option<Range<BlockHeight>>,
+    /// Blocks whose files are missing or unreadable.
+    pub(crate) missing: Vec<BlockHeight>,
+    /// Blocks whose files do not decode to the block the metadata describes.
+    pub(crate) corrupt: Vec<BlockHeight>,
+    /// Ranges of heights within `heights` that have no cached block.
+    pub(crate) holes: Vec<Range<BlockHeight>>,
+    /// The size on disk of the block files and metadata database.
+    pub(crate) total_bytes: u64,
+}
+
+/// Opens the metadata database of an existing cache. It is never created, so that a
+/// wrong root is reported as such rather than as an empty cache.
+fn open_meta(root: &Path, flags: OpenFlags) -> anyhow::Result<Connection> {
+    let path = root.join(BLOCKMETA_DB);
+    Connection::open_with_flags(&path, flags).map_err(|e| {
+        anyhow!(
+            "Unable to open block cache metadata {}: {}",
+            path.display(),
+            e
+        )
+    })
+}
+
+/// Returns the metadata of the cached blocks with heights in the given range, in height
+/// order.
+fn block_meta(conn: &Connection, range: Range<BlockHeight>) -> anyhow::Result<Vec<BlockMeta>> {
+    let mut stmt = conn.prepare(
+        "SELECT height, blockhash, time, sapling_outputs_count, orchard_actions_count
+         FROM compactblocks_meta
+         WHERE height >= :start AND height < :end
+         ORDER BY height",
+    )?;
+
+    stmt.query_and_then(
+        named_params! {
+            ":start": u32::from(range.start),
+            ":end": u32::from(range.end),
+        },
+        |row| -> anyhow::Result<_> {
+            Ok(BlockMeta {
+                height: BlockHeight::from_u32(row.get("height")?),
+                block_hash: BlockHash::try_from_slice(&row.get::<_, Vec<u8>>("blockhash")?)
+                    .ok_or_else(|| anyhow!("Invalid block hash in block metadata"))?,
+                block_time: row.get("time")?,
+                sapling_outputs_count: row.get("sapling_outputs_count")?,
+                orchard_actions_count: row.get("orchard_actions_count")?,
+            })
+        },
+    )?
+    .collect()
+}
+
+fn block_path(root: &Path, meta: &BlockMeta) -> PathBuf {
+    meta.block_file_path(&root.join(BLOCKS_DIR))
+}
+
+/// Removes the cached blocks below the given height, returning the number removed.
+///
+/// The metadata is removed before the files, so that an interruption can leave behind
+/// unindexed files but never metadata for missing files.
+pub(crate) fn prune_below(root: &Path, height: BlockHeight) -> anyhow::Result<u64> {
+    let mut conn = open_meta(
+        root,
+        OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    let pruned = block_meta(&conn, BlockHeight::from_u32(0)..height)?;
+
+    let tx = conn.transaction()?;
+    tx.execute(
+        "DELETE FROM compactblocks_meta WHERE height < :height",
+        named_params! {":height": u32::from(height)},
+    )?;
+    tx.commit()?;
+
+    for meta in &pruned {
+        match fs::remove_file(block_path(root, meta)) {
+            Ok(()) => (),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (),
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    debug!("Pruned {} cached blocks below {}", pruned.len(), height);
+    Ok(pruned.len() as u64)
+}
+
+/// Returns whether the contents of a block file are the block described by `meta`.
+///
+/// The raw fields are compared, because `CompactBlock::height` and `CompactBlock::hash`
+/// panic on the out-of-range values a corrupt file may hold.
+fn is_intact(meta: &BlockMeta, contents: &[u8]) -> bool {
+    CompactBlock::decode(contents).is_ok_and(|block| {
+        block.height == u64::from(u32::from(meta.height)) && block.hash == meta.block_hash.0
+    })
+}
+
+/// Returns the ranges of heights missing between consecutive cached blocks.
+fn holes(heights: impl IntoIterator<Item = BlockHeight>) -> Vec<Range<BlockHeight>> {
+    let mut holes = vec![];
+    let mut prev: Option<BlockHeight> = None;
+    for height in heights {
+        if let Some(prev) = prev
+            && height > prev + 1
+        {
+            holes.push((prev + 1)..height);
+        }
+        prev = Some(height);
+    }
+    holes
+}
+
+/// Returns the total size of the files in the given directory, or zero if it does not
+/// exist.
+fn dir_size(dir: &Path) -> io::Result<u64> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+
+    let mut size = 0;
+    for entry in entries {
+        let metadata = entry?.metadata()?;
+        if metadata.is_file() {
+            size += metadata.len();
+        }
+    }
+    Ok(size)
+}
+
+/// Checks that every cached block has a readable file holding the block its metadata
+/// describes, and reports gaps in the cached heights and the size of the cache.
+pub(crate) fn verify(root: &Path) -> anyhow::Result<Report> {
+    let conn = open_meta(
+        root,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    let metas = block_meta(
+        &conn,
+        BlockHeight::from_u32(0)..BlockHeight::from_u32(u32::MAX),
+    )?;
+
+    let mut report = Report {
+        block_count: metas.len() as u64,
+        heights: metas
+            .first()
+            .zip(metas.last())
+            .map(|(first, last)| first.height..(last.height + 1)),
+        holes: holes(metas.iter().map(|meta| meta.height)),
+        ..Default::default()
+    };
+
+    for meta in &metas {
+        match fs::read(block_path(root, meta)) {
+            Ok(contents) if is_intact(meta, &contents) => (),
+            Ok(_) => report.corrupt.push(meta.height),
+            Err(_) => report.missing.push(meta.height),
+        }
+    }
+
+    // Include SQLite's journal and WAL files alongside the metadata database.
+    report.total_bytes = dir_size(&root.join(BLOCKS_DIR))?
+        + fs::read_dir(root)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(BLOCKMETA_DB)
+            })
+            .filter_map(|entry| entry.metadata().ok())
+            .map(|metadata| metadata.len())
+            .sum::<u64>();
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use zcash_client_sqlite::{FsBlockDb, chain::init::init_blockmeta_db};
+
+    use super::*;
+
+    /// A block cache in a temporary directory, which is removed when dropped.
+    struct TestCache {
+        root: PathBuf,
+    }
+
+    impl TestCache {
+        fn new(name: &str) -> Self {
+            let root =
+                std::env::temp_dir().join(format!("block_cache_{}_{}", name, std::process::id()));
+            fs::create_dir_all(&root).unwrap();
+            init_blockmeta_db(&mut FsBlockDb::for_path(&root).unwrap()).unwrap();
+            TestCache { root }
+        }
+
+        /// Writes the files and metadata of blocks at the given heights.
+        fn add_blocks(&self, hs: &[u32]) {
+            let metas = hs
+                .iter()
+                .map(|h| {
+                    let meta = BlockMeta {
+                        height: BlockHeight::from_u32(*h),
+                        block_hash: BlockHash([*h as u8; 32]),
+                        block_time: 0,
+                        sapling_outputs_count: 0,
+                        orchard_actions_count: 0,
+                    };
+                    let block = CompactBlock {
+                        height: (*h).into(),
+                        hash: meta.block_hash.0.to_vec(),
+                        ..Default::default()
+                    };
+                    fs::write(block_path(&self.root, &meta), block.encode_to_vec()).unwrap();
+                    meta
+                })
+                .collect::<Vec<_>>();
+            FsBlockDb::for_path(&self.root)
+                .unwrap()
+                .write_block_metadata(&metas)
+                .unwrap();
+        }
+
+        fn block_file(&self, h: u32) -> PathBuf {
+            let metas = block_meta(
+                &open_meta(&self.root, OpenFlags::SQLITE_OPEN_READ_ONLY).unwrap(),
+                BlockHeight::from_u32(h)..BlockHeight::from_u32(h + 1),
+            )
+            .unwrap();
+            block_path(&self.root, &metas[0])
+        }
+    }
+
+    impl Drop for TestCache {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    #[test]
+    fn verify_reports_missing_corrupt_and_holes() {
+        let cache = TestCache::new("verify");
+        cache.add_blocks(&[10, 11, 12, 14, 15]);
+        fs::remove_file(cache.block_file(11)).unwrap();
+        fs::write(cache.block_file(14), [0xff, 0xff]).unwrap();
+
+        let report = verify(&cache.root).unwrap();
+        assert_eq!(report.block_count, 5);
+        assert_eq!(
+            report.heights,
+            Some(BlockHeight::from_u32(10)..BlockHeight::from_u32(16))
+        );
+        assert_eq!(report.missing, heights(&[11]));
+        assert_eq!(report.corrupt, heights(&[14]));
+        assert_eq!(
+            report.holes,
+            [BlockHeight::from_u32(13)..BlockHeight::from_u32(14)]
+        );
+        assert!(report.total_bytes > 0);
+    }
+
+    #[test]
+    fn prune_removes_blocks_below_height() {
+        let cache = TestCache::new("prune");
+        cache.add_blocks(&[10, 11, 12]);
+        let pruned_file = cache.block_file(10);
+
+        assert_eq!(
+            prune_below(&cache.root, BlockHeight::from_u32(12)).unwrap(),
+            2
+        );
+        assert!(!pruned_file.exists());
+
+        let report = verify(&cache.root).unwrap();
+        assert_eq!(report.block_count, 1);
+        assert!(report.missing.is_empty());
+    }
+
+    #[test]
+    fn missing_cache_is_not_created() {
+        let root = std::env::temp_dir().join(format!("block_cache_missing_{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+
+        assert!(verify(&root).is_err());
+        assert!(prune_below(&root, BlockHeight::from_u32(1)).is_err());
+        assert!(!root.join(BLOCKMETA_DB).exists());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    fn heights(hs: &[u32]) -> Vec<BlockHeight> {
+        hs.iter().copied().map(BlockHeight::from_u32).collect()
+    }
+
+    #[test]
+    fn holes_are_reported_between_cached_blocks() {
+        let holes = holes(heights(&[10, 11, 14, 15, 17]))
+            .into_iter()
+            .map(|r| (u32::from(r.start), u32::from(r.end)))
+            .collect::<Vec<_>>();
+        assert_eq!(holes, [(12, 14), (16, 17)]);
+    }
+
+    #[test]
+    fn contiguous_blocks_have_no_holes() {
+        assert!(holes(heights(&[5, 6, 7])).is_empty());
+        assert!(holes(vec![]).is_empty());
+    }
+
+    #[test]
+    fn intact_block_matches_metadata() {
+        let meta = BlockMeta {
+            height: BlockHeight::from_u32(7),
+            block_hash: BlockHash([3; 32]),
+            block_time: 0,
+            sapling_outputs_count: 0,
+            orchard_actions_count: 0,
+        };
+        let block = CompactBlock {
+            height: 7,
+            hash: vec![3; 32],
+            ..Default::default()
+        };
+        assert!(is_intact(&meta, &block.encode_to_vec()));
+
+        let other = CompactBlock {
+            hash: vec![4; 32],
+            ..block
+        };
+        assert!(!is_intact(&meta, &other.encode_to_vec()));
+        assert!(!is_intact(&meta, &[0xff, 0xff]));
+    }
+}
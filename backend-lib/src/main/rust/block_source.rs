@@ -0,0 +1,229 @@
+//! Block sources for scanning.
+//!
+//! [`MemoryBlockSource`] holds a batch of compact blocks in memory, so that blocks
+//! received from lightwalletd can be scanned without first being written to the
+//! `FsBlockDb` cache and read back.
+//!
+//! [`scan_cached_blocks`] reads the blocks to be scanned from its block source, possibly
+//! more than once, and then writes the results and note commitment tree updates to the
+//! wallet. [`InstrumentedBlockSource`] records when the last read of the blocks ends, which
+//! splits a scan into the time spent reading and scanning blocks and the time spent
+//! writing to the wallet database.
+//!
+//! [`scan_cached_blocks`]: zcash_client_backend::data_api::chain::scan_cached_blocks
+
+use std::cell::Cell;
+use std::convert::Infallible;
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use prost::Message;
+use zcash_client_backend::{
+    data_api::chain::{BlockSource, error::Error as ChainError},
+    proto::compact_formats::CompactBlock,
+};
+use zcash_protocol::consensus::BlockHeight;
+
+/// A block source backed by a batch of compact blocks held in memory.
+pub(crate) struct MemoryBlockSource {
+    /// The blocks, in height order.
+    blocks: Vec<CompactBlock>,
+}
+
+impl MemoryBlockSource {
+    pub(crate) fn new(mut blocks: Vec<CompactBlock>) -> Self {
+        blocks.sort_by_key(|block| block.height);
+        MemoryBlockSource { blocks }
+    }
+
+    /// Decodes a batch of serialized `CompactBlock` protobufs.
+    pub(crate) fn decode(encoded: &[Vec<u8>]) -> anyhow::Result<Self> {
+        encoded
+            .iter()
+            .map(|bytes| {
+                CompactBlock::decode(&bytes[..])
+                    .map_err(|e| anyhow!("Invalid compact block: {}", e))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map(Self::new)
+    }
+}
+
+impl BlockSource for MemoryBlockSource {
+    type Error = Infallible;
+
+    fn with_blocks<F, WalletErrT>(
+        &self,
+        from_height: Option<BlockHeight>,
+        limit: Option<usize>,
+        mut with_block: F,
+    ) -> Result<(), ChainError<WalletErrT, Self::Error>>
+    where
+        F: FnMut(CompactBlock) -> Result<(), ChainError<WalletErrT, Self::Error>>,
+    {
+        for block in self
+            .blocks
+            .iter()
+            .filter(|block| from_height.is_none_or(|h| block.height() >= h))
+            .take(limit.unwrap_or(usize::MAX))
+        {
+            with_block(block.clone())?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the height of the last block in a batch of at most `limit` blocks starting at
+/// `from_height`, leaving out any blocks above the chain tip, as lightwalletd fails the
+/// whole request for a range that extends past its tip.
+pub(crate) fn batch_end(
+    from_height: BlockHeight,
+    limit: u32,
+    tip: BlockHeight,
+) -> anyhow::Result<BlockHeight> {
+    if from_height > tip {
+        return Err(anyhow!(
+            "Cannot scan from height {} above the chain tip at height {}",
+            from_height,
+            tip
+        ));
+    }
+    Ok((from_height + limit.saturating_sub(1)).min(tip))
+}
+
+/// Measurements of a call to `scan_cached_blocks`.
+pub(crate) struct ScanMetrics {
+    /// The number of blocks read in the final pass over the block source.
+    pub(crate) blocks_processed: u64,
+    /// The time from the start of the scan until the last pass over the block source
+    /// ended, which includes reading the wallet state that scanning needs, and reading
+    /// and scanning the blocks.
+    pub(crate) read_and_scan_time: Duration,
+    /// The time from the end of the last pass over the block source until the end of the
+    /// scan, which is spent writing the results to the wallet database.
+    pub(crate) db_write_time: Duration,
+}
+
+/// Wraps a block source, recording when each pass over its blocks ends.
+pub(crate) struct InstrumentedBlockSource<'a, S> {
+    inner: &'a S,
+    started: Instant,
+    last_pass_end: Cell<Option<Instant>>,
+    last_pass_blocks: Cell<u64>,
+}
+
+impl<'a, S> InstrumentedBlockSource<'a, S> {
+    pub(crate) fn new(inner: &'a S) -> Self {
+        InstrumentedBlockSource {
+            inner,
+            started: Instant::now(),
+            last_pass_end: Cell::new(None),
+            last_pass_blocks: Cell::new(0),
+        }
+    }
+
+    /// Returns the measurements of the scan, which must have started when this source was
+    /// created and just completed.
+    pub(crate) fn finish(&self) -> ScanMetrics {
+        let finished = Instant::now();
+        let last_pass_end = self.last_pass_end.get().unwrap_or(finished);
+        ScanMetrics {
+            blocks_processed: self.last_pass_blocks.get(),
+            read_and_scan_time: last_pass_end.duration_since(self.started),
+            db_write_time: finished.duration_since(last_pass_end),
+        }
+    }
+}
+
+impl<S: BlockSource> BlockSource for InstrumentedBlockSource<'_, S> {
+    type Error = S::Error;
+
+    fn with_blocks<F, WalletErrT>(
+        &self,
+        from_height: Option<BlockHeight>,
+        limit: Option<usize>,
+        mut with_block: F,
+    ) -> Result<(), ChainError<WalletErrT, Self::Error>>
+    where
+        F: FnMut(CompactBlock) -> Result<(), ChainError<WalletErrT, Self::Error>>,
+    {
+        let mut blocks = 0;
+        let res = self.inner.with_blocks(from_height, limit, |block| {
+            blocks += 1;
+            with_block(block)
+        });
+        self.last_pass_blocks.set(blocks);
+        self.last_pass_end.set(Some(Instant::now()));
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(height: u64) -> CompactBlock {
+        CompactBlock {
+            height,
+            ..Default::default()
+        }
+    }
+
+    fn heights<B>(source: &B, from: Option<u32>, limit: Option<usize>) -> Vec<u64>
+    where
+        B: BlockSource,
+        B::Error: std::fmt::Debug,
+    {
+        let mut heights = vec![];
+        source
+            .with_blocks::<_, Infallible>(from.map(BlockHeight::from_u32), limit, |block| {
+                heights.push(block.height);
+                Ok(())
+            })
+            .unwrap();
+        heights
+    }
+
+    #[test]
+    fn memory_source_yields_blocks_in_height_order() {
+        let source = MemoryBlockSource::new(vec![block(12), block(10), block(11), block(13)]);
+        assert_eq!(heights(&source, None, None), [10, 11, 12, 13]);
+        assert_eq!(heights(&source, Some(11), Some(2)), [11, 12]);
+    }
+
+    #[test]
+    fn memory_source_decodes_protobufs() {
+        let encoded = vec![block(5).encode_to_vec(), block(4).encode_to_vec()];
+        let source = MemoryBlockSource::decode(&encoded).unwrap();
+        assert_eq!(heights(&source, None, None), [4, 5]);
+        assert!(MemoryBlockSource::decode(&[vec![0xff]]).is_err());
+    }
+
+    #[test]
+    fn batch_ends_at_the_chain_tip() {
+        let end = |from, limit, tip| {
+            batch_end(
+                BlockHeight::from_u32(from),
+                limit,
+                BlockHeight::from_u32(tip),
+            )
+            .map(u32::from)
+        };
+        assert_eq!(end(100, 10, 200).unwrap(), 109);
+        assert_eq!(end(100, 10, 105).unwrap(), 105);
+        assert_eq!(end(100, 10, 100).unwrap(), 100);
+        assert_eq!(
+            end(100, 10, 99).unwrap_err().to_string(),
+            "Cannot scan from height 100 above the chain tip at height 99"
+        );
+    }
+
+    #[test]
+    fn instrumented_source_counts_last_pass() {
+        let inner = MemoryBlockSource::new(vec![block(1), block(2), block(3)]);
+        let source = InstrumentedBlockSource::new(&inner);
+        heights(&source, None, None);
+        heights(&source, Some(2), None);
+        assert_eq!(source.finish().blocks_processed, 2);
+    }
+}
@@ -0,0 +1,713 @@
+//! Coin control support.
+//!
+//! [`GreedyInputSelector`] chooses the inputs for a transaction on the caller's behalf.
+//! The functions in this module instead let the caller name the exact notes and UTXOs
+//! that a transaction may spend, so that they can avoid linking specific funds.
+//!
+//! It also proposes note management transactions, which consolidate many small notes
+//! into one or split a balance into several notes, sending the funds back to the account.
+//!
+//! [`GreedyInputSelector`]: zcash_client_backend::data_api::wallet::input_selection::GreedyInputSelector
+
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+
+use anyhow::anyhow;
+use nonempty::NonEmpty;
+use rand::rngs::OsRng;
+
+use transparent::bundle::TxOut;
+use zcash_client_backend::{
+    address::Address,
+    data_api::{
+        AccountMeta, InputSource, MaxSpendMode, PoolMeta, TargetValue, WalletRead,
+        wallet::{ConfirmationsPolicy, TargetHeight},
+    },
+    fees::{
+        ChangeError, ChangeStrategy, DustOutputPolicy, SplitPolicy, StandardFeeRule,
+        orchard as orchard_fees, sapling as sapling_fees, zip317::MultiOutputChangeStrategy,
+    },
+    proposal::{Proposal, ShieldedInputs},
+    wallet::{Note, ReceivedNote, WalletTransparentOutput},
+    zip321::TransactionRequest,
+};
+use zcash_client_sqlite::{AccountUuid, ReceivedNoteId, WalletDb, util::SystemClock};
+use zcash_primitives::transaction::{TxId, fees::zip317};
+use zcash_protocol::{
+    PoolType, ShieldedProtocol,
+    consensus::{BlockHeight, Network, Parameters},
+    value::Zatoshis,
+};
+
+pub(crate) type Db = WalletDb<rusqlite::Connection, Network, SystemClock, OsRng>;
+
+/// Identifies a single output that the wallet has received.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct OutputRef {
+    pub(crate) txid: TxId,
+    pub(crate) pool: PoolType,
+    pub(crate) output_index: u32,
+}
+
+impl std::fmt::Display for OutputRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.txid, self.pool, self.output_index)
+    }
+}
+
+/// The notes and UTXOs that an account could spend in a transaction targeting
+/// `target_height`.
+pub(crate) struct SpendableInputs {
+    pub(crate) target_height: TargetHeight,
+    pub(crate) anchor_height: BlockHeight,
+    pub(crate) sapling: Vec<ReceivedNote<ReceivedNoteId, sapling::Note>>,
+    pub(crate) orchard: Vec<ReceivedNote<ReceivedNoteId, orchard::note::Note>>,
+    pub(crate) transparent: Vec<WalletTransparentOutput>,
+}
+
+impl SpendableInputs {
+    /// Fetches every input that the given account could currently spend.
+    pub(crate) fn fetch(
+        db_data: &Db,
+        account_uuid: AccountUuid,
+        confirmations_policy: ConfirmationsPolicy,
+    ) -> anyhow::Result<Self> {
+        let (target_height, anchor_height) = db_data
+            .get_target_and_anchor_heights(confirmations_policy.untrusted())
+            .map_err(|e| anyhow!("Error while fetching anchor height: {}", e))?
+            .ok_or_else(|| anyhow!("Anchor height not available; scan required."))?;
+
+        let notes = db_data
+            .select_spendable_notes(
+                account_uuid,
+                TargetValue::AllFunds(MaxSpendMode::Everything),
+                &[ShieldedProtocol::Sapling, ShieldedProtocol::Orchard],
+                target_height,
+                confirmations_policy,
+                &[],
+            )
+            .map_err(|e| anyhow!("Error while fetching spendable notes: {}", e))?;
+
+        // Zashi does not support standalone keys, so we do not request standalone receivers.
+        let mut transparent = vec![];
+        for taddr in db_data
+            .get_transparent_receivers(account_uuid, true, false)?
+            .keys()
+        {
+            transparent.extend(
+                db_data
+                    .get_spendable_transparent_outputs(taddr, target_height, confirmations_policy)
+                    .map_err(|e| anyhow!("Error while fetching spendable UTXOs: {}", e))?
+                    .into_iter()
+                    .map(|utxo| utxo.into_wallet_output()),
+            );
+        }
+
+        Ok(SpendableInputs {
+            target_height,
+            anchor_height,
+            sapling: notes.sapling().to_vec(),
+            orchard: notes.orchard().to_vec(),
+            transparent,
+        })
+    }
+
+    /// Restricts this set of inputs to exactly those identified by `selected`.
+    ///
+    /// Returns an error if any of the selected outputs is not currently spendable.
+    pub(crate) fn restrict_to(self, selected: &[OutputRef]) -> anyhow::Result<Self> {
+        if let Some(missing) = selected.iter().find(|r| !self.contains(r)) {
+            return Err(anyhow!(
+                "Selected input {} is not spendable by this account",
+                missing
+            ));
+        }
+
+        let is_selected = |txid: &TxId, pool: PoolType, output_index: u32| {
+            selected.contains(&OutputRef {
+                txid: *txid,
+                pool,
+                output_index,
+            })
+        };
+
+        Ok(SpendableInputs {
+            sapling: self
+                .sapling
+                .into_iter()
+                .filter(|n| is_selected(n.txid(), PoolType::SAPLING, n.output_index().into()))
+                .collect(),
+            orchard: self
+                .orchard
+                .into_iter()
+                .filter(|n| is_selected(n.txid(), PoolType::ORCHARD, n.output_index().into()))
+                .collect(),
+            transparent: self
+                .transparent
+                .into_iter()
+                .filter(|o| {
+                    is_selected(
+                        &TxId::from_bytes(*o.outpoint().hash()),
+                        PoolType::TRANSPARENT,
+                        o.outpoint().n(),
+                    )
+                })
+                .collect(),
+            ..self
+        })
+    }
+
+    /// Returns whether the given output is one of these inputs.
+    pub(crate) fn contains(&self, output: &OutputRef) -> bool {
+        match output.pool {
+            PoolType::Shielded(ShieldedProtocol::Sapling) => self.sapling.iter().any(|n| {
+                n.txid() == &output.txid && u32::from(n.output_index()) == output.output_index
+            }),
+            PoolType::Shielded(ShieldedProtocol::Orchard) => self.orchard.iter().any(|n| {
+                n.txid() == &output.txid && u32::from(n.output_index()) == output.output_index
+            }),
+            PoolType::Transparent => self.transparent.iter().any(|o| {
+                o.outpoint().hash() == output.txid.as_ref()
+                    && o.outpoint().n() == output.output_index
+            }),
+        }
+    }
+}
+
+/// The value of a shielded payment, for the purpose of fee and change computation.
+struct PaymentValue(Zatoshis);
+
+impl sapling_fees::OutputView for PaymentValue {
+    fn value(&self) -> Zatoshis {
+        self.0
+    }
+}
+
+impl orchard_fees::OutputView for PaymentValue {
+    fn value(&self) -> Zatoshis {
+        self.0
+    }
+}
+
+/// Constructs a single-step proposal for the given transaction request that spends
+/// exactly the given inputs, and no others.
+///
+/// If the inputs are insufficient to pay for the request and its fee, the returned error
+/// states the shortfall.
+pub(crate) fn propose_transfer_from_inputs(
+    db_data: &Db,
+    params: &Network,
+    account_uuid: AccountUuid,
+    change_strategy: &MultiOutputChangeStrategy<StandardFeeRule, Db>,
+    request: TransactionRequest,
+    selected: &[OutputRef],
+    confirmations_policy: ConfirmationsPolicy,
+) -> anyhow::Result<Proposal<StandardFeeRule, ReceivedNoteId>> {
+    if selected.is_empty() {
+        return Err(anyhow!("At least one input must be selected"));
+    }
+
+    let inputs = SpendableInputs::fetch(db_data, account_uuid, confirmations_policy)?
+        .restrict_to(selected)?;
+
+    let mut payment_pools = BTreeMap::new();
+    let mut transparent_outputs = vec![];
+    let mut sapling_outputs = vec![];
+    let mut orchard_outputs = vec![];
+    for (idx, payment) in request.payments() {
+        let recipient = payment
+            .recipient_address()
+            .clone()
+            .convert_if_network::<Address>(params.network_type())
+            .map_err(|e| anyhow!("Can't parse recipient address: {}", e))?;
+
+        let pool = match recipient {
+            Address::Transparent(addr) => {
+                transparent_outputs.push(TxOut::new(payment.amount(), addr.script().into()));
+                PoolType::TRANSPARENT
+            }
+            Address::Tex(_) => {
+                return Err(anyhow!(
+                    "Payments to TEX addresses are not supported with explicit input selection."
+                ));
+            }
+            Address::Sapling(_) => {
+                sapling_outputs.push(PaymentValue(payment.amount()));
+                PoolType::SAPLING
+            }
+            Address::Unified(ua) => {
+                if ua.has_orchard() {
+                    orchard_outputs.push(PaymentValue(payment.amount()));
+                    PoolType::ORCHARD
+                } else if ua.has_sapling() {
+                    sapling_outputs.push(PaymentValue(payment.amount()));
+                    PoolType::SAPLING
+                } else if let Some(addr) = ua.transparent() {
+                    transparent_outputs.push(TxOut::new(payment.amount(), addr.script().into()));
+                    PoolType::TRANSPARENT
+                } else {
+                    return Err(anyhow!("Unified Address has no supported receivers"));
+                }
+            }
+        };
+        payment_pools.insert(*idx, pool);
+    }
+
+    let wallet_meta = change_strategy
+        .fetch_wallet_meta(db_data, account_uuid, inputs.target_height, &[])
+        .map_err(|e| anyhow!("Error while fetching wallet metadata: {}", e))?;
+
+    let balance = change_strategy
+        .compute_balance(
+            params,
+            inputs.target_height,
+            &inputs.transparent,
+            &transparent_outputs,
+            &(
+                sapling::builder::BundleType::DEFAULT,
+                &inputs.sapling[..],
+                &sapling_outputs[..],
+            ),
+            &(
+                orchard::builder::BundleType::DEFAULT,
+                &inputs.orchard[..],
+                &orchard_outputs[..],
+            ),
+            None,
+            &wallet_meta,
+        )
+        .map_err(|e| match e {
+            ChangeError::InsufficientFunds {
+                available,
+                required,
+            } => anyhow!(
+                "Selected inputs are insufficient: {} zatoshis available, {} required (shortfall of {} zatoshis)",
+                u64::from(available),
+                u64::from(required),
+                u64::from(required) - u64::from(available),
+            ),
+            e => anyhow!("Error while computing transaction balance: {}", e),
+        })?;
+
+    let shielded_inputs = NonEmpty::from_vec(
+        inputs
+            .sapling
+            .into_iter()
+            .map(|n| n.map_note(Note::Sapling))
+            .chain(
+                inputs
+                    .orchard
+                    .into_iter()
+                    .map(|n| n.map_note(Note::Orchard)),
+            )
+            .collect(),
+    )
+    .map(|notes| ShieldedInputs::from_parts(inputs.anchor_height, notes));
+
+    Proposal::single_step(
+        request,
+        payment_pools,
+        inputs.transparent,
+        shielded_inputs,
+        balance,
+        *change_strategy.fee_rule(),
+        inputs.target_height,
+        false,
+    )
+    .map_err(|e| anyhow!("Error creating transaction proposal: {}", e))
+}
+
+/// Returns the notes worth more than the ZIP 317 marginal fee, which it costs to spend
+/// them.
+fn economic_notes<N>(
+    notes: Vec<ReceivedNote<ReceivedNoteId, N>>,
+    value: impl Fn(&ReceivedNote<ReceivedNoteId, N>) -> Zatoshis,
+) -> Vec<ReceivedNote<ReceivedNoteId, N>> {
+    notes
+        .into_iter()
+        .filter(|n| value(n) > zip317::MARGINAL_FEE)
+        .collect()
+}
+
+/// Fetches the notes in the given pool that are worth spending, ordered by increasing
+/// value.
+fn pool_inputs(
+    db_data: &Db,
+    account_uuid: AccountUuid,
+    pool: ShieldedProtocol,
+    confirmations_policy: ConfirmationsPolicy,
+) -> anyhow::Result<SpendableInputs> {
+    let mut inputs = SpendableInputs::fetch(db_data, account_uuid, confirmations_policy)?;
+    inputs.transparent.clear();
+    match pool {
+        ShieldedProtocol::Sapling => {
+            inputs.orchard.clear();
+            inputs.sapling = economic_notes(inputs.sapling, sapling_fees::InputView::value);
+            inputs.sapling.sort_by_key(sapling_fees::InputView::value);
+        }
+        ShieldedProtocol::Orchard => {
+            inputs.sapling.clear();
+            inputs.orchard = economic_notes(inputs.orchard, orchard_fees::InputView::value);
+            inputs.orchard.sort_by_key(orchard_fees::InputView::value);
+        }
+    }
+    Ok(inputs)
+}
+
+/// Constructs a single-step proposal with no payments, that spends the given inputs back
+/// to the account as change split according to `split_policy`.
+fn propose_to_self(
+    params: &Network,
+    inputs: SpendableInputs,
+    split_policy: SplitPolicy,
+) -> anyhow::Result<Proposal<StandardFeeRule, ReceivedNoteId>> {
+    let change_strategy = MultiOutputChangeStrategy::<StandardFeeRule, Db>::new(
+        StandardFeeRule::Zip317,
+        None,
+        ShieldedProtocol::Orchard,
+        DustOutputPolicy::default(),
+        split_policy,
+    );
+
+    // The split policy reduces the number of change outputs by the number of notes the
+    // account already holds; we want exactly the requested number.
+    let wallet_meta = AccountMeta::new(
+        Some(PoolMeta::new(0, Zatoshis::ZERO)),
+        Some(PoolMeta::new(0, Zatoshis::ZERO)),
+    );
+    let no_outputs: &[PaymentValue] = &[];
+
+    let balance = change_strategy
+        .compute_balance(
+            params,
+            inputs.target_height,
+            &inputs.transparent,
+            &[] as &[TxOut],
+            &(
+                sapling::builder::BundleType::DEFAULT,
+                &inputs.sapling[..],
+                no_outputs,
+            ),
+            &(
+                orchard::builder::BundleType::DEFAULT,
+                &inputs.orchard[..],
+                no_outputs,
+            ),
+            None,
+            &wallet_meta,
+        )
+        .map_err(|e| match e {
+            ChangeError::InsufficientFunds { available, .. } => anyhow!(
+                "The selected notes ({} zatoshis) do not cover the fee to spend them",
+                u64::from(available),
+            ),
+            e => anyhow!("Error while computing transaction balance: {}", e),
+        })?;
+
+    let shielded_inputs = NonEmpty::from_vec(
+        inputs
+            .sapling
+            .into_iter()
+            .map(|n| n.map_note(Note::Sapling))
+            .chain(
+                inputs
+                    .orchard
+                    .into_iter()
+                    .map(|n| n.map_note(Note::Orchard)),
+            )
+            .collect(),
+    )
+    .map(|notes| ShieldedInputs::from_parts(inputs.anchor_height, notes));
+
+    Proposal::single_step(
+        TransactionRequest::empty(),
+        BTreeMap::new(),
+        vec![],
+        shielded_inputs,
+        balance,
+        StandardFeeRule::Zip317,
+        inputs.target_height,
+        false,
+    )
+    .map_err(|e| anyhow!("Error creating transaction proposal: {}", e))
+}
+
+/// Proposes a transaction that consolidates up to `max_notes` of the smallest notes in
+/// the given pool into a single note in the same pool.
+///
+/// Notes worth no more than the ZIP 317 marginal fee are never selected, as spending
+/// them would lose value.
+pub(crate) fn propose_consolidation(
+    db_data: &Db,
+    params: &Network,
+    account_uuid: AccountUuid,
+    pool: ShieldedProtocol,
+    max_notes: usize,
+    confirmations_policy: ConfirmationsPolicy,
+) -> anyhow::Result<Proposal<StandardFeeRule, ReceivedNoteId>> {
+    let mut inputs = pool_inputs(db_data, account_uuid, pool, confirmations_policy)?;
+    inputs.sapling.truncate(max_notes);
+    inputs.orchard.truncate(max_notes);
+
+    if inputs.sapling.len() + inputs.orchard.len() < 2 {
+        return Err(anyhow!(
+            "At least two {:?} notes worth more than the marginal fee are required for consolidation",
+            pool
+        ));
+    }
+
+    propose_to_self(params, inputs, SplitPolicy::single_output())
+}
+
+/// Proposes a transaction that splits the account's balance in the given pool into
+/// `note_count` notes of roughly equal value in the same pool, so that the account can
+/// make that many spends without waiting for change to confirm.
+///
+/// Returns an error if the balance is too small for each note to be worth more than the
+/// ZIP 317 marginal fee.
+pub(crate) fn propose_split(
+    db_data: &Db,
+    params: &Network,
+    account_uuid: AccountUuid,
+    pool: ShieldedProtocol,
+    note_count: NonZeroUsize,
+    confirmations_policy: ConfirmationsPolicy,
+) -> anyhow::Result<Proposal<StandardFeeRule, ReceivedNoteId>> {
+    let inputs = pool_inputs(db_data, account_uuid, pool, confirmations_policy)?;
+    if inputs.sapling.is_empty() && inputs.orchard.is_empty() {
+        return Err(anyhow!("There are no spendable {:?} notes to split", pool));
+    }
+
+    let proposal = propose_to_self(
+        params,
+        inputs,
+        SplitPolicy::with_min_output_value(note_count, zip317::MARGINAL_FEE),
+    )?;
+
+    if proposal.steps().head.balance().proposed_change().len() < note_count.get() {
+        return Err(anyhow!(
+            "The {:?} balance is too small to split into {} notes",
+            pool,
+            note_count
+        ));
+    }
+
+    Ok(proposal)
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::Connection;
+    use transparent::address::TransparentAddress;
+    use zcash_address::ZcashAddress;
+    use zcash_client_backend::{
+        data_api::WalletWrite, encoding::AddressCodec, fees::DustOutputPolicy, zip321::Payment,
+    };
+
+    use super::*;
+    use crate::testing::{
+        NETWORK, add_account, insert_tx, insert_utxo, spend_utxo, txid, wallet_db,
+    };
+
+    /// Returns a wallet with an account and no notes, that has an anchor to spend from
+    /// once it is [`scanned`].
+    fn account_db() -> (Connection, AccountUuid) {
+        let mut conn = wallet_db();
+        let account = add_account(&mut conn, 1, 3_000_000);
+        conn.execute(
+            "INSERT INTO sapling_tree_checkpoints (checkpoint_id) VALUES (3000099)",
+            [],
+        )
+        .unwrap();
+        (conn, account)
+    }
+
+    fn scanned(conn: Connection) -> Db {
+        let mut db_data = WalletDb::from_connection(conn, NETWORK, SystemClock, OsRng);
+        db_data
+            .update_chain_tip(BlockHeight::from_u32(3_000_100))
+            .unwrap();
+        db_data
+    }
+
+    fn scanned_db() -> (Db, AccountUuid) {
+        let (conn, account) = account_db();
+        (scanned(conn), account)
+    }
+
+    /// Adds mined UTXOs of the given values at the account's first external transparent
+    /// address, and returns references to them.
+    fn fund_transparent(conn: &Connection, account: AccountUuid, values: &[u64]) -> Vec<OutputRef> {
+        let address: String = conn
+            .query_row(
+                "SELECT cached_transparent_receiver_address FROM addresses
+                 WHERE key_scope = 0 AND cached_transparent_receiver_address IS NOT NULL
+                 ORDER BY id LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let tx = insert_tx(conn, 1, Some(3_000_050), 0);
+        values
+            .iter()
+            .zip(0..)
+            .map(|(value, output_index)| {
+                insert_utxo(conn, tx, output_index, account, &address, *value);
+                OutputRef {
+                    txid: txid(1),
+                    pool: PoolType::TRANSPARENT,
+                    output_index,
+                }
+            })
+            .collect()
+    }
+
+    fn change_strategy() -> MultiOutputChangeStrategy<StandardFeeRule, Db> {
+        MultiOutputChangeStrategy::new(
+            StandardFeeRule::Zip317,
+            None,
+            ShieldedProtocol::Orchard,
+            DustOutputPolicy::default(),
+            SplitPolicy::single_output(),
+        )
+    }
+
+    fn pay(value: u64) -> TransactionRequest {
+        let recipient = TransparentAddress::PublicKeyHash([7; 20]).encode(&NETWORK);
+        TransactionRequest::new(vec![Payment::without_memo(
+            ZcashAddress::try_from_encoded(&recipient).unwrap(),
+            Zatoshis::from_u64(value).unwrap(),
+        )])
+        .unwrap()
+    }
+
+    fn propose(
+        db_data: &Db,
+        account: AccountUuid,
+        value: u64,
+        selected: &[OutputRef],
+    ) -> anyhow::Result<Proposal<StandardFeeRule, ReceivedNoteId>> {
+        propose_transfer_from_inputs(
+            db_data,
+            &NETWORK,
+            account,
+            &change_strategy(),
+            pay(value),
+            selected,
+            ConfirmationsPolicy::MIN,
+        )
+    }
+
+    #[test]
+    fn transfer_spends_only_selected_inputs() {
+        let (conn, account) = account_db();
+        let utxos = fund_transparent(&conn, account, &[100_000, 200_000, 300_000]);
+        let db_data = scanned(conn);
+
+        let proposal = propose(&db_data, account, 150_000, &utxos[1..2]).unwrap();
+        let step = &proposal.steps().head;
+        assert_eq!(
+            step.transparent_inputs()
+                .iter()
+                .map(|o| o.outpoint().n())
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+        assert!(step.shielded_inputs().is_none());
+        let change = step
+            .balance()
+            .proposed_change()
+            .iter()
+            .map(|c| u64::from(c.value()))
+            .sum::<u64>();
+        assert_eq!(
+            150_000 + change + u64::from(step.balance().fee_required()),
+            200_000
+        );
+    }
+
+    #[test]
+    fn transfer_reports_the_shortfall_of_selected_inputs() {
+        let (conn, account) = account_db();
+        let utxos = fund_transparent(&conn, account, &[100_000, 200_000]);
+        let db_data = scanned(conn);
+
+        let err = propose(&db_data, account, 150_000, &utxos[..1]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Selected inputs are insufficient: 100000 zatoshis available, 160000 required (shortfall of 60000 zatoshis)"
+        );
+    }
+
+    #[test]
+    fn transfer_rejects_unknown_and_spent_inputs() {
+        let (conn, account) = account_db();
+        let utxos = fund_transparent(&conn, account, &[100_000, 200_000]);
+        let spent = conn
+            .query_row(
+                "SELECT id FROM transparent_received_outputs WHERE output_index = 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        spend_utxo(&conn, spent, insert_tx(&conn, 2, Some(3_000_060), 0));
+        let db_data = scanned(conn);
+
+        let unknown = OutputRef {
+            txid: txid(9),
+            ..utxos[0]
+        };
+        for missing in [unknown, utxos[1]] {
+            let err = propose(&db_data, account, 50_000, &[utxos[0], missing]).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "Selected input {} is not spendable by this account",
+                    missing
+                )
+            );
+        }
+        assert_eq!(
+            propose(&db_data, account, 50_000, &[])
+                .unwrap_err()
+                .to_string(),
+            "At least one input must be selected"
+        );
+    }
+
+    #[test]
+    fn consolidation_requires_two_notes() {
+        let (db_data, account) = scanned_db();
+        let err = propose_consolidation(
+            &db_data,
+            &NETWORK,
+            account,
+            ShieldedProtocol::Sapling,
+            10,
+            ConfirmationsPolicy::MIN,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "At least two Sapling notes worth more than the marginal fee are required for consolidation"
+        );
+    }
+
+    #[test]
+    fn split_requires_a_spendable_note() {
+        let (db_data, account) = scanned_db();
+        let err = propose_split(
+            &db_data,
+            &NETWORK,
+            account,
+            ShieldedProtocol::Orchard,
+            NonZeroUsize::new(4).unwrap(),
+            ConfirmationsPolicy::MIN,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "There are no spendable Orchard notes to split"
+        );
+    }
+}
@@ -0,0 +1,486 @@
+//! Deferred storage of transactions created by the wallet.
+//!
+//! `extract_and_store_transaction_from_pczt` and `create_proposed_transactions` store the
+//! transactions they create with their own calls to `store_transactions_to_be_sent`, and
+//! the wallet database only supports writes outside of a database transaction.
+//! [`DeferredStore`] wraps the wallet database, forwarding every call except
+//! `store_transactions_to_be_sent`, whose transactions it holds back so that several can
+//! then be stored in a single database transaction, or discarded.
+
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::time::SystemTime;
+
+use secrecy::SecretVec;
+use shardtree::{ShardTree, error::ShardTreeError};
+use transparent::{address::TransparentAddress, bundle::OutPoint, keys::TransparentKeyScope};
+use zcash_client_backend::{
+    address::UnifiedAddress,
+    data_api::{
+        AccountBirthday, AccountPurpose, AddressInfo, Balance, BlockMetadata, DecryptedTransaction,
+        NullifierQuery, ORCHARD_SHARD_HEIGHT, SAPLING_SHARD_HEIGHT, ScannedBlock, SeedRelevance,
+        SentTransaction, SentTransactionOutput, TransactionDataRequest, TransactionStatus,
+        TransactionsInvolvingAddress, WalletCommitmentTrees, WalletRead, WalletSummary,
+        WalletWrite, Zip32Derivation,
+        chain::{ChainState, CommitmentTreeRoot},
+        scanning::ScanRange,
+        wallet::{ConfirmationsPolicy, TargetHeight},
+    },
+    keys::{UnifiedAddressRequest, UnifiedFullViewingKey, UnifiedSpendingKey},
+    wallet::{NoteId, TransparentAddressMetadata, WalletTransparentOutput},
+};
+use zcash_client_sqlite::{AccountUuid, error::SqliteClientError};
+use zcash_primitives::{
+    block::BlockHash,
+    transaction::{Transaction, TxId},
+};
+use zcash_protocol::{consensus::BlockHeight, memo::Memo, value::Zatoshis};
+use zip32::DiversifierIndex;
+
+use crate::coin_control::Db;
+
+/// An owned copy of a [`SentTransaction`].
+struct HeldTransaction {
+    tx: Transaction,
+    created: time::OffsetDateTime,
+    target_height: TargetHeight,
+    account: AccountUuid,
+    outputs: Vec<SentTransactionOutput<AccountUuid>>,
+    fee_amount: Zatoshis,
+    utxos_spent: Vec<OutPoint>,
+}
+
+impl HeldTransaction {
+    fn copy(sent: &SentTransaction<AccountUuid>) -> Result<Self, SqliteClientError> {
+        let mut raw = vec![];
+        sent.tx().write(&mut raw)?;
+        Ok(HeldTransaction {
+            tx: Transaction::read(&raw[..], sent.tx().consensus_branch_id())?,
+            created: sent.created(),
+            target_height: sent.target_height(),
+            account: *sent.account_id(),
+            outputs: sent
+                .outputs()
+                .iter()
+                .map(|output| {
+                    SentTransactionOutput::from_parts(
+                        output.output_index(),
+                        output.recipient().clone(),
+                        output.value(),
+                        output.memo().cloned(),
+                    )
+                })
+                .collect(),
+            fee_amount: sent.fee_amount(),
+            utxos_spent: sent.utxos_spent().to_vec(),
+        })
+    }
+
+    fn as_sent(&self) -> SentTransaction<'_, AccountUuid> {
+        SentTransaction::new(
+            &self.tx,
+            self.created,
+            self.target_height,
+            self.account,
+            &self.outputs,
+            self.fee_amount,
+            &self.utxos_spent,
+        )
+    }
+}
+
+/// A wallet database whose sent transactions are held back until [`DeferredStore::commit`].
+pub(crate) struct DeferredStore<'a> {
+    db: &'a mut Db,
+    held: Vec<HeldTransaction>,
+}
+
+impl<'a> DeferredStore<'a> {
+    pub(crate) fn new(db: &'a mut Db) -> Self {
+        DeferredStore { db, held: vec![] }
+    }
+
+    /// Stores every transaction held back so far in a single database transaction.
+    pub(crate) fn commit(self) -> Result<(), SqliteClientError> {
+        let sent = self
+            .held
+            .iter()
+            .map(HeldTransaction::as_sent)
+            .collect::<Vec<_>>();
+        self.db.store_transactions_to_be_sent(&sent)
+    }
+}
+
+impl WalletRead for DeferredStore<'_> {
+    type Error = <Db as WalletRead>::Error;
+    type AccountId = <Db as WalletRead>::AccountId;
+    type Account = <Db as WalletRead>::Account;
+
+    fn get_account_ids(&self) -> Result<Vec<Self::AccountId>, Self::Error> {
+        self.db.get_account_ids()
+    }
+
+    fn get_account(
+        &self,
+        account_id: Self::AccountId,
+    ) -> Result<Option<Self::Account>, Self::Error> {
+        self.db.get_account(account_id)
+    }
+
+    fn get_derived_account(
+        &self,
+        derivation: &Zip32Derivation,
+    ) -> Result<Option<Self::Account>, Self::Error> {
+        self.db.get_derived_account(derivation)
+    }
+
+    fn validate_seed(
+        &self,
+        account_id: Self::AccountId,
+        seed: &SecretVec<u8>,
+    ) -> Result<bool, Self::Error> {
+        self.db.validate_seed(account_id, seed)
+    }
+
+    fn seed_relevance_to_derived_accounts(
+        &self,
+        seed: &SecretVec<u8>,
+    ) -> Result<SeedRelevance<Self::AccountId>, Self::Error> {
+        self.db.seed_relevance_to_derived_accounts(seed)
+    }
+
+    fn get_account_for_ufvk(
+        &self,
+        ufvk: &UnifiedFullViewingKey,
+    ) -> Result<Option<Self::Account>, Self::Error> {
+        self.db.get_account_for_ufvk(ufvk)
+    }
+
+    fn list_addresses(&self, account: Self::AccountId) -> Result<Vec<AddressInfo>, Self::Error> {
+        self.db.list_addresses(account)
+    }
+
+    fn get_last_generated_address_matching(
+        &self,
+        account: Self::AccountId,
+        address_filter: UnifiedAddressRequest,
+    ) -> Result<Option<UnifiedAddress>, Self::Error> {
+        self.db
+            .get_last_generated_address_matching(account, address_filter)
+    }
+
+    fn get_account_birthday(&self, account: Self::AccountId) -> Result<BlockHeight, Self::Error> {
+        self.db.get_account_birthday(account)
+    }
+
+    fn get_wallet_birthday(&self) -> Result<Option<BlockHeight>, Self::Error> {
+        self.db.get_wallet_birthday()
+    }
+
+    fn get_wallet_summary(
+        &self,
+        confirmations_policy: ConfirmationsPolicy,
+    ) -> Result<Option<WalletSummary<Self::AccountId>>, Self::Error> {
+        self.db.get_wallet_summary(confirmations_policy)
+    }
+
+    fn chain_height(&self) -> Result<Option<BlockHeight>, Self::Error> {
+        self.db.chain_height()
+    }
+
+    fn get_block_hash(&self, block_height: BlockHeight) -> Result<Option<BlockHash>, Self::Error> {
+        self.db.get_block_hash(block_height)
+    }
+
+    fn block_metadata(&self, height: BlockHeight) -> Result<Option<BlockMetadata>, Self::Error> {
+        self.db.block_metadata(height)
+    }
+
+    fn block_fully_scanned(&self) -> Result<Option<BlockMetadata>, Self::Error> {
+        self.db.block_fully_scanned()
+    }
+
+    fn get_max_height_hash(&self) -> Result<Option<(BlockHeight, BlockHash)>, Self::Error> {
+        self.db.get_max_height_hash()
+    }
+
+    fn block_max_scanned(&self) -> Result<Option<BlockMetadata>, Self::Error> {
+        self.db.block_max_scanned()
+    }
+
+    fn suggest_scan_ranges(&self) -> Result<Vec<ScanRange>, Self::Error> {
+        self.db.suggest_scan_ranges()
+    }
+
+    fn get_target_and_anchor_heights(
+        &self,
+        min_confirmations: NonZeroU32,
+    ) -> Result<Option<(TargetHeight, BlockHeight)>, Self::Error> {
+        self.db.get_target_and_anchor_heights(min_confirmations)
+    }
+
+    fn get_tx_height(&self, txid: TxId) -> Result<Option<BlockHeight>, Self::Error> {
+        self.db.get_tx_height(txid)
+    }
+
+    fn get_unified_full_viewing_keys(
+        &self,
+    ) -> Result<HashMap<Self::AccountId, UnifiedFullViewingKey>, Self::Error> {
+        self.db.get_unified_full_viewing_keys()
+    }
+
+    fn get_memo(&self, note_id: NoteId) -> Result<Option<Memo>, Self::Error> {
+        self.db.get_memo(note_id)
+    }
+
+    fn get_transaction(&self, txid: TxId) -> Result<Option<Transaction>, Self::Error> {
+        self.db.get_transaction(txid)
+    }
+
+    fn get_sapling_nullifiers(
+        &self,
+        query: NullifierQuery,
+    ) -> Result<Vec<(Self::AccountId, sapling::Nullifier)>, Self::Error> {
+        self.db.get_sapling_nullifiers(query)
+    }
+
+    fn get_orchard_nullifiers(
+        &self,
+        query: NullifierQuery,
+    ) -> Result<Vec<(Self::AccountId, orchard::note::Nullifier)>, Self::Error> {
+        self.db.get_orchard_nullifiers(query)
+    }
+
+    fn get_transparent_receivers(
+        &self,
+        account: Self::AccountId,
+        include_change: bool,
+        include_standalone: bool,
+    ) -> Result<HashMap<TransparentAddress, TransparentAddressMetadata>, Self::Error> {
+        self.db
+            .get_transparent_receivers(account, include_change, include_standalone)
+    }
+
+    fn get_ephemeral_transparent_receivers(
+        &self,
+        account: Self::AccountId,
+        exposure_depth: u32,
+        exclude_used: bool,
+    ) -> Result<HashMap<TransparentAddress, TransparentAddressMetadata>, Self::Error> {
+        self.db
+            .get_ephemeral_transparent_receivers(account, exposure_depth, exclude_used)
+    }
+
+    fn get_transparent_balances(
+        &self,
+        account: Self::AccountId,
+        target_height: TargetHeight,
+        confirmations_policy: ConfirmationsPolicy,
+    ) -> Result<HashMap<TransparentAddress, (TransparentKeyScope, Balance)>, Self::Error> {
+        self.db
+            .get_transparent_balances(account, target_height, confirmations_policy)
+    }
+
+    fn get_transparent_address_metadata(
+        &self,
+        account: Self::AccountId,
+        address: &TransparentAddress,
+    ) -> Result<Option<TransparentAddressMetadata>, Self::Error> {
+        self.db.get_transparent_address_metadata(account, address)
+    }
+
+    fn utxo_query_height(&self, account: Self::AccountId) -> Result<BlockHeight, Self::Error> {
+        self.db.utxo_query_height(account)
+    }
+
+    fn transaction_data_requests(&self) -> Result<Vec<TransactionDataRequest>, Self::Error> {
+        self.db.transaction_data_requests()
+    }
+}
+
+impl WalletWrite for DeferredStore<'_> {
+    type UtxoRef = <Db as WalletWrite>::UtxoRef;
+
+    fn create_account(
+        &mut self,
+        account_name: &str,
+        seed: &SecretVec<u8>,
+        birthday: &AccountBirthday,
+        key_source: Option<&str>,
+    ) -> Result<(Self::AccountId, UnifiedSpendingKey), Self::Error> {
+        self.db
+            .create_account(account_name, seed, birthday, key_source)
+    }
+
+    fn import_account_hd(
+        &mut self,
+        account_name: &str,
+        seed: &SecretVec<u8>,
+        account_index: zip32::AccountId,
+        birthday: &AccountBirthday,
+        key_source: Option<&str>,
+    ) -> Result<(Self::Account, UnifiedSpendingKey), Self::Error> {
+        self.db
+            .import_account_hd(account_name, seed, account_index, birthday, key_source)
+    }
+
+    fn import_account_ufvk(
+        &mut self,
+        account_name: &str,
+        unified_key: &UnifiedFullViewingKey,
+        birthday: &AccountBirthday,
+        purpose: AccountPurpose,
+        key_source: Option<&str>,
+    ) -> Result<Self::Account, Self::Error> {
+        self.db
+            .import_account_ufvk(account_name, unified_key, birthday, purpose, key_source)
+    }
+
+    fn delete_account(&mut self, account: Self::AccountId) -> Result<(), Self::Error> {
+        self.db.delete_account(account)
+    }
+
+    fn get_next_available_address(
+        &mut self,
+        account: Self::AccountId,
+        request: UnifiedAddressRequest,
+    ) -> Result<Option<(UnifiedAddress, DiversifierIndex)>, Self::Error> {
+        self.db.get_next_available_address(account, request)
+    }
+
+    fn get_address_for_index(
+        &mut self,
+        account: Self::AccountId,
+        diversifier_index: DiversifierIndex,
+        request: UnifiedAddressRequest,
+    ) -> Result<Option<UnifiedAddress>, Self::Error> {
+        self.db
+            .get_address_for_index(account, diversifier_index, request)
+    }
+
+    fn update_chain_tip(&mut self, tip_height: BlockHeight) -> Result<(), Self::Error> {
+        self.db.update_chain_tip(tip_height)
+    }
+
+    fn put_blocks(
+        &mut self,
+        from_state: &ChainState,
+        blocks: Vec<ScannedBlock<Self::AccountId>>,
+    ) -> Result<(), Self::Error> {
+        self.db.put_blocks(from_state, blocks)
+    }
+
+    fn put_received_transparent_utxo(
+        &mut self,
+        output: &WalletTransparentOutput,
+    ) -> Result<Self::UtxoRef, Self::Error> {
+        self.db.put_received_transparent_utxo(output)
+    }
+
+    fn store_decrypted_tx(
+        &mut self,
+        received_tx: DecryptedTransaction<Self::AccountId>,
+    ) -> Result<(), Self::Error> {
+        self.db.store_decrypted_tx(received_tx)
+    }
+
+    fn set_tx_trust(&mut self, txid: TxId, trusted: bool) -> Result<(), Self::Error> {
+        self.db.set_tx_trust(txid, trusted)
+    }
+
+    fn store_transactions_to_be_sent(
+        &mut self,
+        transactions: &[SentTransaction<Self::AccountId>],
+    ) -> Result<(), Self::Error> {
+        for sent in transactions {
+            self.held.push(HeldTransaction::copy(sent)?);
+        }
+        Ok(())
+    }
+
+    fn truncate_to_height(&mut self, max_height: BlockHeight) -> Result<BlockHeight, Self::Error> {
+        self.db.truncate_to_height(max_height)
+    }
+
+    fn reserve_next_n_ephemeral_addresses(
+        &mut self,
+        account_id: Self::AccountId,
+        n: usize,
+    ) -> Result<Vec<(TransparentAddress, TransparentAddressMetadata)>, Self::Error> {
+        self.db.reserve_next_n_ephemeral_addresses(account_id, n)
+    }
+
+    fn set_transaction_status(
+        &mut self,
+        txid: TxId,
+        status: TransactionStatus,
+    ) -> Result<(), Self::Error> {
+        self.db.set_transaction_status(txid, status)
+    }
+
+    fn schedule_next_check(
+        &mut self,
+        address: &TransparentAddress,
+        offset_seconds: u32,
+    ) -> Result<Option<SystemTime>, Self::Error> {
+        self.db.schedule_next_check(address, offset_seconds)
+    }
+
+    fn notify_address_checked(
+        &mut self,
+        request: TransactionsInvolvingAddress,
+        as_of_height: BlockHeight,
+    ) -> Result<(), Self::Error> {
+        self.db.notify_address_checked(request, as_of_height)
+    }
+}
+
+impl WalletCommitmentTrees for DeferredStore<'_> {
+    type Error = <Db as WalletCommitmentTrees>::Error;
+    type SaplingShardStore<'b> = <Db as WalletCommitmentTrees>::SaplingShardStore<'b>;
+    type OrchardShardStore<'b> = <Db as WalletCommitmentTrees>::OrchardShardStore<'b>;
+
+    fn with_sapling_tree_mut<F, A, E>(&mut self, callback: F) -> Result<A, E>
+    where
+        for<'b> F: FnMut(
+            &'b mut ShardTree<
+                Self::SaplingShardStore<'b>,
+                { sapling::NOTE_COMMITMENT_TREE_DEPTH },
+                SAPLING_SHARD_HEIGHT,
+            >,
+        ) -> Result<A, E>,
+        E: From<ShardTreeError<Self::Error>>,
+    {
+        self.db.with_sapling_tree_mut(callback)
+    }
+
+    fn put_sapling_subtree_roots(
+        &mut self,
+        start_index: u64,
+        roots: &[CommitmentTreeRoot<sapling::Node>],
+    ) -> Result<(), ShardTreeError<Self::Error>> {
+        self.db.put_sapling_subtree_roots(start_index, roots)
+    }
+
+    fn with_orchard_tree_mut<F, A, E>(&mut self, callback: F) -> Result<A, E>
+    where
+        for<'b> F: FnMut(
+            &'b mut ShardTree<
+                Self::OrchardShardStore<'b>,
+                { ORCHARD_SHARD_HEIGHT * 2 },
+                ORCHARD_SHARD_HEIGHT,
+            >,
+        ) -> Result<A, E>,
+        E: From<ShardTreeError<Self::Error>>,
+    {
+        self.db.with_orchard_tree_mut(callback)
+    }
+
+    fn put_orchard_subtree_roots(
+        &mut self,
+        start_index: u64,
+        roots: &[CommitmentTreeRoot<orchard::tree::MerkleHashOrchard>],
+    ) -> Result<(), ShardTreeError<Self::Error>> {
+        self.db.put_orchard_subtree_roots(start_index, roots)
+    }
+}
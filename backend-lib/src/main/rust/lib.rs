@@ -3,7 +3,7 @@ use std::error::Error;
 use std::io;
 use std::num::{NonZeroU32, NonZeroUsize};
 use std::panic;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::ptr;
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -13,8 +13,11 @@ use bytes::Bytes;
 use http_body_util::BodyExt;
 use jni::{
     JNIEnv,
-    objects::{JByteArray, JClass, JObject, JObjectArray, JString, JValue},
-    sys::{JNI_FALSE, JNI_TRUE, jboolean, jbyteArray, jint, jlong, jobject, jobjectArray, jstring},
+    objects::{JByteArray, JClass, JLongArray, JObject, JObjectArray, JString, JValue},
+    sys::{
+        JNI_FALSE, JNI_TRUE, jboolean, jbyteArray, jint, jlong, jobject, jobjectArray, jsize,
+        jstring,
+    },
 };
 use nonempty::NonEmpty;
 use prost::Message;
@@ -28,7 +31,7 @@ use uuid::Uuid;
 
 use pczt::{
     Pczt,
-    roles::{combiner::Combiner, prover::Prover, redactor::Redactor},
+    roles::{combiner::Combiner, prover::Prover, redactor::Redactor, verifier::Verifier},
 };
 use transparent::{
     address::{Script, TransparentAddress},
@@ -46,7 +49,7 @@ use zcash_client_backend::{
         OutputStatusFilter, SeedRelevance, TransactionDataRequest, TransactionStatus,
         TransactionStatusFilter, WalletCommitmentTrees, WalletRead, WalletSummary, WalletWrite,
         Zip32Derivation,
-        chain::{CommitmentTreeRoot, ScanSummary, scan_cached_blocks},
+        chain::{BlockSource, ChainState, CommitmentTreeRoot, ScanSummary, scan_cached_blocks},
         scanning::{ScanPriority, ScanRange},
         wallet::{
             self, create_pczt_from_proposal, create_proposed_transactions,
@@ -80,9 +83,8 @@ use zcash_primitives::{
     merkle_tree::HashSer,
     transaction::{Transaction, TxId},
 };
-use zcash_proofs::prover::LocalTxProver;
 use zcash_protocol::{
-    ShieldedProtocol,
+    PoolType, ShieldedProtocol,
     consensus::{
         BlockHeight, BranchId, Network,
         Network::{MainNetwork, TestNetwork},
@@ -100,8 +102,31 @@ use crate::utils::{
     catch_unwind, exception::unwrap_exc_or, java_nullable_string_to_rust, java_string_to_rust,
 };
 
+mod auto_shield;
+mod balance_history;
+mod block_cache;
+mod block_source;
+mod coin_control;
+mod deferred_store;
+mod memo;
+mod pczt_chain;
+mod pczt_signer;
+mod pczt_summary;
+mod pczt_ur;
+mod pczt_verify;
+mod pool_migration;
+mod proving;
+mod received_outputs;
+mod rewind_preview;
+mod sapling_params;
+mod sdk_schema;
+#[cfg(test)]
+mod testing;
 mod tor;
+mod tx_history;
+mod unmined;
 mod utils;
+mod wallet_backup;
 
 #[cfg(debug_assertions)]
 fn print_debug_state() {
@@ -118,7 +143,28 @@ fn wallet_db<P: Parameters>(
     params: P,
     db_data: JString,
 ) -> anyhow::Result<WalletDb<rusqlite::Connection, P, SystemClock, OsRng>> {
-    WalletDb::for_path(path_from_jni(env, db_data)?, params, SystemClock, OsRng)
+    wallet_db_at(&path_from_jni(env, db_data)?, params)
+}
+
+fn wallet_db_at<P: Parameters>(
+    db_path: &Path,
+    params: P,
+) -> anyhow::Result<WalletDb<rusqlite::Connection, P, SystemClock, OsRng>> {
+    WalletDb::for_path(db_path, params, SystemClock, OsRng)
+        .map_err(|e| anyhow!("Error opening wallet database connection: {}", e))
+}
+
+/// Opens a read-only connection to the wallet database, for queries that
+/// `zcash_client_sqlite` does not expose.
+fn wallet_db_conn(db_path: &Path) -> anyhow::Result<rusqlite::Connection> {
+    rusqlite::Connection::open_with_flags(db_path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| anyhow!("Error opening wallet database connection: {}", e))
+}
+
+/// Opens a read-write connection to the wallet database, for tables that this crate
+/// maintains alongside those of `zcash_client_sqlite`.
+fn wallet_db_conn_mut(db_path: &Path) -> anyhow::Result<rusqlite::Connection> {
+    rusqlite::Connection::open(db_path)
         .map_err(|e| anyhow!("Error opening wallet database connection: {}", e))
 }
 
@@ -134,6 +180,9 @@ fn block_db(env: &mut JNIEnv, fsblockdb_root: JString) -> anyhow::Result<FsBlock
 /// `debug` - Logs lower priority information
 /// `off` - The logs are completely disabled
 ///
+/// If `warm_proving_keys` is true, the Orchard proving and verifying keys are built in the
+/// background, so that they are cached before the first transaction is created.
+///
 /// # Panics
 ///
 /// This method panics if called more than once.
@@ -142,6 +191,7 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_initOnLoa
     mut _env: JNIEnv<'local>,
     _: JClass<'local>,
     _log_level: JString<'local>,
+    warm_proving_keys: jboolean,
 ) {
     // Set up the Android tracing layer.
     #[cfg(target_os = "android")]
@@ -193,6 +243,12 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_initOnLoa
         .build_global()
         .expect("Only initialized once");
 
+    // Build the Orchard keys in the background, so that the first transaction isn't
+    // delayed by several seconds.
+    if warm_proving_keys == JNI_TRUE {
+        proving::warm_orchard_keys();
+    }
+
     debug!("Rust backend has been initialized successfully");
     print_debug_state();
 }
@@ -217,6 +273,66 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_initBlock
     unwrap_exc_or(&mut env, res, -1)
 }
 
+/// Writes a consistent snapshot of the data database to `backup_path`, optionally
+/// compressed, and encrypted with a key derived from `seed` if it is not `null`.
+///
+/// Returns the size of the backup in bytes.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_backupDataDb<'local>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    backup_path: JString<'local>,
+    compress: jboolean,
+    seed: JByteArray<'local>,
+    network_id: jint,
+) -> jlong {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.backupDataDb").entered();
+        let network = parse_network(network_id as u32)?;
+        let db_path = path_from_jni(env, db_data)?;
+        let backup_path = path_from_jni(env, backup_path)?;
+        let key = utils::java_nullable_bytes_to_rust(env, &seed)?
+            .map(|seed| wallet_backup_key(&SecretVec::new(seed), &network))
+            .transpose()?;
+
+        let size =
+            wallet_backup::backup(&db_path, &backup_path, compress == JNI_TRUE, key.as_ref())
+                .map_err(|e| anyhow!("Error while backing up data DB: {}", e))?;
+        Ok(size as i64)
+    });
+    unwrap_exc_or(&mut env, res, -1)
+}
+
+/// Replaces the data database with the backup at `backup_path`, after checking that it
+/// belongs to the given network and (if `seed` is not `null`) seed, and migrating it to
+/// the current schema. `seed` is required to restore an encrypted backup.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_restoreDataDb<'local>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    backup_path: JString<'local>,
+    seed: JByteArray<'local>,
+    network_id: jint,
+) {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.restoreDataDb").entered();
+        let network = parse_network(network_id as u32)?;
+        let db_path = path_from_jni(env, db_data)?;
+        let backup_path = path_from_jni(env, backup_path)?;
+        let seed = utils::java_nullable_bytes_to_rust(env, &seed)?.map(SecretVec::new);
+        let key = seed
+            .as_ref()
+            .map(|seed| wallet_backup_key(seed, &network))
+            .transpose()?;
+
+        wallet_backup::restore(&backup_path, &db_path, network, seed, key.as_ref())
+            .map_err(|e| anyhow!("Error while restoring data DB: {}", e))
+    });
+    unwrap_exc_or(&mut env, res, ())
+}
+
 /// Sets up the internal structure of the data database.
 ///
 /// If `seed` is `null`, database migrations will be attempted without it.
@@ -235,13 +351,18 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_initDataD
 ) -> jint {
     let res = catch_unwind(&mut env, |env| {
         let network = parse_network(network_id as u32)?;
-        let mut db_data = wallet_db(env, network, db_data)
+        let db_path = path_from_jni(env, db_data)?;
+        let mut db_data = wallet_db_at(&db_path, network)
             .map_err(|e| anyhow!("Error while opening data DB: {}", e))?;
 
         let seed = utils::java_nullable_bytes_to_rust(env, &seed)?.map(SecretVec::new);
 
         match init_wallet_db(&mut db_data, seed) {
-            Ok(()) => Ok(0),
+            Ok(()) => {
+                sdk_schema::init(&wallet_db_conn_mut(&db_path)?)
+                    .map_err(|e| anyhow!("Error while initializing data DB: {}", e))?;
+                Ok(0)
+            }
             Err(e)
                 if matches!(
                     e.source().and_then(|e| e.downcast_ref()),
@@ -1126,6 +1247,186 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_getMemoAs
     unwrap_exc_or(&mut env, res, ptr::null_mut())
 }
 
+const JNI_TRANSACTION_OUTPUT: &str = "cash/z/ecc/android/sdk/internal/model/JniTransactionOutput";
+
+fn encode_transaction_output<'a>(
+    env: &mut JNIEnv<'a>,
+    output: tx_history::TxOutput,
+) -> jni::errors::Result<JObject<'a>> {
+    let from_account_uuid = match output.from_account_uuid {
+        Some(uuid) => env.byte_array_from_slice(&uuid)?.into(),
+        None => JObject::null(),
+    };
+    let to_account_uuid = match output.to_account_uuid {
+        Some(uuid) => env.byte_array_from_slice(&uuid)?.into(),
+        None => JObject::null(),
+    };
+    let recipient = match output.recipient {
+        Some(recipient) => env.new_string(recipient)?.into(),
+        None => JObject::null(),
+    };
+    let memo = match &output.memo {
+        Some(memo) => env.byte_array_from_slice(memo.as_array())?.into(),
+        None => JObject::null(),
+    };
+
+    env.new_object(
+        JNI_TRANSACTION_OUTPUT,
+        "(II[B[BLjava/lang/String;JZ[BI)V",
+        &[
+            JValue::Int(pool_code(output.pool)),
+            JValue::Int(i32::try_from(output.output_index).expect("output index fits in i32")),
+            (&from_account_uuid).into(),
+            (&to_account_uuid).into(),
+            (&recipient).into(),
+            JValue::Long(ZatBalance::from(output.value).into()),
+            JValue::Bool(output.is_change.into()),
+            (&memo).into(),
+            // Use -1 to return null across the FFI.
+            JValue::Int(
+                output
+                    .memo
+                    .as_ref()
+                    .map_or(-1, |memo| memo::MemoKind::classify(memo).code()),
+            ),
+        ],
+    )
+}
+
+/// Returns every output of the given transaction that was sent or received by the
+/// wallet, including memos that are not UTF-8 text.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_getTransactionDetail<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    txid_bytes: JByteArray<'local>,
+) -> jobjectArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.getTransactionDetail").entered();
+        let conn = wallet_db_conn(&path_from_jni(env, db_data)?)?;
+        let txid = parse_txid(env, txid_bytes)?;
+
+        let outputs = tx_history::transaction_outputs(&conn, &txid)?;
+
+        Ok(
+            utils::rust_vec_to_java(env, outputs, JNI_TRANSACTION_OUTPUT, |env, output| {
+                encode_transaction_output(env, output)
+            })?
+            .into_raw(),
+        )
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+/// Returns the raw 512-byte memo of the given output, or null if the output has no memo.
+///
+/// Unlike `getMemoAsUtf8`, this returns memos of every kind.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_getMemoBytes<'local>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    txid_bytes: JByteArray<'local>,
+    pool_type: jint,
+    output_index: jint,
+) -> jbyteArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.getMemoBytes").entered();
+        let conn = wallet_db_conn(&path_from_jni(env, db_data)?)?;
+        let txid = parse_txid(env, txid_bytes)?;
+        let pool = parse_pool(pool_type)?;
+        let output_index = u32::try_from(output_index)?;
+
+        let output = tx_history::transaction_outputs(&conn, &txid)?
+            .into_iter()
+            .find(|o| o.pool == pool && o.output_index == output_index)
+            .ok_or_else(|| anyhow!("Output not found in the wallet"))?;
+
+        match output.memo {
+            Some(memo) => Ok(utils::rust_bytes_to_java(env, memo.as_array())?.into_raw()),
+            None => Ok(ptr::null_mut()),
+        }
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+const JNI_DECODED_MEMO: &str = "cash/z/ecc/android/sdk/internal/model/JniDecodedMemo";
+
+fn encode_decoded_memo<'a>(
+    env: &mut JNIEnv<'a>,
+    memo: memo::DecodedMemo,
+) -> jni::errors::Result<JObject<'a>> {
+    let text = match memo.text {
+        Some(text) => env.new_string(text)?.into(),
+        None => JObject::null(),
+    };
+    let data = match memo.data {
+        Some(data) => env.byte_array_from_slice(&data)?.into(),
+        None => JObject::null(),
+    };
+    let reply_to = match memo.reply_to {
+        Some(reply_to) => env.new_string(reply_to)?.into(),
+        None => JObject::null(),
+    };
+
+    env.new_object(
+        JNI_DECODED_MEMO,
+        "(ILjava/lang/String;[BLjava/lang/String;)V",
+        &[
+            JValue::Int(memo.kind.code()),
+            (&text).into(),
+            (&data).into(),
+            (&reply_to).into(),
+        ],
+    )
+}
+
+/// Decodes a memo of up to 512 bytes according to ZIP 302.
+///
+/// Text memos are checked for a trailing reply-to address that is valid for the given
+/// network.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_decodeMemo<'local>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    memo: JByteArray<'local>,
+    network_id: jint,
+) -> jobject {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.decodeMemo").entered();
+        let network = parse_network(network_id as u32)?;
+        let memo = MemoBytes::from_bytes(&utils::java_bytes_to_rust(env, &memo)?)
+            .map_err(|e| anyhow!("Invalid MemoBytes: {}", e))?;
+
+        Ok(encode_decoded_memo(env, memo::decode(&network, &memo))?.into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+/// Encodes up to 511 bytes of data as a ZIP 302 arbitrary-data memo, returning the
+/// 512-byte memo.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_encodeArbitraryMemoBytes<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    data: JByteArray<'local>,
+) -> jbyteArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.encodeArbitraryMemoBytes").entered();
+        let data = utils::java_bytes_to_rust(env, &data)?;
+
+        let memo = memo::encode_arbitrary(&data)?;
+
+        Ok(utils::rust_bytes_to_java(env, memo.as_array())?.into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
 fn encode_blockmeta<'a>(env: &mut JNIEnv<'a>, meta: BlockMeta) -> jni::errors::Result<JObject<'a>> {
     let block_hash = env.byte_array_from_slice(&meta.block_hash.0)?;
     env.new_object(
@@ -1272,6 +1573,97 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_rewindBlo
     unwrap_exc_or(&mut env, res, ())
 }
 
+/// Removes cached blocks below the wallet's fully-scanned height, returning the number of
+/// blocks removed.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_pruneBlockCache<'local>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    fsblockdb_root: JString<'local>,
+    db_data: JString<'local>,
+    network_id: jint,
+) -> jlong {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.pruneBlockCache").entered();
+        let network = parse_network(network_id as u32)?;
+        let root = path_from_jni(env, fsblockdb_root)?;
+        let db_data = wallet_db(env, network, db_data)?;
+
+        let fully_scanned = db_data
+            .block_fully_scanned()
+            .map_err(|e| anyhow!("Failed to read block metadata from WalletDb: {:?}", e))?;
+
+        match fully_scanned {
+            Some(metadata) => block_cache::prune_below(&root, metadata.block_height())
+                .map(|pruned| pruned as i64)
+                .map_err(|e| anyhow!("Error while pruning block cache: {}", e)),
+            None => Ok(0),
+        }
+    });
+    unwrap_exc_or(&mut env, res, -1)
+}
+
+fn encode_heights<'a>(
+    env: &mut JNIEnv<'a>,
+    heights: impl Iterator<Item = BlockHeight>,
+) -> jni::errors::Result<JLongArray<'a>> {
+    let heights = heights.map(|h| i64::from(u32::from(h))).collect::<Vec<_>>();
+    let array = env.new_long_array(heights.len() as jsize)?;
+    env.set_long_array_region(&array, 0, &heights)?;
+    Ok(array)
+}
+
+fn encode_block_cache_report<'a>(
+    env: &mut JNIEnv<'a>,
+    report: block_cache::Report,
+) -> jni::errors::Result<JObject<'a>> {
+    // Use -1 to return null across the FFI.
+    let (min_height, max_height) = report.heights.as_ref().map_or((-1, -1), |heights| {
+        (
+            i64::from(u32::from(heights.start)),
+            i64::from(u32::from(heights.end - 1)),
+        )
+    });
+    let missing = encode_heights(env, report.missing.into_iter())?;
+    let corrupt = encode_heights(env, report.corrupt.into_iter())?;
+    let hole_starts = encode_heights(env, report.holes.iter().map(|h| h.start))?;
+    let hole_ends = encode_heights(env, report.holes.iter().map(|h| h.end))?;
+
+    env.new_object(
+        "cash/z/ecc/android/sdk/internal/model/JniBlockCacheReport",
+        "(JJJ[J[J[J[JJ)V",
+        &[
+            JValue::Long(report.block_count as i64),
+            JValue::Long(min_height),
+            JValue::Long(max_height),
+            (&missing).into(),
+            (&corrupt).into(),
+            (&hole_starts).into(),
+            (&hole_ends).into(),
+            JValue::Long(report.total_bytes as i64),
+        ],
+    )
+}
+
+/// Checks that every block indexed by the block cache has an intact file, and reports
+/// gaps in the cached heights and the size of the cache.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_verifyBlockCache<'local>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    fsblockdb_root: JString<'local>,
+) -> jobject {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.verifyBlockCache").entered();
+        let root = path_from_jni(env, fsblockdb_root)?;
+
+        let report = block_cache::verify(&root)
+            .map_err(|e| anyhow!("Error while verifying block cache: {}", e))?;
+        Ok(encode_block_cache_report(env, report)?.into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
 fn encode_rewind_result<'a>(
     env: &mut JNIEnv<'a>,
     requested_height: BlockHeight,
@@ -1324,6 +1716,88 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_rewindToH
     unwrap_exc_or(&mut env, res, ptr::null_mut())
 }
 
+const JNI_REWIND_AFFECTED_OUTPUT: &str =
+    "cash/z/ecc/android/sdk/internal/model/JniRewindAffectedOutput";
+
+fn encode_rewind_affected_output<'a>(
+    env: &mut JNIEnv<'a>,
+    output: rewind_preview::AffectedOutput,
+) -> jni::errors::Result<JObject<'a>> {
+    env.new_object(
+        JNI_REWIND_AFFECTED_OUTPUT,
+        "([B[BIIJJ)V",
+        &[
+            (&env.byte_array_from_slice(output.account_uuid.expose_uuid().as_bytes())?).into(),
+            (&env.byte_array_from_slice(output.txid.as_ref())?).into(),
+            JValue::Int(pool_code(output.pool)),
+            JValue::Int(i32::try_from(output.output_index).expect("output index fits in i32")),
+            JValue::Long(ZatBalance::from(output.value).into()),
+            JValue::Long(i64::from(u32::from(output.mined_height))),
+        ],
+    )
+}
+
+fn encode_rewind_preview<'a>(
+    env: &mut JNIEnv<'a>,
+    preview: rewind_preview::RewindPreview,
+) -> jni::errors::Result<JObject<'a>> {
+    let unmined_txids = utils::rust_vec_to_java(env, preview.unmined_txids, "[B", |env, txid| {
+        utils::rust_bytes_to_java(env, txid.as_ref())
+    })?;
+    let affected_outputs = utils::rust_vec_to_java(
+        env,
+        preview.affected_outputs,
+        JNI_REWIND_AFFECTED_OUTPUT,
+        |env, output| encode_rewind_affected_output(env, output),
+    )?;
+
+    env.new_object(
+        "cash/z/ecc/android/sdk/internal/model/JniRewindPreview",
+        format!("(JJJ[[B[L{};J)V", JNI_REWIND_AFFECTED_OUTPUT),
+        &[
+            JValue::Long(u32::from(preview.requested_height).into()),
+            // Use -1 to return null across the FFI.
+            JValue::Long(
+                preview
+                    .rewind_height
+                    .map_or(-1, |h| i64::from(u32::from(h))),
+            ),
+            JValue::Long(
+                preview
+                    .safe_rewind_height
+                    .map_or(-1, |h| i64::from(u32::from(h))),
+            ),
+            (&unmined_txids).into(),
+            (&affected_outputs).into(),
+            JValue::Long(preview.blocks_to_rescan as i64),
+        ],
+    )
+}
+
+/// Reports what `rewindToHeight` would do for the given height, without modifying the
+/// wallet.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_previewRewindToHeight<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    height: jlong,
+) -> jobject {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.previewRewindToHeight").entered();
+        let conn = wallet_db_conn(&path_from_jni(env, db_data)?)?;
+
+        let height = BlockHeight::try_from(height)?;
+        let preview = rewind_preview::preview(&conn, height)?;
+
+        Ok(encode_rewind_preview(env, preview)?.into_raw())
+    });
+
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
 fn decode_subtree_root<H>(
     env: &mut JNIEnv,
     obj: JObject,
@@ -1624,20 +2098,463 @@ fn encode_scan_range<'a>(
     )
 }
 
+const JNI_RECEIVED_OUTPUT: &str = "cash/z/ecc/android/sdk/internal/model/JniReceivedOutput";
+
+fn encode_received_output<'a>(
+    env: &mut JNIEnv<'a>,
+    received: received_outputs::ReceivedOutput,
+) -> jni::errors::Result<JObject<'a>> {
+    env.new_object(
+        JNI_RECEIVED_OUTPUT,
+        "([BIIJJJIZZZ)V",
+        &[
+            (&env.byte_array_from_slice(received.output.txid.as_ref())?).into(),
+            JValue::Int(pool_code(received.output.pool)),
+            JValue::Int(
+                i32::try_from(received.output.output_index).expect("output index fits in i32"),
+            ),
+            JValue::Long(ZatBalance::from(received.value).into()),
+            // Use -1 to return null across the FFI.
+            JValue::Long(
+                received
+                    .mined_height
+                    .map_or(-1, |h| i64::from(u32::from(h))),
+            ),
+            JValue::Long(i64::from(received.confirmations)),
+            JValue::Int(received.spend_status.code()),
+            JValue::Bool(received.has_memo.into()),
+            JValue::Bool(received.is_change.into()),
+            JValue::Bool(received.is_spendable.into()),
+        ],
+    )
+}
+
+/// Returns a page of the Sapling and Orchard notes and transparent outputs received by
+/// the given account, most recent first, along with their spend status.
 #[unsafe(no_mangle)]
-pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_suggestScanRanges<'local>(
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_listReceivedOutputs<
+    'local,
+>(
     mut env: JNIEnv<'local>,
     _: JClass<'local>,
     db_data: JString<'local>,
+    account_uuid: JByteArray<'local>,
+    offset: jlong,
+    limit: jlong,
     network_id: jint,
 ) -> jobjectArray {
     let res = catch_unwind(&mut env, |env| {
-        let _span = tracing::info_span!("RustBackend.suggestScanRanges").entered();
+        let _span = tracing::info_span!("RustBackend.listReceivedOutputs").entered();
         let network = parse_network(network_id as u32)?;
-        let db_data = wallet_db(env, network, db_data)?;
+        let db_path = path_from_jni(env, db_data)?;
+        let db_data = wallet_db_at(&db_path, network)?;
+        let conn = wallet_db_conn(&db_path)?;
+        let account_uuid = account_id_from_jni(env, account_uuid)?;
+        let offset = u32::try_from(offset).map_err(|_| anyhow!("Invalid offset {}", offset))?;
+        let limit = u32::try_from(limit).map_err(|_| anyhow!("Invalid limit {}", limit))?;
 
-        let ranges = db_data
-            .suggest_scan_ranges()
+        let received =
+            received_outputs::list_received_outputs(&conn, &db_data, account_uuid, offset, limit)?;
+
+        Ok(
+            utils::rust_vec_to_java(env, received, JNI_RECEIVED_OUTPUT, |env, received| {
+                encode_received_output(env, received)
+            })?
+            .into_raw(),
+        )
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+const JNI_TRANSPARENT_RECEIVER_BALANCE: &str =
+    "cash/z/ecc/android/sdk/internal/model/JniTransparentReceiverBalance";
+
+fn encode_transparent_receiver_balance<'a>(
+    env: &mut JNIEnv<'a>,
+    balance: received_outputs::TransparentReceiverBalance,
+) -> jni::errors::Result<JObject<'a>> {
+    let scope = match balance.scope {
+        TransparentKeyScope::EXTERNAL => 0,
+        TransparentKeyScope::INTERNAL => 1,
+        TransparentKeyScope::EPHEMERAL => 2,
+        // Use -1 for scopes not known to the SDK.
+        _ => -1,
+    };
+    env.new_object(
+        JNI_TRANSPARENT_RECEIVER_BALANCE,
+        "(Ljava/lang/String;IJJJI)V",
+        &[
+            (&env.new_string(balance.address)?).into(),
+            JValue::Int(scope),
+            JValue::Long(ZatBalance::from(balance.spendable).into()),
+            JValue::Long(ZatBalance::from(balance.pending).into()),
+            JValue::Long(ZatBalance::from(balance.immature).into()),
+            JValue::Int(i32::try_from(balance.utxo_count).unwrap_or(i32::MAX)),
+        ],
+    )
+}
+
+/// Returns the funds held by each of the account's transparent receivers, including
+/// change and ephemeral receivers, with their key scopes and UTXO counts.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_getTransparentReceiverBalances<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    account_uuid: JByteArray<'local>,
+    network_id: jint,
+) -> jobjectArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.getTransparentReceiverBalances").entered();
+        let network = parse_network(network_id as u32)?;
+        let db_path = path_from_jni(env, db_data)?;
+        let db_data = wallet_db_at(&db_path, network)?;
+        let conn = wallet_db_conn(&db_path)?;
+        let account_uuid = account_id_from_jni(env, account_uuid)?;
+
+        let balances = received_outputs::transparent_receiver_balances(
+            &conn,
+            &db_data,
+            &network,
+            account_uuid,
+        )?;
+
+        Ok(utils::rust_vec_to_java(
+            env,
+            balances,
+            JNI_TRANSPARENT_RECEIVER_BALANCE,
+            |env, balance| encode_transparent_receiver_balance(env, balance),
+        )?
+        .into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+const JNI_HISTORICAL_BALANCE: &str = "cash/z/ecc/android/sdk/internal/model/JniHistoricalBalance";
+
+fn encode_historical_balance<'a>(
+    env: &mut JNIEnv<'a>,
+    balance: balance_history::HistoricalBalance,
+) -> jni::errors::Result<JObject<'a>> {
+    env.new_object(
+        JNI_HISTORICAL_BALANCE,
+        "(JJJJJ)V",
+        &[
+            JValue::Long(i64::from(u32::from(balance.height))),
+            // Use -1 to return null across the FFI.
+            JValue::Long(balance.block_time.map_or(-1, i64::from)),
+            JValue::Long(ZatBalance::from(balance.transparent).into()),
+            JValue::Long(ZatBalance::from(balance.sapling).into()),
+            JValue::Long(ZatBalance::from(balance.orchard).into()),
+        ],
+    )
+}
+
+/// Returns the height up to which the wallet has scanned every block.
+fn fully_scanned_height(
+    db_data: &WalletDb<rusqlite::Connection, Network, SystemClock, OsRng>,
+) -> anyhow::Result<Option<BlockHeight>> {
+    Ok(db_data
+        .block_fully_scanned()
+        .map_err(|e| anyhow!("Error while fetching fully scanned height: {}", e))?
+        .map(|meta| meta.block_height()))
+}
+
+/// Returns the account's balance in each pool as of the given height, counting only
+/// mined transactions. The height must not be above the wallet's fully scanned height.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_getBalanceAtHeight<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    account_uuid: JByteArray<'local>,
+    height: jlong,
+    network_id: jint,
+) -> jobject {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.getBalanceAtHeight").entered();
+        let network = parse_network(network_id as u32)?;
+        let db_path = path_from_jni(env, db_data)?;
+        let fully_scanned = fully_scanned_height(&wallet_db_at(&db_path, network)?)?;
+        let conn = wallet_db_conn(&db_path)?;
+        let account_uuid = account_id_from_jni(env, account_uuid)?;
+        let height = BlockHeight::try_from(height)?;
+
+        let balance = balance_history::balance_at(&conn, account_uuid, height, fully_scanned)?;
+
+        Ok(encode_historical_balance(env, balance)?.into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+/// Returns the account's balance in each pool over the given range of heights, counting
+/// only mined transactions. The range must not extend above the wallet's fully scanned
+/// height.
+///
+/// The series is sampled every `interval_blocks` blocks and at `to_height`, or, if
+/// `interval_blocks` is -1, at the last scanned block of each UTC day.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_getBalanceHistory<'local>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    account_uuid: JByteArray<'local>,
+    from_height: jlong,
+    to_height: jlong,
+    interval_blocks: jint,
+    network_id: jint,
+) -> jobjectArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.getBalanceHistory").entered();
+        let network = parse_network(network_id as u32)?;
+        let db_path = path_from_jni(env, db_data)?;
+        let fully_scanned = fully_scanned_height(&wallet_db_at(&db_path, network)?)?;
+        let conn = wallet_db_conn(&db_path)?;
+        let account_uuid = account_id_from_jni(env, account_uuid)?;
+        let from_height = BlockHeight::try_from(from_height)?;
+        let to_height = BlockHeight::try_from(to_height)?;
+        let interval = match interval_blocks {
+            -1 => balance_history::Interval::Days,
+            n => balance_history::Interval::Blocks(
+                u32::try_from(n).map_err(|_| anyhow!("Invalid interval: {n}"))?,
+            ),
+        };
+
+        let series = balance_history::balance_series(
+            &conn,
+            account_uuid,
+            from_height,
+            to_height,
+            interval,
+            fully_scanned,
+        )?;
+
+        Ok(
+            utils::rust_vec_to_java(env, series, JNI_HISTORICAL_BALANCE, |env, balance| {
+                encode_historical_balance(env, balance)
+            })?
+            .into_raw(),
+        )
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+fn decode_history_query(
+    env: &mut JNIEnv,
+    obj: JObject,
+) -> anyhow::Result<(tx_history::HistoryFilter, Option<tx_history::Cursor>, u32)> {
+    let optional_long = |env: &mut JNIEnv, name: &str| -> anyhow::Result<Option<i64>> {
+        let value = env.get_field(&obj, name, "J")?.j()?;
+        Ok((value != -1).then_some(value))
+    };
+    let optional_int = |env: &mut JNIEnv, name: &str| -> anyhow::Result<Option<i32>> {
+        let value = env.get_field(&obj, name, "I")?.i()?;
+        Ok((value != -1).then_some(value))
+    };
+
+    let account_uuid = {
+        let field = JByteArray::from(env.get_field(&obj, "accountUuid", "[B")?.l()?);
+        match utils::java_nullable_bytes_to_rust(env, &field)? {
+            Some(bytes) => Some(AccountUuid::from_uuid(Uuid::from_slice(&bytes)?)),
+            None => None,
+        }
+    };
+    let height = |value: Option<i64>| -> anyhow::Result<Option<BlockHeight>> {
+        value
+            .map(|h| Ok(BlockHeight::from_u32(u32::try_from(h)?)))
+            .transpose()
+    };
+    let from_height = height(optional_long(env, "fromHeight")?)?;
+    let to_height = height(optional_long(env, "toHeight")?)?;
+    let from_time = optional_long(env, "fromTime")?;
+    let to_time = optional_long(env, "toTime")?;
+    let direction = optional_int(env, "direction")?
+        .map(|code| match code {
+            0 => Ok(tx_history::Direction::Sent),
+            1 => Ok(tx_history::Direction::Received),
+            2 => Ok(tx_history::Direction::Shielding),
+            _ => Err(anyhow!("Transaction direction not recognized: {code}")),
+        })
+        .transpose()?;
+    let pool = optional_int(env, "poolType")?.map(parse_pool).transpose()?;
+    let memo_text = {
+        let field = JString::from(env.get_field(&obj, "memoText", "Ljava/lang/String;")?.l()?);
+        utils::java_nullable_string_to_rust(env, &field)?
+    };
+    let status = optional_int(env, "status")?
+        .map(|code| match code {
+            0 => Ok(tx_history::Status::Pending),
+            1 => Ok(tx_history::Status::Mined),
+            2 => Ok(tx_history::Status::Expired),
+            _ => Err(anyhow!("Transaction status not recognized: {code}")),
+        })
+        .transpose()?;
+    let cursor = {
+        let field = JByteArray::from(env.get_field(&obj, "cursor", "[B")?.l()?);
+        utils::java_nullable_bytes_to_rust(env, &field)?
+            .map(|bytes| tx_history::Cursor::decode(&bytes))
+            .transpose()?
+    };
+    let limit = match u32::try_from(env.get_field(&obj, "limit", "I")?.i()?) {
+        Ok(limit) if limit > 0 => limit,
+        _ => return Err(anyhow!("Page size must be positive")),
+    };
+
+    Ok((
+        tx_history::HistoryFilter {
+            account_uuid,
+            from_height,
+            to_height,
+            from_time,
+            to_time,
+            direction,
+            pool,
+            memo_text,
+            status,
+        },
+        cursor,
+        limit,
+    ))
+}
+
+const JNI_TRANSACTION_RECIPIENT: &str =
+    "cash/z/ecc/android/sdk/internal/model/JniTransactionRecipient";
+
+fn encode_transaction_recipient<'a>(
+    env: &mut JNIEnv<'a>,
+    recipient: tx_history::Recipient,
+) -> jni::errors::Result<JObject<'a>> {
+    let address = match recipient.address {
+        Some(address) => env.new_string(address)?.into(),
+        None => JObject::null(),
+    };
+    let account_uuid = match recipient.account_uuid {
+        Some(uuid) => env.byte_array_from_slice(&uuid)?.into(),
+        None => JObject::null(),
+    };
+
+    env.new_object(
+        JNI_TRANSACTION_RECIPIENT,
+        "(ILjava/lang/String;[BJ)V",
+        &[
+            JValue::Int(pool_code(recipient.pool)),
+            (&address).into(),
+            (&account_uuid).into(),
+            JValue::Long(recipient.value),
+        ],
+    )
+}
+
+const JNI_TRANSACTION_HISTORY_ENTRY: &str =
+    "cash/z/ecc/android/sdk/internal/model/JniTransactionHistoryEntry";
+
+fn encode_transaction_history_entry<'a>(
+    env: &mut JNIEnv<'a>,
+    entry: tx_history::HistoryEntry,
+) -> jni::errors::Result<JObject<'a>> {
+    let recipients = utils::rust_vec_to_java(
+        env,
+        entry.recipients,
+        JNI_TRANSACTION_RECIPIENT,
+        |env, recipient| encode_transaction_recipient(env, recipient),
+    )?;
+
+    env.new_object(
+        JNI_TRANSACTION_HISTORY_ENTRY,
+        format!("([B[BJJJJJII[L{};)V", JNI_TRANSACTION_RECIPIENT),
+        &[
+            (&env.byte_array_from_slice(entry.txid.as_ref())?).into(),
+            (&env.byte_array_from_slice(&entry.account_uuid)?).into(),
+            // Use -1 to return null across the FFI.
+            JValue::Long(entry.mined_height.map_or(-1, |h| i64::from(u32::from(h)))),
+            JValue::Long(entry.expiry_height.map_or(-1, |h| i64::from(u32::from(h)))),
+            JValue::Long(entry.block_time.unwrap_or(-1)),
+            JValue::Long(entry.net_value),
+            JValue::Long(entry.fee.unwrap_or(-1)),
+            JValue::Int(match entry.direction {
+                tx_history::Direction::Sent => 0,
+                tx_history::Direction::Received => 1,
+                tx_history::Direction::Shielding => 2,
+            }),
+            JValue::Int(match entry.status {
+                tx_history::Status::Pending => 0,
+                tx_history::Status::Mined => 1,
+                tx_history::Status::Expired => 2,
+            }),
+            (&recipients).into(),
+        ],
+    )
+}
+
+const JNI_TRANSACTION_HISTORY_PAGE: &str =
+    "cash/z/ecc/android/sdk/internal/model/JniTransactionHistoryPage";
+
+fn encode_transaction_history_page<'a>(
+    env: &mut JNIEnv<'a>,
+    page: tx_history::HistoryPage,
+) -> jni::errors::Result<JObject<'a>> {
+    let entries = utils::rust_vec_to_java(
+        env,
+        page.entries,
+        JNI_TRANSACTION_HISTORY_ENTRY,
+        |env, entry| encode_transaction_history_entry(env, entry),
+    )?;
+    let next_cursor = match page.next_cursor {
+        Some(cursor) => env.byte_array_from_slice(&cursor.encode())?.into(),
+        None => JObject::null(),
+    };
+
+    env.new_object(
+        JNI_TRANSACTION_HISTORY_PAGE,
+        format!("([L{};[B)V", JNI_TRANSACTION_HISTORY_ENTRY),
+        &[(&entries).into(), (&next_cursor).into()],
+    )
+}
+
+/// Returns a page of the wallet's transaction history matching the given
+/// `JniTransactionHistoryQuery`, most recent first.
+///
+/// To fetch the following page, repeat the query with its cursor set to the
+/// `nextCursor` of the returned page.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_queryTransactionHistory<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    query: JObject<'local>,
+) -> jobject {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.queryTransactionHistory").entered();
+        let conn = wallet_db_conn(&path_from_jni(env, db_data)?)?;
+        let (filter, cursor, limit) = decode_history_query(env, query)?;
+
+        let page = tx_history::query_history(&conn, &filter, cursor.as_ref(), limit)?;
+
+        Ok(encode_transaction_history_page(env, page)?.into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_suggestScanRanges<'local>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    network_id: jint,
+) -> jobjectArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.suggestScanRanges").entered();
+        let network = parse_network(network_id as u32)?;
+        let db_data = wallet_db(env, network, db_data)?;
+
+        let ranges = db_data
+            .suggest_scan_ranges()
             .map_err(|e| anyhow!("Error while fetching suggested scan ranges: {}", e))?;
 
         Ok(utils::rust_vec_to_java(
@@ -1654,20 +2571,64 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_suggestSc
 fn encode_scan_summary<'a>(
     env: &mut JNIEnv<'a>,
     scan_summary: ScanSummary,
+    metrics: block_source::ScanMetrics,
 ) -> anyhow::Result<JObject<'a>> {
     let scanned_range = scan_summary.scanned_range();
     Ok(env.new_object(
         "cash/z/ecc/android/sdk/internal/model/JniScanSummary",
-        "(JJJJ)V",
+        "(JJJJJJJJJ)V",
         &[
             i64::from(u32::from(scanned_range.start)).into(),
             i64::from(u32::from(scanned_range.end)).into(),
             i64::try_from(scan_summary.spent_sapling_note_count())?.into(),
             i64::try_from(scan_summary.received_sapling_note_count())?.into(),
+            i64::try_from(scan_summary.spent_orchard_note_count())?.into(),
+            i64::try_from(scan_summary.received_orchard_note_count())?.into(),
+            i64::try_from(metrics.blocks_processed)?.into(),
+            i64::try_from(metrics.read_and_scan_time.as_millis())?.into(),
+            i64::try_from(metrics.db_write_time.as_millis())?.into(),
         ],
     )?)
 }
 
+/// Scans up to `limit` blocks from the given block source, starting at `from_height`,
+/// and returns the `JniScanSummary` for the scan.
+///
+/// The summary has no count of transparent outputs: those are not found by scanning
+/// compact blocks, but added to the wallet by UTXO and transaction enhancement fetches.
+fn scan_blocks_from<'a, S>(
+    env: &mut JNIEnv<'a>,
+    network: Network,
+    blocks: &S,
+    db_path: &Path,
+    from_height: BlockHeight,
+    from_state: &ChainState,
+    limit: usize,
+) -> anyhow::Result<JObject<'a>>
+where
+    S: BlockSource,
+    S::Error: std::fmt::Display,
+{
+    let mut db_data = wallet_db_at(db_path, network)?;
+    let block_source = block_source::InstrumentedBlockSource::new(blocks);
+
+    match scan_cached_blocks(
+        &network,
+        &block_source,
+        &mut db_data,
+        from_height,
+        from_state,
+        limit,
+    ) {
+        Ok(scan_summary) => encode_scan_summary(env, scan_summary, block_source.finish()),
+        Err(e) => Err(anyhow!(
+            "Rust error while scanning blocks (limit {:?}): {}",
+            limit,
+            e
+        )),
+    }
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_scanBlocks<'local>(
     mut env: JNIEnv<'local>,
@@ -1683,26 +2644,68 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_scanBlock
         let _span = tracing::info_span!("RustBackend.scanBlocks").entered();
         let network = parse_network(network_id as u32)?;
         let db_cache = block_db(env, db_cache)?;
-        let mut db_data = wallet_db(env, network, db_data)?;
+        let db_path = path_from_jni(env, db_data)?;
         let from_height = BlockHeight::try_from(from_height)?;
         let from_state = parse_treestate(env, from_state)?.to_chain_state()?;
         let limit = usize::try_from(limit)?;
 
-        match scan_cached_blocks(
-            &network,
+        Ok(scan_blocks_from(
+            env,
+            network,
             &db_cache,
-            &mut db_data,
+            &db_path,
             from_height,
             &from_state,
             limit,
-        ) {
-            Ok(scan_summary) => Ok(encode_scan_summary(env, scan_summary)?.into_raw()),
-            Err(e) => Err(anyhow!(
-                "Rust error while scanning blocks (limit {:?}): {}",
-                limit,
-                e
-            )),
-        }
+        )?
+        .into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+/// Scans the given serialized `CompactBlock`s, starting at `from_height`, without
+/// writing them to the block cache.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_scanBlocksFromMemory<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    blocks: JObjectArray<'local>,
+    from_height: jlong,
+    from_state: JByteArray<'local>,
+    limit: jlong,
+    network_id: jint,
+) -> jobject {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.scanBlocksFromMemory").entered();
+        let network = parse_network(network_id as u32)?;
+        let db_path = path_from_jni(env, db_data)?;
+        let blocks = {
+            let count = env.get_array_length(&blocks)?;
+            let encoded = (0..count)
+                .map(|i| {
+                    let block = JByteArray::from(env.get_object_array_element(&blocks, i)?);
+                    utils::java_bytes_to_rust(env, &block)
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            block_source::MemoryBlockSource::decode(&encoded)?
+        };
+        let from_height = BlockHeight::try_from(from_height)?;
+        let from_state = parse_treestate(env, from_state)?.to_chain_state()?;
+        let limit = usize::try_from(limit)?;
+
+        Ok(scan_blocks_from(
+            env,
+            network,
+            &blocks,
+            &db_path,
+            from_height,
+            &from_state,
+            limit,
+        )?
+        .into_raw())
     });
     unwrap_exc_or(&mut env, res, ptr::null_mut())
 }
@@ -1985,17 +2988,121 @@ fn zip317_helper<DbT>(
     )
 }
 
+const JNI_UNMINED_TRANSACTION: &str = "cash/z/ecc/android/sdk/internal/model/JniUnminedTransaction";
+
+fn encode_unmined_transaction<'a>(
+    env: &mut JNIEnv<'a>,
+    tx: unmined::UnminedTx,
+) -> jni::errors::Result<JObject<'a>> {
+    env.new_object(
+        JNI_UNMINED_TRANSACTION,
+        "([BJZZ)V",
+        &[
+            (&env.byte_array_from_slice(tx.txid.as_ref())?).into(),
+            // Use -1 to return null across the FFI.
+            JValue::Long(tx.expiry_height.map_or(-1, |h| i64::from(u32::from(h)))),
+            JValue::Bool(tx.is_expired.into()),
+            JValue::Bool(tx.has_raw.into()),
+        ],
+    )
+}
+
+/// Returns the transactions created by the wallet that have not been mined and have not
+/// been abandoned, with their expiry heights.
 #[unsafe(no_mangle)]
-pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_proposeTransferFromUri<
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_listUnminedTransactions<
     'local,
 >(
     mut env: JNIEnv<'local>,
     _: JClass<'local>,
     db_data: JString<'local>,
-    account_uuid: JByteArray<'local>,
-    payment_uri: JString<'local>,
     network_id: jint,
-) -> jbyteArray {
+) -> jobjectArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.listUnminedTransactions").entered();
+        let network = parse_network(network_id as u32)?;
+        let db_path = path_from_jni(env, db_data)?;
+        let db_data = wallet_db_at(&db_path, network)?;
+        let conn = wallet_db_conn(&db_path)?;
+
+        let unmined = unmined::list_unmined(&conn, fully_scanned_height(&db_data)?)?;
+
+        Ok(
+            utils::rust_vec_to_java(env, unmined, JNI_UNMINED_TRANSACTION, |env, tx| {
+                encode_unmined_transaction(env, tx)
+            })?
+            .into_raw(),
+        )
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+/// Returns the raw bytes of the given transaction from the wallet database, for
+/// resubmission, or null if the wallet does not have them.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_getRawTransaction<'local>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    txid_bytes: JByteArray<'local>,
+) -> jbyteArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.getRawTransaction").entered();
+        let conn = wallet_db_conn(&path_from_jni(env, db_data)?)?;
+        let txid = parse_txid(env, txid_bytes)?;
+
+        match unmined::raw_transaction(&conn, &txid)? {
+            Some(raw) => Ok(utils::rust_bytes_to_java(env, &raw)?.into_raw()),
+            None => Ok(ptr::null_mut()),
+        }
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+/// Marks an expired, unmined transaction as abandoned, so that it is no longer returned
+/// by `listUnminedTransactions`.
+///
+/// Throws if the transaction has been mined or has not yet expired.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_abandonTransaction<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    txid_bytes: JByteArray<'local>,
+    network_id: jint,
+) {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.abandonTransaction").entered();
+        let network = parse_network(network_id as u32)?;
+        let db_path = path_from_jni(env, db_data)?;
+        let db_data = wallet_db_at(&db_path, network)?;
+        let mut conn = wallet_db_conn_mut(&db_path)?;
+        let txid = parse_txid(env, txid_bytes)?;
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        unmined::abandon(
+            &mut conn,
+            &txid,
+            fully_scanned_height(&db_data)?,
+            i64::try_from(now)?,
+        )
+    });
+    unwrap_exc_or(&mut env, res, ())
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_proposeTransferFromUri<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    account_uuid: JByteArray<'local>,
+    payment_uri: JString<'local>,
+    network_id: jint,
+) -> jbyteArray {
     let res = catch_unwind(&mut env, |env| {
         let _span = tracing::info_span!("RustBackend.proposeTransfer").entered();
         let network = parse_network(network_id as u32)?;
@@ -2071,26 +3178,491 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_proposeTr
         ])
         .map_err(|e| anyhow!("Error creating transaction request: {:?}", e))?;
 
-        let proposal = propose_transfer::<_, _, _, _, Infallible>(
-            &mut db_data,
-            &network,
+        let proposal = propose_transfer::<_, _, _, _, Infallible>(
+            &mut db_data,
+            &network,
+            account_uuid,
+            &input_selector,
+            &change_strategy,
+            request,
+            wallet::ConfirmationsPolicy::default(),
+        )
+        .map_err(|e| anyhow!("Error creating transaction proposal: {}", e))?;
+
+        Ok(utils::rust_bytes_to_java(
+            env,
+            Proposal::from_standard_proposal(&proposal)
+                .encode_to_vec()
+                .as_ref(),
+        )?
+        .into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+const JNI_SPENDABLE_INPUT: &str = "cash/z/ecc/android/sdk/internal/model/JniSpendableInput";
+
+fn encode_spendable_input<'a>(
+    env: &mut JNIEnv<'a>,
+    output: coin_control::OutputRef,
+    value: Zatoshis,
+    mined_height: Option<BlockHeight>,
+) -> jni::errors::Result<JObject<'a>> {
+    env.new_object(
+        JNI_SPENDABLE_INPUT,
+        "([BIIJJ)V",
+        &[
+            (&env.byte_array_from_slice(output.txid.as_ref())?).into(),
+            JValue::Int(pool_code(output.pool)),
+            JValue::Int(i32::try_from(output.output_index).expect("output index fits in i32")),
+            JValue::Long(ZatBalance::from(value).into()),
+            // Use -1 to return null across the FFI.
+            JValue::Long(mined_height.map_or(-1, |h| i64::from(u32::from(h)))),
+        ],
+    )
+}
+
+fn decode_output_ref(env: &mut JNIEnv, obj: JObject) -> anyhow::Result<coin_control::OutputRef> {
+    let txid = JByteArray::from(env.get_field(&obj, "txid", "[B")?.l()?);
+    let txid = parse_txid(env, txid)?;
+    let pool = parse_pool(env.get_field(&obj, "poolType", "I")?.i()?)?;
+    let output_index = u32::try_from(env.get_field(&obj, "outputIndex", "I")?.i()?)?;
+
+    Ok(coin_control::OutputRef {
+        txid,
+        pool,
+        output_index,
+    })
+}
+
+/// Returns the notes and UTXOs that the given account could spend in a transaction
+/// created now, for use in coin control.
+///
+/// Each input is identified by its txid, pool and output index; pass a subset of the
+/// returned inputs to `proposeTransferFromInputs` to spend exactly those inputs.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_listSpendableInputs<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    account_uuid: JByteArray<'local>,
+    network_id: jint,
+) -> jobjectArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.listSpendableInputs").entered();
+        let network = parse_network(network_id as u32)?;
+        let db_data = wallet_db(env, network, db_data)?;
+        let account_uuid = account_id_from_jni(env, account_uuid)?;
+
+        let inputs = coin_control::SpendableInputs::fetch(
+            &db_data,
+            account_uuid,
+            wallet::ConfirmationsPolicy::default(),
+        )?;
+
+        let sapling = inputs
+            .sapling
+            .iter()
+            .map(|n| {
+                Ok((
+                    coin_control::OutputRef {
+                        txid: *n.txid(),
+                        pool: PoolType::SAPLING,
+                        output_index: n.output_index().into(),
+                    },
+                    n.note_value()?,
+                    n.mined_height(),
+                ))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let orchard = inputs
+            .orchard
+            .iter()
+            .map(|n| {
+                Ok((
+                    coin_control::OutputRef {
+                        txid: *n.txid(),
+                        pool: PoolType::ORCHARD,
+                        output_index: n.output_index().into(),
+                    },
+                    n.note_value()?,
+                    n.mined_height(),
+                ))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let transparent = inputs.transparent.iter().map(|utxo| {
+            (
+                coin_control::OutputRef {
+                    txid: TxId::from_bytes(*utxo.outpoint().hash()),
+                    pool: PoolType::TRANSPARENT,
+                    output_index: utxo.outpoint().n(),
+                },
+                utxo.txout().value(),
+                utxo.mined_height(),
+            )
+        });
+
+        let spendable = sapling
+            .into_iter()
+            .chain(orchard)
+            .chain(transparent)
+            .collect::<Vec<_>>();
+
+        Ok(utils::rust_vec_to_java(
+            env,
+            spendable,
+            JNI_SPENDABLE_INPUT,
+            |env, (output, value, mined_height)| {
+                encode_spendable_input(env, output, value, mined_height)
+            },
+        )?
+        .into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+/// Proposes a transfer that spends exactly the given inputs, rather than letting the
+/// wallet choose which notes and UTXOs to spend.
+///
+/// `inputs` must be a non-empty array of `JniSpendableInput` values, as returned by
+/// `listSpendableInputs`. If the selected inputs do not cover the payment and its fee,
+/// this fails with an error stating the shortfall.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_proposeTransferFromInputs<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    account_uuid: JByteArray<'local>,
+    to: JString<'local>,
+    value: jlong,
+    memo: JByteArray<'local>,
+    inputs: JObjectArray<'local>,
+    network_id: jint,
+) -> jbyteArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.proposeTransferFromInputs").entered();
+        let network = parse_network(network_id as u32)?;
+        let db_data = wallet_db(env, network, db_data)?;
+        let account_uuid = account_id_from_jni(env, account_uuid)?;
+        let to = utils::java_string_to_rust(env, &to)?;
+        let value = Zatoshis::from_nonnegative_i64(value)
+            .map_err(|_| anyhow!("Invalid amount, out of range"))?;
+
+        let to = to
+            .parse()
+            .map_err(|e| anyhow!("Can't parse recipient address: {}", e))?;
+
+        let memo = utils::java_nullable_bytes_to_rust(env, &memo)?
+            .as_deref()
+            .map(MemoBytes::from_bytes)
+            .transpose()
+            .map_err(|e| anyhow!("Invalid MemoBytes: {}", e))?;
+
+        let inputs = {
+            let count = env.get_array_length(&inputs)?;
+            (0..count)
+                .scan(&mut *env, |env, i| {
+                    Some(
+                        env.get_object_array_element(&inputs, i)
+                            .map_err(|e| e.into())
+                            .and_then(|jobj| decode_output_ref(env, jobj)),
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        // Always use ZIP 317 fees
+        let (change_strategy, _) = zip317_helper(None);
+
+        let request = TransactionRequest::new(vec![
+            Payment::new(to, value, memo, None, None, vec![]).ok_or_else(|| {
+                anyhow!("Memos are not permitted when sending to transparent recipients.")
+            })?,
+        ])
+        .map_err(|e| anyhow!("Error creating transaction request: {:?}", e))?;
+
+        let proposal = coin_control::propose_transfer_from_inputs(
+            &db_data,
+            &network,
+            account_uuid,
+            &change_strategy,
+            request,
+            &inputs,
+            wallet::ConfirmationsPolicy::default(),
+        )?;
+
+        Ok(utils::rust_bytes_to_java(
+            env,
+            Proposal::from_standard_proposal(&proposal)
+                .encode_to_vec()
+                .as_ref(),
+        )?
+        .into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+/// Proposes a transaction that consolidates up to `max_notes` of the smallest notes in
+/// the given shielded pool into a single note in that pool.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_proposeConsolidation<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    account_uuid: JByteArray<'local>,
+    pool_type: jint,
+    max_notes: jint,
+    network_id: jint,
+) -> jbyteArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.proposeConsolidation").entered();
+        let network = parse_network(network_id as u32)?;
+        let db_data = wallet_db(env, network, db_data)?;
+        let account_uuid = account_id_from_jni(env, account_uuid)?;
+        let pool = parse_protocol(pool_type)?;
+        let max_notes =
+            usize::try_from(max_notes).map_err(|_| anyhow!("Invalid note count: {max_notes}"))?;
+
+        let proposal = coin_control::propose_consolidation(
+            &db_data,
+            &network,
+            account_uuid,
+            pool,
+            max_notes,
+            wallet::ConfirmationsPolicy::default(),
+        )?;
+
+        Ok(utils::rust_bytes_to_java(
+            env,
+            Proposal::from_standard_proposal(&proposal)
+                .encode_to_vec()
+                .as_ref(),
+        )?
+        .into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+/// Proposes a transaction that splits the account's balance in the given shielded pool
+/// into `note_count` notes of roughly equal value in that pool.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_proposeSplit<'local>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    account_uuid: JByteArray<'local>,
+    pool_type: jint,
+    note_count: jint,
+    network_id: jint,
+) -> jbyteArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.proposeSplit").entered();
+        let network = parse_network(network_id as u32)?;
+        let db_data = wallet_db(env, network, db_data)?;
+        let account_uuid = account_id_from_jni(env, account_uuid)?;
+        let pool = parse_protocol(pool_type)?;
+        let note_count = usize::try_from(note_count)
+            .ok()
+            .and_then(NonZeroUsize::new)
+            .ok_or_else(|| anyhow!("Invalid note count: {note_count}"))?;
+
+        let proposal = coin_control::propose_split(
+            &db_data,
+            &network,
+            account_uuid,
+            pool,
+            note_count,
+            wallet::ConfirmationsPolicy::default(),
+        )?;
+
+        Ok(utils::rust_bytes_to_java(
+            env,
+            Proposal::from_standard_proposal(&proposal)
+                .encode_to_vec()
+                .as_ref(),
+        )?
+        .into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+const JNI_MIGRATION_STEP: &str = "cash/z/ecc/android/sdk/internal/model/JniMigrationStep";
+
+fn encode_migration_step<'a>(
+    env: &mut JNIEnv<'a>,
+    step: pool_migration::Step,
+) -> jni::errors::Result<JObject<'a>> {
+    let txid = match step.txid {
+        Some(txid) => env.byte_array_from_slice(txid.as_ref())?.into(),
+        None => JObject::null(),
+    };
+    env.new_object(
+        JNI_MIGRATION_STEP,
+        "(IJJ[BI)V",
+        &[
+            JValue::Int(step.index as i32),
+            JValue::Long(u64::from(step.value) as i64),
+            JValue::Long(i64::from(u32::from(step.due_height))),
+            (&txid).into(),
+            JValue::Int(step.status as i32),
+        ],
+    )
+}
+
+/// Replaces any Sapling-to-Orchard migration plan for the account with a new plan that
+/// moves its spendable Sapling balance in standard denominations, at randomized heights
+/// roughly `interval_blocks` apart.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_planPoolMigration<'local>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    account_uuid: JByteArray<'local>,
+    interval_blocks: jint,
+    network_id: jint,
+) -> jobjectArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.planPoolMigration").entered();
+        let network = parse_network(network_id as u32)?;
+        let db_path = path_from_jni(env, db_data)?;
+        let db_data = wallet_db_at(&db_path, network)?;
+        let mut conn = wallet_db_conn_mut(&db_path)?;
+        let account_uuid = account_id_from_jni(env, account_uuid)?;
+        let interval = u32::try_from(interval_blocks)
+            .map_err(|_| anyhow!("Invalid interval: {interval_blocks}"))?;
+
+        let steps = pool_migration::plan(&mut conn, &db_data, account_uuid, interval)?;
+
+        Ok(
+            utils::rust_vec_to_java(env, steps, JNI_MIGRATION_STEP, |env, step| {
+                encode_migration_step(env, step)
+            })?
+            .into_raw(),
+        )
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+/// Returns the steps of the account's Sapling-to-Orchard migration plan, which is empty
+/// if no migration has been planned.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_getPoolMigrationPlan<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    account_uuid: JByteArray<'local>,
+    network_id: jint,
+) -> jobjectArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.getPoolMigrationPlan").entered();
+        let network = parse_network(network_id as u32)?;
+        let db_path = path_from_jni(env, db_data)?;
+        let db_data = wallet_db_at(&db_path, network)?;
+        let conn = wallet_db_conn(&db_path)?;
+        let account_uuid = account_id_from_jni(env, account_uuid)?;
+
+        let steps = pool_migration::steps(&conn, account_uuid, fully_scanned_height(&db_data)?)?;
+
+        Ok(
+            utils::rust_vec_to_java(env, steps, JNI_MIGRATION_STEP, |env, step| {
+                encode_migration_step(env, step)
+            })?
+            .into_raw(),
+        )
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+const JNI_MIGRATION_STEP_PROPOSAL: &str =
+    "cash/z/ecc/android/sdk/internal/model/JniMigrationStepProposal";
+
+/// Proposes the transaction for the next step of the account's Sapling-to-Orchard
+/// migration plan that is due, or returns null if no step is due.
+///
+/// Once the transaction has been created, the caller should record it with
+/// `recordPoolMigrationStep`.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_proposeNextPoolMigrationStep<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    account_uuid: JByteArray<'local>,
+    network_id: jint,
+) -> jobject {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.proposeNextPoolMigrationStep").entered();
+        let network = parse_network(network_id as u32)?;
+        let db_path = path_from_jni(env, db_data)?;
+        let db_data = wallet_db_at(&db_path, network)?;
+        let conn = wallet_db_conn(&db_path)?;
+        let account_uuid = account_id_from_jni(env, account_uuid)?;
+
+        match pool_migration::propose_next_step(&conn, &db_data, &network, account_uuid)? {
+            Some((step, proposal)) => {
+                let proposal = utils::rust_bytes_to_java(
+                    env,
+                    Proposal::from_standard_proposal(&proposal)
+                        .encode_to_vec()
+                        .as_ref(),
+                )?;
+                Ok(env
+                    .new_object(
+                        JNI_MIGRATION_STEP_PROPOSAL,
+                        "(I[B)V",
+                        &[JValue::Int(step.index as i32), (&proposal).into()],
+                    )?
+                    .into_raw())
+            }
+            None => Ok(ptr::null_mut()),
+        }
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+/// Records the transaction created for a step of the account's Sapling-to-Orchard
+/// migration plan.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_recordPoolMigrationStep<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    account_uuid: JByteArray<'local>,
+    step_index: jint,
+    txid_bytes: JByteArray<'local>,
+    network_id: jint,
+) {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.recordPoolMigrationStep").entered();
+        let network = parse_network(network_id as u32)?;
+        let db_path = path_from_jni(env, db_data)?;
+        let db_data = wallet_db_at(&db_path, network)?;
+        let conn = wallet_db_conn_mut(&db_path)?;
+        let account_uuid = account_id_from_jni(env, account_uuid)?;
+        let step_index =
+            u32::try_from(step_index).map_err(|_| anyhow!("Invalid step index: {step_index}"))?;
+        let txid = parse_txid(env, txid_bytes)?;
+
+        pool_migration::record_step(
+            &conn,
             account_uuid,
-            &input_selector,
-            &change_strategy,
-            request,
-            wallet::ConfirmationsPolicy::default(),
+            step_index,
+            &txid,
+            fully_scanned_height(&db_data)?,
         )
-        .map_err(|e| anyhow!("Error creating transaction proposal: {}", e))?;
-
-        Ok(utils::rust_bytes_to_java(
-            env,
-            Proposal::from_standard_proposal(&proposal)
-                .encode_to_vec()
-                .as_ref(),
-        )?
-        .into_raw())
     });
-    unwrap_exc_or(&mut env, res, ptr::null_mut())
+    unwrap_exc_or(&mut env, res, ())
 }
 
 #[unsafe(no_mangle)]
@@ -2227,6 +3799,200 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_proposeSh
     unwrap_exc_or(&mut env, res, ptr::null_mut())
 }
 
+fn decode_auto_shielding_policy(
+    env: &mut JNIEnv,
+    obj: JObject,
+) -> anyhow::Result<auto_shield::Policy> {
+    let min_value = Zatoshis::from_nonnegative_i64(env.get_field(&obj, "minValue", "J")?.j()?)
+        .map_err(|_| anyhow!("Invalid minimum value, out of range"))?;
+    let max_utxo_age = match env.get_field(&obj, "maxUtxoAge", "I")?.i()? {
+        -1 => None,
+        age => Some(u32::try_from(age)?),
+    };
+    let one_address_per_tx = env.get_field(&obj, "oneAddressPerTransaction", "Z")?.z()?;
+    let min_delay = u32::try_from(env.get_field(&obj, "minDelayBlocks", "I")?.i()?)?;
+    let max_delay = u32::try_from(env.get_field(&obj, "maxDelayBlocks", "I")?.i()?)?;
+    if max_delay < min_delay {
+        return Err(anyhow!("Maximum delay must not be less than minimum delay"));
+    }
+
+    Ok(auto_shield::Policy {
+        min_value,
+        max_utxo_age,
+        one_address_per_tx,
+        min_delay,
+        max_delay,
+    })
+}
+
+/// Evaluates the given `JniAutoShieldingPolicy` against the account's transparent
+/// receivers, and returns a shielding proposal for each group of receivers whose funds
+/// are due to be shielded now.
+///
+/// Returns an empty array if nothing is due.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_proposeDueShielding<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    account_uuid: JByteArray<'local>,
+    policy: JObject<'local>,
+    memo: JByteArray<'local>,
+    network_id: jint,
+) -> jobjectArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.proposeDueShielding").entered();
+        let network = parse_network(network_id as u32)?;
+        let mut db_data = wallet_db(env, network, db_data)?;
+        let account_uuid = account_id_from_jni(env, account_uuid)?;
+        let policy = decode_auto_shielding_policy(env, policy)?;
+        let memo = utils::java_nullable_bytes_to_rust(env, &memo)?
+            .as_deref()
+            .map(MemoBytes::from_bytes)
+            .transpose()
+            .map_err(|e| anyhow!("Invalid MemoBytes: {}", e))?;
+
+        let groups = auto_shield::due_receivers(&db_data, &network, account_uuid, &policy)?;
+
+        // Always use ZIP 317 fees
+        let (change_strategy, input_selector) = zip317_helper(memo);
+
+        let mut proposals = vec![];
+        for from_addrs in groups {
+            let proposal = propose_shielding::<_, _, _, _, Infallible>(
+                &mut db_data,
+                &network,
+                &input_selector,
+                &change_strategy,
+                // The policy has already checked each receiver's value.
+                Zatoshis::ZERO,
+                &from_addrs,
+                account_uuid,
+                wallet::ConfirmationsPolicy::MIN,
+            )
+            .map_err(|e| anyhow!("Error while shielding transaction: {}", e))?;
+            proposals.push(Proposal::from_standard_proposal(&proposal).encode_to_vec());
+        }
+
+        Ok(
+            utils::rust_vec_to_java(env, proposals, "[B", |env, proposal| {
+                utils::rust_bytes_to_java(env, &proposal)
+            })?
+            .into_raw(),
+        )
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+/// Runs `f`, which creates proofs, on a separate thread while reporting its progress to
+/// the given `JniProvingListener` and polling it for cancellation.
+///
+/// If the listener is null, `f` is run on the current thread and cannot be cancelled.
+fn prove_with_listener<T: Send>(
+    env: &mut JNIEnv,
+    listener: &JObject,
+    step_proofs: Vec<u32>,
+    f: impl FnOnce(&proving::ProvingTracker) -> anyhow::Result<T> + Send,
+) -> anyhow::Result<T> {
+    let (updates, progress) = std::sync::mpsc::channel();
+    let tracker = proving::ProvingTracker::new(step_proofs, updates);
+
+    if listener.is_null() {
+        return f(&tracker);
+    }
+
+    let report = |env: &mut JNIEnv, update: proving::Progress| -> anyhow::Result<()> {
+        env.call_method(
+            listener,
+            "onProgress",
+            "(IIII)V",
+            &[
+                JValue::Int(i32::try_from(update.completed_proofs)?),
+                JValue::Int(i32::try_from(update.total_proofs)?),
+                JValue::Int(i32::try_from(update.step)?),
+                JValue::Int(i32::try_from(update.step_count)?),
+            ],
+        )?;
+        Ok(())
+    };
+
+    std::thread::scope(|s| {
+        let worker = s.spawn(|| f(&tracker));
+
+        // JNI calls can only be made from this thread, so the worker sends its progress
+        // here rather than calling the listener itself.
+        let listened = (|| -> anyhow::Result<()> {
+            while !worker.is_finished() {
+                if let Ok(update) = progress.recv_timeout(std::time::Duration::from_millis(100)) {
+                    report(env, update)?;
+                }
+                if env.call_method(listener, "isCancelled", "()Z", &[])?.z()? {
+                    tracker.cancel();
+                }
+            }
+            progress
+                .try_iter()
+                .try_for_each(|update| report(env, update))
+        })();
+        if listened.is_err() {
+            tracker.cancel();
+        }
+
+        let res = worker
+            .join()
+            .unwrap_or_else(|payload| panic::resume_unwind(payload));
+        listened.and(res)
+    })
+}
+
+/// Returns the expected number of Sapling proofs for each step of the given proposal.
+///
+/// Sapling bundles are padded to at least two outputs, so the padding outputs are
+/// included.
+fn sapling_proof_counts<FeeRuleT, NoteRef>(
+    proposal: &zcash_client_backend::proposal::Proposal<FeeRuleT, NoteRef>,
+) -> Vec<u32> {
+    proposal
+        .steps()
+        .iter()
+        .map(|step| {
+            let spends = step.shielded_inputs().map_or(0, |inputs| {
+                inputs
+                    .notes()
+                    .iter()
+                    .filter(|n| n.note().protocol() == ShieldedProtocol::Sapling)
+                    .count()
+            });
+            let outputs = step
+                .payment_pools()
+                .values()
+                .filter(|pool| **pool == PoolType::SAPLING)
+                .count()
+                + step
+                    .balance()
+                    .proposed_change()
+                    .iter()
+                    .filter(|change| change.output_pool() == PoolType::SAPLING)
+                    .count();
+
+            let proofs = if spends + outputs > 0 {
+                spends + outputs.max(2)
+            } else {
+                0
+            };
+            u32::try_from(proofs).expect("proof count fits in u32")
+        })
+        .collect()
+}
+
+/// Creates the transactions for the given proposal, and stores them in the wallet.
+///
+/// If `listener` is not null, it is notified as each Sapling proof is created, and the
+/// remaining Sapling proofs are skipped once it reports that it has been cancelled. The
+/// Orchard proof of each transaction is created by the transaction builder, and so is
+/// neither reported nor skipped. A cancelled call leaves no transactions in the wallet.
 #[unsafe(no_mangle)]
 pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_createProposedTransactions<
     'local,
@@ -2239,6 +4005,7 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_createPro
     spend_params: JString<'local>,
     output_params: JString<'local>,
     network_id: jint,
+    listener: JObject<'local>,
 ) -> jobjectArray {
     let res = catch_unwind(&mut env, |env| {
         let _span = tracing::info_span!("RustBackend.createProposedTransaction").entered();
@@ -2248,22 +4015,36 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_createPro
         let spend_params = path_from_jni(env, spend_params)?;
         let output_params = path_from_jni(env, output_params)?;
 
-        let prover = LocalTxProver::new(&spend_params, &output_params);
+        let prover = proving::sapling_prover(&spend_params, &output_params);
 
         let proposal = Proposal::decode(utils::java_bytes_to_rust(env, &proposal)?.as_slice())
             .map_err(|e| anyhow!("Invalid proposal: {}", e))?
             .try_into_standard_proposal(&db_data)?;
 
-        let txids = create_proposed_transactions::<_, _, Infallible, _, Infallible, _>(
-            &mut db_data,
-            &network,
-            &prover,
-            &prover,
-            &wallet::SpendingKeys::from_unified_spending_key(usk),
-            OvkPolicy::Sender,
-            &proposal,
-        )
-        .map_err(|e| anyhow!("Error while creating transactions: {}", e))?;
+        let step_proofs = sapling_proof_counts(&proposal);
+        let txids = prove_with_listener(env, &listener, step_proofs, |tracker| {
+            let prover = proving::TrackingProver::new(&*prover, tracker);
+
+            // The transactions are held back until we know that proving was not
+            // cancelled, as they would otherwise be stored with placeholder proofs.
+            let mut deferred = deferred_store::DeferredStore::new(&mut db_data);
+            let txids = create_proposed_transactions::<_, _, Infallible, _, Infallible, _>(
+                &mut deferred,
+                &network,
+                &prover,
+                &prover,
+                &wallet::SpendingKeys::from_unified_spending_key(usk),
+                OvkPolicy::Sender,
+                &proposal,
+            )
+            .map_err(|e| anyhow!("Error while creating transactions: {}", e))?;
+
+            tracker.check_cancelled()?;
+            deferred
+                .commit()
+                .map_err(|e| anyhow!("Error while storing transactions: {}", e))?;
+            Ok(txids)
+        })?;
 
         Ok(
             utils::rust_vec_to_java(env, txids.into(), "[B", |env, txid| {
@@ -2315,7 +4096,7 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_createPcz
             Ok(utils::rust_bytes_to_java(env, &pczt.serialize())?.into_raw())
         } else {
             Err(anyhow!(
-                "Multi-step proposals are not yet supported for PCZT generation."
+                "Multi-step proposals must be converted to PCZTs with createPcztsFromProposal."
             ))
         }
     });
@@ -2390,45 +4171,280 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_requiresS
     unwrap_exc_or(&mut env, res, JNI_TRUE)
 }
 
+const JNI_PCZT_SPEND: &str = "cash/z/ecc/android/sdk/internal/model/JniPcztSpend";
+
+fn encode_pczt_spend<'a>(
+    env: &mut JNIEnv<'a>,
+    spend: pczt_summary::SpendSummary,
+) -> jni::errors::Result<JObject<'a>> {
+    env.new_object(
+        JNI_PCZT_SPEND,
+        "(IIJZZ)V",
+        &[
+            JValue::Int(pool_code(spend.pool)),
+            JValue::Int(i32::try_from(spend.index).expect("spend index fits in i32")),
+            // Use -1 to return null across the FFI.
+            JValue::Long(spend.value.map_or(-1, |v| ZatBalance::from(v).into())),
+            JValue::Bool(spend.missing_signature.into()),
+            JValue::Bool(spend.missing_proof.into()),
+        ],
+    )
+}
+
+const JNI_PCZT_OUTPUT: &str = "cash/z/ecc/android/sdk/internal/model/JniPcztOutput";
+
+fn encode_pczt_output<'a>(
+    env: &mut JNIEnv<'a>,
+    output: pczt_summary::OutputSummary,
+) -> jni::errors::Result<JObject<'a>> {
+    let address = match output.address {
+        Some(address) => env.new_string(address)?.into(),
+        None => JObject::null(),
+    };
+    let memo = match &output.memo {
+        Some(memo) => env.byte_array_from_slice(memo.as_array())?.into(),
+        None => JObject::null(),
+    };
+
+    env.new_object(
+        JNI_PCZT_OUTPUT,
+        "(IIILjava/lang/String;J[BZ)V",
+        &[
+            JValue::Int(pool_code(output.pool)),
+            JValue::Int(i32::try_from(output.index).expect("output index fits in i32")),
+            JValue::Int(output.kind.code()),
+            (&address).into(),
+            // Use -1 to return null across the FFI.
+            JValue::Long(output.value.map_or(-1, |v| ZatBalance::from(v).into())),
+            (&memo).into(),
+            JValue::Bool(output.missing_proof.into()),
+        ],
+    )
+}
+
+const JNI_PCZT_SUMMARY: &str = "cash/z/ecc/android/sdk/internal/model/JniPcztSummary";
+
+fn encode_pczt_summary<'a>(
+    env: &mut JNIEnv<'a>,
+    summary: pczt_summary::PcztSummary,
+) -> jni::errors::Result<JObject<'a>> {
+    let spends = utils::rust_vec_to_java(env, summary.spends, JNI_PCZT_SPEND, |env, spend| {
+        encode_pczt_spend(env, spend)
+    })?;
+    let outputs = utils::rust_vec_to_java(env, summary.outputs, JNI_PCZT_OUTPUT, |env, output| {
+        encode_pczt_output(env, output)
+    })?;
+
+    env.new_object(
+        JNI_PCZT_SUMMARY,
+        format!("([L{};[L{};JJJ)V", JNI_PCZT_SPEND, JNI_PCZT_OUTPUT),
+        &[
+            (&spends).into(),
+            (&outputs).into(),
+            // Use -1 to return null across the FFI.
+            JValue::Long(summary.fee.unwrap_or(-1)),
+            JValue::Long(summary.expiry_height.into()),
+            JValue::Long(summary.consensus_branch_id.into()),
+        ],
+    )
+}
+
+/// Returns a summary of what the given PCZT does, for confirmation before it is signed.
+///
+/// This includes the inputs that still lack signatures or proofs.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_describePczt<'local>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    pczt: JByteArray<'local>,
+    network_id: jint,
+) -> jobject {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.describePczt").entered();
+        let network = parse_network(network_id as u32)?;
+        let pczt = parse_pczt(env, pczt)?;
+
+        let summary = pczt_summary::describe(&network, &pczt)?;
+
+        Ok(encode_pczt_summary(env, summary)?.into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+/// Signs every transparent, Sapling and Orchard input of the given PCZT that can be spent
+/// with the given unified spending key.
+///
+/// Returns the updated PCZT in its serialized format.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_signPczt<'local>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    pczt: JByteArray<'local>,
+    usk: JByteArray<'local>,
+) -> jbyteArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.signPczt").entered();
+
+        let pczt = parse_pczt(env, pczt)?;
+        let usk = decode_usk(env, usk)?;
+
+        let signed_pczt = pczt_signer::sign_pczt(pczt, &usk)?;
+
+        Ok(utils::rust_bytes_to_java(env, &signed_pczt.serialize())?.into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
 /// Adds proofs to the given PCZT.
 ///
+/// If `listener` is not null, it is notified as each proof is created, with the Orchard
+/// proof counted as one proof per action. Once the listener reports that it has been
+/// cancelled, no further bundle is proven, the remaining Sapling proofs are skipped, and
+/// the call fails.
+///
 /// Returns the updated PCZT in its serialized format.
 #[unsafe(no_mangle)]
-pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_addProofsToPczt<'local>(
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_addProofsToPczt<'local>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    pczt: JByteArray<'local>,
+    spend_params: JString<'local>,
+    output_params: JString<'local>,
+    listener: JObject<'local>,
+) -> jbyteArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.addProofsToPczt").entered();
+
+        let pczt = parse_pczt(env, pczt)?;
+        let spend_params = path_from_jni(env, spend_params)?;
+        let output_params = path_from_jni(env, output_params)?;
+
+        let mut sapling_proofs = 0;
+        let pczt = Verifier::new(pczt)
+            .with_sapling::<Infallible, _>(|bundle| {
+                sapling_proofs = bundle
+                    .spends()
+                    .iter()
+                    .filter(|spend| spend.zkproof().is_none())
+                    .count()
+                    + bundle
+                        .outputs()
+                        .iter()
+                        .filter(|output| output.zkproof().is_none())
+                        .count();
+                Ok(())
+            })
+            .map_err(|e| anyhow!("Invalid Sapling bundle in PCZT: {:?}", e))?
+            .finish();
+        let orchard_actions = pczt.orchard().actions().len();
+
+        let mut prover = Prover::new(pczt);
+        let orchard_proofs = if prover.requires_orchard_proof() {
+            u32::try_from(orchard_actions)?
+        } else {
+            0
+        };
+        let step_proofs = vec![orchard_proofs + u32::try_from(sapling_proofs)?];
+
+        let pczt_with_proofs = prove_with_listener(env, &listener, step_proofs, move |tracker| {
+            if prover.requires_orchard_proof() {
+                tracker.check_cancelled()?;
+                prover = prover
+                    .create_orchard_proof(proving::orchard_proving_key())
+                    .map_err(|e| anyhow!("Failed to create Orchard proof for PCZT: {:?}", e))?;
+                tracker.proofs_created(orchard_proofs);
+            }
+            assert!(!prover.requires_orchard_proof());
+
+            if prover.requires_sapling_proofs() {
+                tracker.check_cancelled()?;
+                let local_prover = proving::sapling_prover(&spend_params, &output_params);
+                let local_prover = proving::TrackingProver::new(&*local_prover, tracker);
+
+                prover = prover
+                    .create_sapling_proofs(&local_prover, &local_prover)
+                    .map_err(|e| anyhow!("Failed to create Sapling proofs for PCZT: {:?}", e))?;
+            }
+            assert!(!prover.requires_sapling_proofs());
+
+            tracker.check_cancelled()?;
+            Ok(prover.finish())
+        })?;
+
+        Ok(utils::rust_bytes_to_java(env, &pczt_with_proofs.serialize())?.into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+/// Checks that combining the given proven and signed PCZTs does not alter any effect of
+/// the transaction described by the original PCZT returned by `createPcztFromProposal`.
+///
+/// Returns a description of each altered field, which is empty if the combined PCZT can be
+/// safely extracted.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_verifyPczt<'local>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    original_pczt: JByteArray<'local>,
+    pczt_with_proofs: JByteArray<'local>,
+    pczt_with_signatures: JByteArray<'local>,
+) -> jobjectArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.verifyPczt").entered();
+
+        let original_pczt = parse_pczt(env, original_pczt)
+            .map_err(|e| anyhow!("Invalid original PCZT: {:?}", e))?;
+        let pczt_with_proofs = parse_pczt(env, pczt_with_proofs)
+            .map_err(|e| anyhow!("Invalid PCZT-with-proofs: {:?}", e))?;
+        let pczt_with_signatures = parse_pczt(env, pczt_with_signatures)
+            .map_err(|e| anyhow!("Invalid PCZT-with-signatures: {:?}", e))?;
+
+        let pczt = Combiner::new(vec![pczt_with_proofs, pczt_with_signatures])
+            .combine()
+            .map_err(|e| anyhow!("Failed to combine PCZTs: {:?}", e))?;
+
+        let diffs = pczt_verify::compare(&original_pczt, &pczt).into_vec();
+
+        Ok(
+            utils::rust_vec_to_java(env, diffs, "java/lang/String", |env, diff| {
+                env.new_string(diff)
+            })?
+            .into_raw(),
+        )
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+/// Encodes the given PCZT as a sequence of `ur:zcash-pczt` parts, for display as an
+/// animated QR code.
+///
+/// Returns a single part if the PCZT fits within `max_fragment_length` bytes, and
+/// otherwise at least `part_count` fountain-coded parts.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_encodePcztAsUr<'local>(
     mut env: JNIEnv<'local>,
     _: JClass<'local>,
     pczt: JByteArray<'local>,
-    spend_params: JString<'local>,
-    output_params: JString<'local>,
-) -> jbyteArray {
+    max_fragment_length: jint,
+    part_count: jint,
+) -> jobjectArray {
     let res = catch_unwind(&mut env, |env| {
-        let _span = tracing::info_span!("RustBackend.addProofsToPczt").entered();
-
-        let pczt = parse_pczt(env, pczt)?;
+        let _span = tracing::info_span!("RustBackend.encodePcztAsUr").entered();
 
-        let mut prover = Prover::new(pczt);
-
-        if prover.requires_orchard_proof() {
-            prover = prover
-                .create_orchard_proof(&orchard::circuit::ProvingKey::build())
-                .map_err(|e| anyhow!("Failed to create Orchard proof for PCZT: {:?}", e))?;
-        }
-        assert!(!prover.requires_orchard_proof());
-
-        if prover.requires_sapling_proofs() {
-            let spend_params = path_from_jni(env, spend_params)?;
-            let output_params = path_from_jni(env, output_params)?;
-            let local_prover = LocalTxProver::new(&spend_params, &output_params);
+        let pczt = utils::java_bytes_to_rust(env, &pczt)?;
+        let max_fragment_length = usize::try_from(max_fragment_length)
+            .map_err(|_| anyhow!("Maximum fragment length must be positive"))?;
+        let part_count =
+            usize::try_from(part_count).map_err(|_| anyhow!("Part count must be positive"))?;
 
-            prover = prover
-                .create_sapling_proofs(&local_prover, &local_prover)
-                .map_err(|e| anyhow!("Failed to create Sapling proofs for PCZT: {:?}", e))?;
-        }
-        assert!(!prover.requires_sapling_proofs());
-
-        let pczt_with_proofs = prover.finish();
+        let parts = pczt_ur::encode(&pczt, max_fragment_length, part_count)?;
 
-        Ok(utils::rust_bytes_to_java(env, &pczt_with_proofs.serialize())?.into_raw())
+        Ok(
+            utils::rust_vec_to_java(env, parts, "java/lang/String", |env, part| {
+                env.new_string(part)
+            })?
+            .into_raw(),
+        )
     });
     unwrap_exc_or(&mut env, res, ptr::null_mut())
 }
@@ -2436,6 +4452,9 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_addProofs
 /// Takes a PCZT that has been separately proven and signed, finalizes it, and stores it
 /// in the wallet.
 ///
+/// The transaction is not stored if combining the proven and signed PCZTs alters any
+/// effect of the transaction described by the original PCZT, as checked by `verifyPczt`.
+///
 /// Returns the txid of the completed transaction.
 #[unsafe(no_mangle)]
 pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_extractAndStoreTxFromPczt<
@@ -2444,6 +4463,7 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_extractAn
     mut env: JNIEnv<'local>,
     _: JClass<'local>,
     db_data: JString<'local>,
+    original_pczt: JByteArray<'local>,
     pczt_with_proofs: JByteArray<'local>,
     pczt_with_signatures: JByteArray<'local>,
     spend_params: JString<'local>,
@@ -2455,6 +4475,9 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_extractAn
         let network = parse_network(network_id as u32)?;
         let mut db_data = wallet_db(env, network, db_data)?;
 
+        let original_pczt = parse_pczt(env, original_pczt)
+            .map_err(|e| anyhow!("Invalid original PCZT: {:?}", e))?;
+
         let pczt_with_proofs = parse_pczt(env, pczt_with_proofs)
             .map_err(|e| anyhow!("Invalid PCZT-with-proofs: {:?}", e))?;
 
@@ -2463,18 +4486,19 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_extractAn
 
         let spend_params = path_from_jni(env, spend_params)?;
         let output_params = path_from_jni(env, output_params)?;
-        let prover = LocalTxProver::new(&spend_params, &output_params);
+        let prover = proving::sapling_prover(&spend_params, &output_params);
         let (spend_vk, output_vk) = prover.verifying_keys();
 
         let pczt = Combiner::new(vec![pczt_with_proofs, pczt_with_signatures])
             .combine()
             .map_err(|e| anyhow!("Failed to combine PCZTs: {:?}", e))?;
+        pczt_verify::compare(&original_pczt, &pczt).into_result()?;
 
         let txid = extract_and_store_transaction_from_pczt::<_, ()>(
             &mut db_data,
             pczt,
             Some((&spend_vk, &output_vk)),
-            Some(&orchard::circuit::VerifyingKey::build()),
+            Some(proving::orchard_verifying_key()),
         )
         .map_err(|e| anyhow!("Failed to extract transaction from PCZT: {:?}", e))?;
 
@@ -2483,6 +4507,136 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_extractAn
     unwrap_exc_or(&mut env, res, ptr::null_mut())
 }
 
+/// Creates one partially-created (unsigned without proofs) transaction for each step of
+/// the given proposal.
+///
+/// This supports multi-step proposals, such as those that pay a TEX address via an
+/// ephemeral transparent address. Each PCZT after the first records the txid of the
+/// step before it, which must be extracted first.
+///
+/// Returns the PCZTs in their serialized format, in step order.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_createPcztsFromProposal<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    account_uuid: JByteArray<'local>,
+    proposal: JByteArray<'local>,
+    network_id: jint,
+) -> jobjectArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.createPcztsFromProposal").entered();
+        let network = parse_network(network_id as u32)?;
+        let mut db_data = wallet_db(env, network, db_data)?;
+        let account_id = account_id_from_jni(env, account_uuid)?;
+
+        let proposal = Proposal::decode(utils::java_bytes_to_rust(env, &proposal)?.as_slice())
+            .map_err(|e| anyhow!("Invalid proposal: {}", e))?
+            .try_into_standard_proposal(&db_data)?;
+
+        let pczts =
+            pczt_chain::create_pczts_from_proposal(&mut db_data, &network, account_id, &proposal)?;
+
+        Ok(utils::rust_vec_to_java(env, pczts, "[B", |env, pczt| {
+            utils::rust_bytes_to_java(env, &pczt.serialize())
+        })?
+        .into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+fn parse_pczt_array(env: &mut JNIEnv, pczts: JObjectArray) -> anyhow::Result<Vec<Pczt>> {
+    let count = env.get_array_length(&pczts)?;
+    (0..count)
+        .map(|i| {
+            let pczt = JByteArray::from(env.get_object_array_element(&pczts, i)?);
+            parse_pczt(env, pczt)
+        })
+        .collect()
+}
+
+/// Takes the chain of PCZTs created by `createPcztsFromProposal`, each of which has been
+/// separately proven and signed, finalizes them, and stores them in the wallet.
+///
+/// Every PCZT is finalized and verified before any transaction is stored, and no
+/// transaction is stored if combining the proven and signed PCZTs of any step alters an
+/// effect of the transaction described by its original PCZT.
+///
+/// Returns the txids of the completed transactions, in step order.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_extractAndStoreTxsFromPczts<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    db_data: JString<'local>,
+    original_pczts: JObjectArray<'local>,
+    pczts_with_proofs: JObjectArray<'local>,
+    pczts_with_signatures: JObjectArray<'local>,
+    spend_params: JString<'local>,
+    output_params: JString<'local>,
+    network_id: jint,
+) -> jobjectArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.extractAndStoreTxsFromPczts").entered();
+        let network = parse_network(network_id as u32)?;
+        let mut db_data = wallet_db(env, network, db_data)?;
+
+        let original_pczts = parse_pczt_array(env, original_pczts)
+            .map_err(|e| anyhow!("Invalid original PCZT: {:?}", e))?;
+
+        let pczts_with_proofs = parse_pczt_array(env, pczts_with_proofs)
+            .map_err(|e| anyhow!("Invalid PCZT-with-proofs: {:?}", e))?;
+
+        let pczts_with_signatures = parse_pczt_array(env, pczts_with_signatures)
+            .map_err(|e| anyhow!("Invalid PCZT-with-signatures: {:?}", e))?;
+
+        if original_pczts.is_empty()
+            || pczts_with_proofs.len() != original_pczts.len()
+            || pczts_with_signatures.len() != original_pczts.len()
+        {
+            return Err(anyhow!(
+                "Expected the same non-zero number of original, proven and signed PCZTs"
+            ));
+        }
+
+        let spend_params = path_from_jni(env, spend_params)?;
+        let output_params = path_from_jni(env, output_params)?;
+        let prover = proving::sapling_prover(&spend_params, &output_params);
+        let (spend_vk, output_vk) = prover.verifying_keys();
+
+        let pczts = original_pczts
+            .iter()
+            .zip(pczts_with_proofs.into_iter().zip(pczts_with_signatures))
+            .enumerate()
+            .map(|(step, (original, (with_proofs, with_signatures)))| {
+                let pczt = Combiner::new(vec![with_proofs, with_signatures])
+                    .combine()
+                    .map_err(|e| anyhow!("Failed to combine PCZTs of step {}: {:?}", step, e))?;
+                pczt_verify::compare(original, &pczt)
+                    .into_result()
+                    .map_err(|e| anyhow!("Step {}: {}", step, e))?;
+                Ok(pczt)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let txids = pczt_chain::extract_and_store_chain(
+            &mut db_data,
+            pczts,
+            (&spend_vk, &output_vk),
+            proving::orchard_verifying_key(),
+        )?;
+
+        Ok(utils::rust_vec_to_java(env, txids, "[B", |env, txid| {
+            utils::rust_bytes_to_java(env, txid.as_ref())
+        })?
+        .into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_branchIdForHeight<'local>(
     mut env: JNIEnv<'local>,
@@ -2669,6 +4823,35 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustDerivationTool_de
     unwrap_exc_or(&mut env, res, ptr::null_mut())
 }
 
+fn account_metadata_key(
+    seed: &SecretVec<u8>,
+    network: &Network,
+    account: zip32::AccountId,
+) -> anyhow::Result<zip32::registered::SecretKey> {
+    Ok(zip32::registered::SecretKey::from_subpath(
+        b"MetadataKeys",
+        seed.expose_secret(),
+        // TODO: Change this to whatever ZIP number is assigned to the metadata key ZIP draft.
+        325,
+        &[
+            PathElement::new(ChildIndex::hardened(network.coin_type()), &[]),
+            PathElement::new(ChildIndex::hardened(account.into()), &[]),
+        ],
+    )?)
+}
+
+/// The private-use subject of the key that encrypts wallet database backups.
+const WALLET_BACKUP_KEY_SUBJECT: &[u8] = b"wallet_db_backup";
+
+/// Derives the key that encrypts wallet database backups, as the private-use metadata key
+/// in the inherent subtree of the seed's first account.
+fn wallet_backup_key(seed: &SecretVec<u8>, network: &Network) -> anyhow::Result<SecretVec<u8>> {
+    let key = account_metadata_key(seed, network, zip32::AccountId::ZERO)?
+        .derive_child_with_tag(ChildIndex::hardened(0), &[])
+        .derive_child_with_tag(ChildIndex::PRIVATE_USE, WALLET_BACKUP_KEY_SUBJECT);
+    Ok(SecretVec::new(key.data().to_vec()))
+}
+
 fn encode_metadata_key<'a>(
     env: &mut JNIEnv<'a>,
     key: zip32::registered::SecretKey,
@@ -2700,16 +4883,7 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustDerivationTool_de
         let seed = secret_from_jni(env, seed)?;
         let account = zip32_account_index_from_jlong(account_index)?;
 
-        let key = zip32::registered::SecretKey::from_subpath(
-            b"MetadataKeys",
-            seed.expose_secret(),
-            // TODO: Change this to whatever ZIP number is assigned to the metadata key ZIP draft.
-            325,
-            &[
-                PathElement::new(ChildIndex::hardened(network.coin_type()), &[]),
-                PathElement::new(ChildIndex::hardened(account.into()), &[]),
-            ],
-        )?;
+        let key = account_metadata_key(&seed, &network, account)?;
 
         Ok(encode_metadata_key(env, key)?.into_raw())
     });
@@ -2860,6 +5034,170 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustDerivationTool_de
     unwrap_exc_or(&mut env, res, ptr::null_mut())
 }
 
+//
+// Sapling parameters
+//
+
+const JNI_SAPLING_PARAM_STATUS: &str =
+    "cash/z/ecc/android/sdk/internal/model/JniSaplingParamStatus";
+
+fn encode_sapling_param_status<'a>(
+    env: &mut JNIEnv<'a>,
+    (file, status): (&sapling_params::ParamFile, sapling_params::Status),
+) -> jni::errors::Result<JObject<'a>> {
+    let file_name = env.new_string(file.name)?;
+    env.new_object(
+        JNI_SAPLING_PARAM_STATUS,
+        "(Ljava/lang/String;I)V",
+        &[(&file_name).into(), JValue::Int(status as i32)],
+    )
+}
+
+/// Checks the size and hash of the Sapling parameter files at the given paths.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_jni_RustBackend_verifySaplingParams<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    spend_params: JString<'local>,
+    output_params: JString<'local>,
+) -> jobjectArray {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("RustBackend.verifySaplingParams").entered();
+        let spend_params = path_from_jni(env, spend_params)?;
+        let output_params = path_from_jni(env, output_params)?;
+
+        let statuses = sapling_params::files(&spend_params, &output_params)
+            .into_iter()
+            .map(|(file, path)| Ok((file, sapling_params::verify(file, path)?)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(utils::rust_vec_to_java(
+            env,
+            statuses,
+            JNI_SAPLING_PARAM_STATUS,
+            encode_sapling_param_status,
+        )?
+        .into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
+/// Downloads any of the Sapling parameter files that are missing or corrupt at the given
+/// paths over Tor, resuming interrupted downloads.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_model_TorClient_downloadSaplingParams<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    tor_runtime: jlong,
+    spend_params: JString<'local>,
+    output_params: JString<'local>,
+) {
+    let res = catch_unwind(&mut env, |env| {
+        let tor_runtime =
+            std::ptr::with_exposed_provenance_mut::<crate::tor::TorRuntime>(tor_runtime as usize);
+        let tor_runtime =
+            unsafe { tor_runtime.as_mut() }.ok_or_else(|| anyhow!("A Tor runtime is required"))?;
+
+        let spend_params = path_from_jni(env, spend_params)?;
+        let output_params = path_from_jni(env, output_params)?;
+
+        for (file, path) in sapling_params::files(&spend_params, &output_params) {
+            sapling_params::download(file, path, tor_runtime)?;
+        }
+
+        Ok(())
+    });
+    unwrap_exc_or(&mut env, res, ())
+}
+
+//
+// Animated QR support
+//
+
+/// Creates a decoder for reassembling a PCZT from scanned `ur:zcash-pczt` parts.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_model_PcztUrDecoder_createPcztUrDecoder<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+) -> jlong {
+    let res = catch_unwind(&mut env, |_| {
+        let decoder = pczt_ur::PcztUrDecoder::default();
+        Ok(Box::into_raw(Box::new(decoder)).expose_provenance() as jlong)
+    });
+    unwrap_exc_or(&mut env, res, -1)
+}
+
+/// Frees a PCZT UR decoder.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_model_PcztUrDecoder_freePcztUrDecoder<
+    'local,
+>(
+    _: JNIEnv<'local>,
+    _: JClass<'local>,
+    ptr: jlong,
+) {
+    let ptr = ptr::with_exposed_provenance_mut::<pczt_ur::PcztUrDecoder>(ptr as usize);
+    if !ptr.is_null() {
+        let s = unsafe { Box::from_raw(ptr) };
+        drop(s);
+    }
+}
+
+const JNI_PCZT_UR_PROGRESS: &str = "cash/z/ecc/android/sdk/internal/model/JniPcztUrProgress";
+
+fn encode_pczt_ur_progress<'a>(
+    env: &mut JNIEnv<'a>,
+    progress: pczt_ur::Progress,
+) -> anyhow::Result<JObject<'a>> {
+    let pczt = match progress.pczt {
+        Some(pczt) => env.byte_array_from_slice(&pczt)?.into(),
+        None => JObject::null(),
+    };
+
+    Ok(env.new_object(
+        JNI_PCZT_UR_PROGRESS,
+        "(II[B)V",
+        &[
+            JValue::Int(i32::try_from(progress.received_parts)?),
+            // Use -1 to return null across the FFI.
+            JValue::Int(progress.expected_parts.map_or(Ok(-1), i32::try_from)?),
+            (&pczt).into(),
+        ],
+    )?)
+}
+
+/// Adds a scanned `ur:zcash-pczt` part to the given decoder.
+///
+/// Returns the decoder's progress, including the reassembled PCZT once enough parts have
+/// been received.
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_model_PcztUrDecoder_receivePcztUrPart<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    decoder: jlong,
+    part: JString<'local>,
+) -> jobject {
+    let res = catch_unwind(&mut env, |env| {
+        let decoder = ptr::with_exposed_provenance_mut::<pczt_ur::PcztUrDecoder>(decoder as usize);
+        let decoder =
+            unsafe { decoder.as_mut() }.ok_or_else(|| anyhow!("A PCZT UR decoder is required"))?;
+
+        let part = utils::java_string_to_rust(env, &part)?;
+        let progress = decoder.receive(&part)?;
+
+        Ok(encode_pczt_ur_progress(env, progress)?.into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
 //
 // Tor support
 //
@@ -3297,6 +5635,69 @@ pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_model_TorWalletClient_sub
     unwrap_exc_or(&mut env, res, ())
 }
 
+/// The largest number of blocks that `TorWalletClient.scanBlocks` fetches and scans at
+/// once. The blocks are held in memory while they are scanned, so this bounds the memory
+/// that a call uses. It matches the SDK's scanning batch size.
+const MAX_STREAMED_SCAN_BLOCKS: u32 = 1000;
+
+/// Fetches up to `limit` compact blocks starting at `from_height` over the given
+/// lightwalletd connection, and scans them without writing them to the block cache.
+/// Blocks above the server's chain tip are not fetched.
+///
+/// `limit` must be at most [`MAX_STREAMED_SCAN_BLOCKS`].
+#[unsafe(no_mangle)]
+pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_model_TorWalletClient_scanBlocks<'local>(
+    mut env: JNIEnv<'local>,
+    _: JClass<'local>,
+    lwd_conn: jlong,
+    db_data: JString<'local>,
+    from_height: jlong,
+    from_state: JByteArray<'local>,
+    limit: jlong,
+    network_id: jint,
+) -> jobject {
+    let res = catch_unwind(&mut env, |env| {
+        let _span = tracing::info_span!("TorWalletClient.scanBlocks").entered();
+        let network = parse_network(network_id as u32)?;
+        let db_path = path_from_jni(env, db_data)?;
+        let from_height = BlockHeight::try_from(from_height)?;
+        let from_state = parse_treestate(env, from_state)?.to_chain_state()?;
+        let limit = u32::try_from(limit)?;
+        if limit == 0 {
+            return Err(anyhow!("At least one block must be scanned"));
+        }
+        if limit > MAX_STREAMED_SCAN_BLOCKS {
+            return Err(anyhow!(
+                "At most {} blocks can be scanned at once, but {} were requested",
+                MAX_STREAMED_SCAN_BLOCKS,
+                limit
+            ));
+        }
+
+        let lwd_conn = ptr::with_exposed_provenance_mut::<crate::tor::LwdConn>(lwd_conn as usize);
+        let lwd_conn = unsafe { lwd_conn.as_mut() }
+            .ok_or_else(|| anyhow!("A Tor lightwalletd connection is required"))?;
+
+        let tip = BlockHeight::try_from(lwd_conn.get_latest_block()?.height)?;
+        let end_height = block_source::batch_end(from_height, limit, tip)?;
+        let blocks = block_source::MemoryBlockSource::new(
+            lwd_conn.get_block_range(from_height, end_height)?,
+        );
+
+        Ok(scan_blocks_from(
+            env,
+            network,
+            &blocks,
+            &db_path,
+            from_height,
+            &from_state,
+            usize::try_from(end_height - from_height)? + 1,
+        )?
+        .into_raw())
+    });
+    unwrap_exc_or(&mut env, res, ptr::null_mut())
+}
+
 /// Fetches the note commitment tree state corresponding to the given block height.
 #[unsafe(no_mangle)]
 pub extern "C" fn Java_cash_z_ecc_android_sdk_internal_model_TorWalletClient_getTreeState<
@@ -3595,6 +5996,21 @@ fn parse_protocol(code: i32) -> anyhow::Result<ShieldedProtocol> {
     }
 }
 
+fn parse_pool(code: i32) -> anyhow::Result<PoolType> {
+    match code {
+        0 => Ok(PoolType::Transparent),
+        _ => parse_protocol(code).map(PoolType::Shielded),
+    }
+}
+
+fn pool_code(pool: PoolType) -> i32 {
+    match pool {
+        PoolType::Transparent => 0,
+        PoolType::Shielded(ShieldedProtocol::Sapling) => 2,
+        PoolType::Shielded(ShieldedProtocol::Orchard) => 3,
+    }
+}
+
 fn parse_network(value: u32) -> anyhow::Result<Network> {
     match value {
         0 => Ok(TestNetwork),
@@ -0,0 +1,189 @@
+//! ZIP 302 memo support.
+//!
+//! [`Memo`] treats memos starting with 0xF5 as reserved for future use, and cannot
+//! represent text memos that are not valid UTF-8. The classification here follows
+//! [ZIP 302] directly instead, so that callers can tell every kind of memo apart.
+//!
+//! Text memos are also checked for the informal reply-to convention used by several
+//! wallets, in which the sender's address follows a `Reply-To:` label at the end of
+//! the memo.
+//!
+//! [`Memo`]: zcash_protocol::memo::Memo
+//! [ZIP 302]: https://zips.z.cash/zip-0302
+
+use anyhow::anyhow;
+use zcash_client_backend::address::Address;
+use zcash_protocol::{consensus::Parameters, memo::MemoBytes};
+
+/// The first byte of a ZIP 302 arbitrary-data memo.
+const ARBITRARY_DATA_PREFIX: u8 = 0xF5;
+
+/// The labels that introduce a reply-to address, in lowercase.
+const REPLY_TO_LABELS: [&str; 2] = ["reply-to:", "reply to:"];
+
+/// The kind of a memo, as determined by its first byte.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum MemoKind {
+    /// The empty memo: 0xF6 followed by 511 zero bytes.
+    Empty,
+    /// A UTF-8 text memo. The first byte is 0xF4 or smaller.
+    Text,
+    /// Arbitrary data, whose first byte is 0xF5 or 0xFF.
+    Arbitrary,
+    /// A memo in a format that is reserved for future use, or a text memo that is not
+    /// valid UTF-8 and so cannot be interpreted.
+    Future,
+}
+
+impl MemoKind {
+    pub(crate) fn classify(memo: &MemoBytes) -> Self {
+        let bytes = memo.as_array();
+        match bytes[0] {
+            0xF6 if bytes[1..].iter().all(|&b| b == 0) => MemoKind::Empty,
+            b if b <= 0xF4 => {
+                if std::str::from_utf8(memo.as_slice()).is_ok() {
+                    MemoKind::Text
+                } else {
+                    MemoKind::Future
+                }
+            }
+            0xF5 | 0xFF => MemoKind::Arbitrary,
+            _ => MemoKind::Future,
+        }
+    }
+
+    pub(crate) fn code(self) -> i32 {
+        match self {
+            MemoKind::Empty => 0,
+            MemoKind::Text => 1,
+            MemoKind::Arbitrary => 2,
+            MemoKind::Future => 3,
+        }
+    }
+}
+
+/// The contents of a memo, interpreted according to its kind.
+pub(crate) struct DecodedMemo {
+    pub(crate) kind: MemoKind,
+    /// The text of a text memo, without any reply-to address.
+    pub(crate) text: Option<String>,
+    /// The 511 bytes following the first byte of an arbitrary-data memo.
+    pub(crate) data: Option<Vec<u8>>,
+    /// The reply-to address embedded in a text memo, if any.
+    pub(crate) reply_to: Option<String>,
+}
+
+/// Decodes the given memo, recognising reply-to addresses that are valid for `params`.
+pub(crate) fn decode<P: Parameters>(params: &P, memo: &MemoBytes) -> DecodedMemo {
+    let kind = MemoKind::classify(memo);
+    let (text, data, reply_to) = match kind {
+        MemoKind::Text => {
+            let text = std::str::from_utf8(memo.as_slice()).expect("checked by classify");
+            let (text, reply_to) =
+                split_reply_to(text, |addr| Address::decode(params, addr).is_some());
+            (Some(text.to_owned()), None, reply_to.map(str::to_owned))
+        }
+        MemoKind::Arbitrary => (None, Some(memo.as_array()[1..].to_vec()), None),
+        MemoKind::Empty | MemoKind::Future => (None, None, None),
+    };
+
+    DecodedMemo {
+        kind,
+        text,
+        data,
+        reply_to,
+    }
+}
+
+/// Encodes `data` as a ZIP 302 arbitrary-data memo, with the 0xF5 prefix.
+///
+/// `data` may be at most 511 bytes long; shorter data is padded with zeros.
+pub(crate) fn encode_arbitrary(data: &[u8]) -> anyhow::Result<MemoBytes> {
+    let mut bytes = Vec::with_capacity(1 + data.len());
+    bytes.push(ARBITRARY_DATA_PREFIX);
+    bytes.extend_from_slice(data);
+    MemoBytes::from_bytes(&bytes).map_err(|_| {
+        anyhow!(
+            "Arbitrary memo data is {} bytes, but at most 511 bytes are allowed",
+            data.len()
+        )
+    })
+}
+
+/// Splits a trailing reply-to address from the given text.
+///
+/// The address must follow the last reply-to label in the text, separated from it only
+/// by whitespace, and must be the last thing in the text. Returns the text preceding the
+/// label, and the address if `is_address` accepts it.
+fn split_reply_to(text: &str, is_address: impl Fn(&str) -> bool) -> (&str, Option<&str>) {
+    // ASCII lowercasing preserves byte offsets.
+    let lowercase = text.to_ascii_lowercase();
+    let label = REPLY_TO_LABELS
+        .iter()
+        .filter_map(|label| lowercase.rfind(label).map(|start| (start, label.len())))
+        .max_by_key(|(start, _)| *start);
+
+    if let Some((start, len)) = label {
+        let address = text[start + len..].trim();
+        if !address.is_empty() && !address.contains(char::is_whitespace) && is_address(address) {
+            return (text[..start].trim_end(), Some(address));
+        }
+    }
+
+    (text, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memo(prefix: &[u8]) -> MemoBytes {
+        MemoBytes::from_bytes(prefix).unwrap()
+    }
+
+    #[test]
+    fn classifies_memos() {
+        assert_eq!(MemoKind::classify(&MemoBytes::empty()), MemoKind::Empty);
+        assert_eq!(MemoKind::classify(&memo(b"Thanks!")), MemoKind::Text);
+        assert_eq!(
+            MemoKind::classify(&memo(&[0xF5, 1, 2, 3])),
+            MemoKind::Arbitrary
+        );
+        assert_eq!(MemoKind::classify(&memo(&[0xFF])), MemoKind::Arbitrary);
+        assert_eq!(MemoKind::classify(&memo(&[0xF6, 1])), MemoKind::Future);
+        assert_eq!(MemoKind::classify(&memo(&[0xF7])), MemoKind::Future);
+        assert_eq!(MemoKind::classify(&memo(&[0xC3, 0x28])), MemoKind::Future);
+    }
+
+    #[test]
+    fn arbitrary_round_trip() {
+        let encoded = encode_arbitrary(&[1, 2, 3]).unwrap();
+        assert_eq!(MemoKind::classify(&encoded), MemoKind::Arbitrary);
+        assert_eq!(&encoded.as_array()[..4], &[0xF5, 1, 2, 3]);
+        assert!(encode_arbitrary(&[0; 511]).is_ok());
+        assert!(encode_arbitrary(&[0; 512]).is_err());
+    }
+
+    #[test]
+    fn splits_reply_to() {
+        let is_address = |addr: &str| addr.starts_with("u1");
+
+        assert_eq!(
+            split_reply_to("Lunch money\nReply-To:\nu1abc", is_address),
+            ("Lunch money", Some("u1abc"))
+        );
+        assert_eq!(
+            split_reply_to("Thanks! reply to: u1abc\n", is_address),
+            ("Thanks!", Some("u1abc"))
+        );
+        assert_eq!(
+            split_reply_to("Reply-To: zs1abc", is_address),
+            ("Reply-To: zs1abc", None)
+        );
+        assert_eq!(
+            split_reply_to("Reply-To: u1abc and more", is_address),
+            ("Reply-To: u1abc and more", None)
+        );
+        assert_eq!(split_reply_to("Hello", is_address), ("Hello", None));
+    }
+}
@@ -0,0 +1,354 @@
+//! PCZT support for multi-step proposals.
+//!
+//! `create_pczt_from_proposal` only supports single-step proposals. A proposal that pays
+//! a TEX address has two steps: the first sends funds to an ephemeral transparent
+//! address, and the second spends that ephemeral output to the TEX address. We create a
+//! PCZT for each step by converting it into a single-step proposal, with the ephemeral
+//! outputs of the previous step as its transparent inputs.
+//!
+//! The transaction ID of each step is fixed once its inputs and outputs are finalized,
+//! so every PCZT in the chain can be created (and signed) before any of them has been
+//! extracted. Each PCZT after the first records the txid of the step it spends from, in
+//! the [`PROPRIETARY_PRIOR_STEP_TXID`] global proprietary field.
+
+use std::convert::Infallible;
+
+use anyhow::anyhow;
+use pczt::{Pczt, roles::updater::Updater};
+#[cfg(test)]
+use transparent::address::TransparentAddress;
+use transparent::bundle::{OutPoint, TxOut};
+use zcash_client_backend::{
+    data_api::{
+        WalletRead,
+        wallet::{create_pczt_from_proposal, extract_and_store_transaction_from_pczt},
+    },
+    fees::StandardFeeRule,
+    proposal::{Proposal, Step, StepOutputIndex},
+    wallet::{OvkPolicy, WalletTransparentOutput},
+};
+use zcash_client_sqlite::{AccountUuid, ReceivedNoteId};
+use zcash_primitives::transaction::{
+    TxId,
+    txid::{TxIdDigester, to_txid},
+};
+use zcash_protocol::{consensus::Network, value::Zatoshis};
+
+use crate::{coin_control::Db, deferred_store::DeferredStore};
+
+/// The global proprietary field recording the txid of the previous step in the chain.
+pub(crate) const PROPRIETARY_PRIOR_STEP_TXID: &str = "zcash_android_wallet_sdk:prior_step_txid";
+
+/// Computes the txid of the transaction that the given PCZT will produce.
+///
+/// The txid does not commit to proofs or signatures, so this can be called on a PCZT
+/// at any point after its inputs and outputs have been finalized.
+pub(crate) fn pczt_txid(pczt: &Pczt) -> anyhow::Result<TxId> {
+    let tx_data = pczt
+        .clone()
+        .into_effects()
+        .ok_or_else(|| anyhow!("PCZT does not describe a complete transaction"))?;
+    let txid_parts = tx_data.digest(TxIdDigester);
+    Ok(to_txid(
+        tx_data.version(),
+        tx_data.consensus_branch_id(),
+        &txid_parts,
+    ))
+}
+
+/// Returns the txid of the previous step recorded in the given PCZT, if any.
+pub(crate) fn prior_step_txid(pczt: &Pczt) -> anyhow::Result<Option<TxId>> {
+    pczt.global()
+        .proprietary()
+        .get(PROPRIETARY_PRIOR_STEP_TXID)
+        .map(|bytes| {
+            Ok(TxId::from_bytes(bytes[..].try_into().map_err(|_| {
+                anyhow!("Invalid {} field", PROPRIETARY_PRIOR_STEP_TXID)
+            })?))
+        })
+        .transpose()
+}
+
+/// Returns the ephemeral transparent outputs of the given PCZT, in output order.
+fn ephemeral_outputs(
+    db_data: &Db,
+    account_id: AccountUuid,
+    pczt: &Pczt,
+) -> anyhow::Result<Vec<WalletTransparentOutput>> {
+    // Every ephemeral address reserved for the chain was exposed at the chain tip, so an
+    // unlimited exposure depth returns all of them.
+    let ephemeral_addrs = db_data
+        .get_ephemeral_transparent_receivers(account_id, u32::MAX, false)
+        .map_err(|e| anyhow!("Error while fetching ephemeral addresses: {}", e))?;
+    let txid = pczt_txid(pczt)?;
+
+    let mut outputs = vec![];
+    for (index, output) in pczt.transparent().outputs().iter().enumerate() {
+        let txout = TxOut::new(
+            Zatoshis::from_u64(*output.value())
+                .map_err(|_| anyhow!("Invalid transparent output value"))?,
+            transparent::address::Script(zcash_script::script::Code(
+                output.script_pubkey().clone(),
+            )),
+        );
+        if txout
+            .recipient_address()
+            .is_some_and(|addr| ephemeral_addrs.contains_key(&addr))
+        {
+            outputs.push(
+                WalletTransparentOutput::from_parts(
+                    OutPoint::new(*txid.as_ref(), u32::try_from(index)?),
+                    txout,
+                    None,
+                )
+                .ok_or_else(|| anyhow!("Ephemeral output is not a transparent address"))?,
+            );
+        }
+    }
+
+    Ok(outputs)
+}
+
+/// Converts a step of a multi-step proposal into a single-step proposal, spending the
+/// given ephemeral outputs of the previous step in place of its prior step inputs.
+fn step_as_proposal(
+    proposal: &Proposal<StandardFeeRule, ReceivedNoteId>,
+    step: &Step<ReceivedNoteId>,
+    prior_ephemeral_outputs: &[WalletTransparentOutput],
+) -> anyhow::Result<Proposal<StandardFeeRule, ReceivedNoteId>> {
+    let mut transparent_inputs = step.transparent_inputs().to_vec();
+    for input in step.prior_step_inputs() {
+        // The ephemeral outputs of a step are its only change outputs that a later step
+        // can spend, and they appear in the transaction in the order they were proposed.
+        let ephemeral_index = match input.output_index() {
+            StepOutputIndex::Change(i) => {
+                proposal.steps().get(input.step_index()).and_then(|prior| {
+                    let change = prior.balance().proposed_change();
+                    change
+                        .get(i)
+                        .filter(|c| c.is_ephemeral())
+                        .map(|_| change[..i].iter().filter(|c| c.is_ephemeral()).count())
+                })
+            }
+            StepOutputIndex::Payment(_) => None,
+        }
+        .ok_or_else(|| {
+            anyhow!("Only ephemeral outputs of a prior step can be spent by a PCZT chain")
+        })?;
+
+        transparent_inputs.push(
+            prior_ephemeral_outputs
+                .get(ephemeral_index)
+                .cloned()
+                .ok_or_else(|| anyhow!("Prior step is missing an ephemeral output"))?,
+        );
+    }
+
+    Proposal::single_step(
+        step.transaction_request().clone(),
+        step.payment_pools().clone(),
+        transparent_inputs,
+        step.shielded_inputs().cloned(),
+        step.balance().clone(),
+        *proposal.fee_rule(),
+        proposal.min_target_height(),
+        step.is_shielding(),
+    )
+    .map_err(|e| anyhow!("Error creating proposal for step: {}", e))
+}
+
+/// Creates one PCZT for each step of the given proposal.
+///
+/// Each step may only depend upon the step immediately before it.
+pub(crate) fn create_pczts_from_proposal(
+    db_data: &mut Db,
+    params: &Network,
+    account_id: AccountUuid,
+    proposal: &Proposal<StandardFeeRule, ReceivedNoteId>,
+) -> anyhow::Result<Vec<Pczt>> {
+    let mut pczts: Vec<Pczt> = Vec::with_capacity(proposal.steps().len());
+
+    for (step_index, step) in proposal.steps().iter().enumerate() {
+        if step
+            .prior_step_inputs()
+            .iter()
+            .any(|input| input.step_index() + 1 != step_index)
+        {
+            return Err(anyhow!(
+                "Step {} spends outputs of a step other than the one before it",
+                step_index
+            ));
+        }
+
+        let prior = match pczts.last() {
+            Some(prior) if !step.prior_step_inputs().is_empty() => Some((
+                pczt_txid(prior)?,
+                ephemeral_outputs(db_data, account_id, prior)?,
+            )),
+            _ => None,
+        };
+
+        let step_proposal = step_as_proposal(
+            proposal,
+            step,
+            prior.as_ref().map_or(&[][..], |(_, outputs)| &outputs[..]),
+        )?;
+
+        let pczt = create_pczt_from_proposal::<_, _, Infallible, _, Infallible, _>(
+            db_data,
+            params,
+            account_id,
+            OvkPolicy::Sender,
+            &step_proposal,
+        )
+        .map_err(|e| {
+            anyhow!(
+                "Error creating PCZT for proposal step {}: {}",
+                step_index,
+                e
+            )
+        })?;
+
+        let pczt = match prior {
+            Some((prior_txid, _)) => Updater::new(pczt)
+                .update_global_with(|mut updater| {
+                    updater.set_proprietary(
+                        PROPRIETARY_PRIOR_STEP_TXID.into(),
+                        prior_txid.as_ref().to_vec(),
+                    )
+                })
+                .finish(),
+            None => pczt,
+        };
+
+        pczts.push(pczt);
+    }
+
+    Ok(pczts)
+}
+
+/// Extracts the transactions from a chain of fully proven and signed PCZTs, as created by
+/// [`create_pczts_from_proposal`], and stores them in the wallet.
+///
+/// Every PCZT in the chain is extracted and verified, and the linkage between steps is
+/// checked, before the transactions are stored together in a single database
+/// transaction, so that either every step of the chain is stored or none is.
+pub(crate) fn extract_and_store_chain(
+    db_data: &mut Db,
+    pczts: Vec<Pczt>,
+    sapling_vks: (
+        &sapling::circuit::SpendVerifyingKey,
+        &sapling::circuit::OutputVerifyingKey,
+    ),
+    orchard_vk: &orchard::circuit::VerifyingKey,
+) -> anyhow::Result<Vec<TxId>> {
+    let mut deferred = DeferredStore::new(db_data);
+    let mut txids: Vec<TxId> = Vec::with_capacity(pczts.len());
+    for (step_index, pczt) in pczts.into_iter().enumerate() {
+        if prior_step_txid(&pczt)? != txids.last().copied() {
+            return Err(anyhow!(
+                "PCZT {} does not follow the previous PCZT in the chain",
+                step_index
+            ));
+        }
+
+        txids.push(
+            extract_and_store_transaction_from_pczt::<_, ()>(
+                &mut deferred,
+                pczt,
+                Some(sapling_vks),
+                Some(orchard_vk),
+            )
+            .map_err(|e| {
+                anyhow!(
+                    "Failed to extract transaction from PCZT {}: {:?}",
+                    step_index,
+                    e
+                )
+            })?,
+        );
+    }
+
+    deferred
+        .commit()
+        .map_err(|e| anyhow!("Failed to store transactions from PCZTs: {}", e))?;
+    Ok(txids)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use zcash_client_backend::data_api::WalletWrite;
+    use zcash_client_sqlite::{WalletDb, util::SystemClock};
+    use zcash_protocol::consensus::BlockHeight;
+
+    use super::*;
+    use crate::testing::{NETWORK, add_account, transparent_pczt, wallet_db};
+
+    #[test]
+    fn prior_step_txid_round_trips() {
+        let to = TransparentAddress::PublicKeyHash([0; 20]);
+        let pczt = transparent_pczt(1, &to, 50_000);
+        assert_eq!(prior_step_txid(&pczt).unwrap(), None);
+
+        let with_field = |bytes: Vec<u8>| {
+            Updater::new(pczt.clone())
+                .update_global_with(|mut updater| {
+                    updater.set_proprietary(PROPRIETARY_PRIOR_STEP_TXID.into(), bytes)
+                })
+                .finish()
+        };
+        let txid = pczt_txid(&pczt).unwrap();
+        assert_eq!(
+            prior_step_txid(&with_field(txid.as_ref().to_vec())).unwrap(),
+            Some(txid)
+        );
+        assert!(prior_step_txid(&with_field(vec![0; 31])).is_err());
+    }
+
+    #[test]
+    fn txid_does_not_depend_on_proprietary_fields() {
+        let to = TransparentAddress::PublicKeyHash([0; 20]);
+        let pczt = transparent_pczt(1, &to, 50_000);
+        let updated = Updater::new(pczt.clone())
+            .update_global_with(|mut updater| {
+                updater.set_proprietary(PROPRIETARY_PRIOR_STEP_TXID.into(), vec![0; 32])
+            })
+            .finish();
+        assert_eq!(pczt_txid(&pczt).unwrap(), pczt_txid(&updated).unwrap());
+        assert_ne!(
+            pczt_txid(&pczt).unwrap(),
+            pczt_txid(&transparent_pczt(2, &to, 50_000)).unwrap()
+        );
+    }
+
+    #[test]
+    fn finds_outputs_to_ephemeral_addresses() {
+        let mut conn = wallet_db();
+        let account = add_account(&mut conn, 1, 3_000_000);
+        let mut db_data = WalletDb::from_connection(conn, NETWORK, SystemClock, OsRng);
+        db_data
+            .update_chain_tip(BlockHeight::from_u32(3_000_100))
+            .unwrap();
+        let (ephemeral, _) = db_data
+            .reserve_next_n_ephemeral_addresses(account, 1)
+            .unwrap()[0];
+
+        let pczt = transparent_pczt(2, &ephemeral, 50_000);
+        let outputs = ephemeral_outputs(&db_data, account, &pczt).unwrap();
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(
+            outputs[0].outpoint(),
+            &OutPoint::new(*pczt_txid(&pczt).unwrap().as_ref(), 0)
+        );
+        assert_eq!(outputs[0].recipient_address(), &ephemeral);
+        assert_eq!(u64::from(outputs[0].value()), 50_000);
+
+        let pczt = transparent_pczt(2, &TransparentAddress::PublicKeyHash([0; 20]), 50_000);
+        assert!(
+            ephemeral_outputs(&db_data, account, &pczt)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! The PCZT Signer role, using a unified spending key held by the app.
+//!
+//! The Signer checks each signature against the input's randomized verification key (or
+//! for transparent inputs, its script), so we try the account's keys against every input
+//! and skip those that belong to someone else. This also skips the dummy spends that pad
+//! the Sapling and Orchard bundles, which have already been signed by the IO Finalizer.
+
+use std::convert::Infallible;
+
+use anyhow::anyhow;
+use pczt::{
+    Pczt,
+    roles::{
+        signer::{self, Signer},
+        verifier::Verifier,
+    },
+};
+use transparent::keys::{NonHardenedChildIndex, TransparentKeyScope};
+use zcash_client_backend::keys::UnifiedSpendingKey;
+
+/// Signs every input of the given PCZT that can be spent with the given key.
+///
+/// Returns an error if the PCZT has no such inputs.
+pub(crate) fn sign_pczt(pczt: Pczt, usk: &UnifiedSpendingKey) -> anyhow::Result<Pczt> {
+    // The BIP 44 derivation of each transparent input ends in its scope and address index.
+    let mut transparent_inputs = vec![];
+    let pczt = Verifier::new(pczt)
+        .with_transparent::<Infallible, _>(|bundle| {
+            transparent_inputs = bundle
+                .inputs()
+                .iter()
+                .map(|input| {
+                    input
+                        .bip32_derivation()
+                        .values()
+                        .filter_map(|derivation| match derivation.derivation_path()[..] {
+                            [.., scope, address_index] => Some((
+                                TransparentKeyScope::custom(scope.0)?,
+                                NonHardenedChildIndex::from_index(address_index.0)?,
+                            )),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            Ok(())
+        })
+        .map_err(|e| anyhow!("Invalid transparent bundle in PCZT: {:?}", e))?
+        .finish();
+    let sapling_spends = pczt.sapling().spends().len();
+    let orchard_actions = pczt.orchard().actions().len();
+
+    let mut signer =
+        Signer::new(pczt).map_err(|e| anyhow!("Failed to initialize Signer: {:?}", e))?;
+    let mut signed = 0;
+
+    for (index, paths) in transparent_inputs.into_iter().enumerate() {
+        for (scope, address_index) in paths {
+            let sk = match usk.transparent().derive_secret_key(scope, address_index) {
+                Ok(sk) => sk,
+                Err(_) => continue,
+            };
+            match signer.sign_transparent(index, &sk) {
+                Ok(()) => {
+                    signed += 1;
+                    break;
+                }
+                Err(signer::Error::TransparentSign(
+                    transparent::pczt::SignerError::WrongSpendingKey,
+                )) => {}
+                Err(e) => {
+                    return Err(anyhow!(
+                        "Failed to sign transparent input {}: {:?}",
+                        index,
+                        e
+                    ));
+                }
+            }
+        }
+    }
+
+    let sapling_ask = &usk.sapling().expsk.ask;
+    for index in 0..sapling_spends {
+        match signer.sign_sapling(index, sapling_ask) {
+            Ok(()) => signed += 1,
+            Err(signer::Error::SaplingSign(
+                sapling::pczt::SignerError::WrongSpendAuthorizingKey,
+            )) => {}
+            Err(e) => {
+                return Err(anyhow!("Failed to sign Sapling spend {}: {:?}", index, e));
+            }
+        }
+    }
+
+    let orchard_ask = orchard::keys::SpendAuthorizingKey::from(usk.orchard());
+    for index in 0..orchard_actions {
+        match signer.sign_orchard(index, &orchard_ask) {
+            Ok(()) => signed += 1,
+            Err(signer::Error::OrchardSign(
+                orchard::pczt::SignerError::WrongSpendAuthorizingKey,
+            )) => {}
+            Err(e) => {
+                return Err(anyhow!("Failed to sign Orchard action {}: {:?}", index, e));
+            }
+        }
+    }
+
+    if signed == 0 {
+        return Err(anyhow!(
+            "PCZT has no inputs that can be signed with the given spending key"
+        ));
+    }
+
+    Ok(signer.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use pczt::roles::updater::Updater;
+    use transparent::{address::TransparentAddress, keys::AccountPrivKey, pczt::Bip32Derivation};
+    use zip32::fingerprint::SeedFingerprint;
+
+    use super::*;
+    use crate::testing::{NETWORK, transparent_pczt};
+
+    const HARDENED: u32 = 1 << 31;
+
+    fn usk(seed_byte: u8) -> UnifiedSpendingKey {
+        UnifiedSpendingKey::from_seed(&NETWORK, &[seed_byte; 32], zip32::AccountId::ZERO).unwrap()
+    }
+
+    /// Returns a PCZT spending from the account derived from a seed of repeated
+    /// `seed_byte`s, with the BIP 44 derivation of its input recorded as the wallet's
+    /// Updater would.
+    fn pczt_from(seed_byte: u8) -> Pczt {
+        let seed = [seed_byte; 32];
+        let pubkey = AccountPrivKey::from_seed(&NETWORK, &seed, zip32::AccountId::ZERO)
+            .unwrap()
+            .to_account_pubkey()
+            .derive_address_pubkey(TransparentKeyScope::EXTERNAL, NonHardenedChildIndex::ZERO)
+            .unwrap();
+        let derivation = Bip32Derivation::parse(
+            SeedFingerprint::from_seed(&seed).unwrap().to_bytes(),
+            vec![44 | HARDENED, 1 | HARDENED, HARDENED, 0, 0],
+        )
+        .unwrap();
+
+        let pczt = transparent_pczt(
+            seed_byte,
+            &TransparentAddress::PublicKeyHash([0; 20]),
+            50_000,
+        );
+        Updater::new(pczt)
+            .update_transparent_with(|mut updater| {
+                updater.update_input_with(0, |mut input| {
+                    input.set_bip32_derivation(pubkey.serialize(), derivation);
+                    Ok(())
+                })
+            })
+            .unwrap()
+            .finish()
+    }
+
+    fn signatures(pczt: Pczt) -> usize {
+        let mut signatures = 0;
+        Verifier::new(pczt)
+            .with_transparent::<Infallible, _>(|bundle| {
+                signatures = bundle.inputs()[0].partial_signatures().len();
+                Ok(())
+            })
+            .unwrap();
+        signatures
+    }
+
+    #[test]
+    fn signs_inputs_of_the_account() {
+        let pczt = pczt_from(1);
+        assert_eq!(signatures(pczt.clone()), 0);
+
+        let signed = sign_pczt(pczt, &usk(1)).unwrap();
+        assert_eq!(signatures(signed), 1);
+    }
+
+    #[test]
+    fn rejects_pczt_without_inputs_of_the_account() {
+        assert!(sign_pczt(pczt_from(1), &usk(2)).is_err());
+
+        // Without a recorded derivation, we cannot find the key for an input.
+        let pczt = transparent_pczt(1, &TransparentAddress::PublicKeyHash([0; 20]), 50_000);
+        assert!(sign_pczt(pczt, &usk(1)).is_err());
+    }
+}
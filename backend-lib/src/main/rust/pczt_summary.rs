@@ -0,0 +1,732 @@
+//! Human-readable summaries of PCZTs, for confirming a transaction before it is signed.
+//!
+//! A PCZT created by `create_pczt_from_proposal` records the address of each payment in
+//! its `user_address` field, and whether each output is a payment or change in the
+//! `zcash_client_backend:output_info` proprietary field. Both may have been removed by a
+//! Redactor, in which case the address is reconstructed from the raw recipient.
+//!
+//! Shielded memos are only present inside the note ciphertexts. They are recovered with
+//! the outgoing cipher key if the PCZT still contains it.
+
+use anyhow::anyhow;
+use orchard::note_encryption::OrchardDomain;
+use pczt::{
+    Pczt,
+    roles::verifier::{OrchardError, SaplingError, Verifier},
+};
+use sapling::note_encryption::{SaplingDomain, Zip212Enforcement};
+use transparent::address::TransparentAddress;
+use zcash_address::{ToAddress, ZcashAddress, unified};
+use zcash_note_encryption::{
+    Domain, ENC_CIPHERTEXT_SIZE, EphemeralKeyBytes, OUT_CIPHERTEXT_SIZE, OutgoingCipherKey,
+    ShieldedOutput, try_output_recovery_with_ock,
+};
+use zcash_protocol::{
+    PoolType, ShieldedProtocol,
+    consensus::{NetworkType, Parameters},
+    memo::MemoBytes,
+    value::Zatoshis,
+};
+use zcash_script::script;
+
+/// The proprietary field in which `zcash_client_backend` records the kind of each output.
+const PROPRIETARY_OUTPUT_INFO: &str = "zcash_client_backend:output_info";
+
+/// The kind of recipient of a PCZT output, as recorded in its output info.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum OutputKind {
+    /// A payment to an address outside of the wallet.
+    External,
+    /// A transparent output to one of the wallet's ephemeral addresses.
+    Ephemeral,
+    /// Change, or a payment to another account of the wallet.
+    Internal,
+    /// The PCZT does not record the kind of the output.
+    Unknown,
+}
+
+impl OutputKind {
+    /// Parses the output info recorded by `zcash_client_backend`.
+    ///
+    /// The output info is a postcard-encoded enum, so its first byte is the variant index.
+    fn from_output_info(output_info: Option<&Vec<u8>>) -> Self {
+        match output_info.and_then(|info| info.first()) {
+            Some(0) => OutputKind::External,
+            Some(1) => OutputKind::Ephemeral,
+            Some(2) => OutputKind::Internal,
+            _ => OutputKind::Unknown,
+        }
+    }
+
+    pub(crate) fn code(self) -> i32 {
+        match self {
+            OutputKind::External => 0,
+            OutputKind::Ephemeral => 1,
+            OutputKind::Internal => 2,
+            OutputKind::Unknown => 3,
+        }
+    }
+}
+
+/// An input spent by a PCZT.
+pub(crate) struct SpendSummary {
+    pub(crate) pool: PoolType,
+    pub(crate) index: usize,
+    /// The value of the input, unless it has been redacted.
+    pub(crate) value: Option<Zatoshis>,
+    pub(crate) missing_signature: bool,
+    pub(crate) missing_proof: bool,
+}
+
+/// An output created by a PCZT.
+pub(crate) struct OutputSummary {
+    pub(crate) pool: PoolType,
+    pub(crate) index: usize,
+    pub(crate) kind: OutputKind,
+    pub(crate) address: Option<String>,
+    /// The value of the output, unless it has been redacted.
+    pub(crate) value: Option<Zatoshis>,
+    /// The memo of a shielded output, if it could be recovered.
+    pub(crate) memo: Option<MemoBytes>,
+    pub(crate) missing_proof: bool,
+}
+
+pub(crate) struct PcztSummary {
+    pub(crate) spends: Vec<SpendSummary>,
+    pub(crate) outputs: Vec<OutputSummary>,
+    /// The fee paid by the transaction, or `None` if it cannot be determined.
+    pub(crate) fee: Option<i64>,
+    pub(crate) expiry_height: u32,
+    pub(crate) consensus_branch_id: u32,
+}
+
+/// A shielded output, as needed for note decryption.
+struct CiphertextOutput {
+    ephemeral_key: [u8; 32],
+    cmstar: [u8; 32],
+    enc_ciphertext: [u8; ENC_CIPHERTEXT_SIZE],
+}
+
+impl<D: Domain<ExtractedCommitmentBytes = [u8; 32]>> ShieldedOutput<D, ENC_CIPHERTEXT_SIZE>
+    for CiphertextOutput
+{
+    fn ephemeral_key(&self) -> EphemeralKeyBytes {
+        EphemeralKeyBytes(self.ephemeral_key)
+    }
+
+    fn cmstar_bytes(&self) -> [u8; 32] {
+        self.cmstar
+    }
+
+    fn enc_ciphertext(&self) -> &[u8; ENC_CIPHERTEXT_SIZE] {
+        &self.enc_ciphertext
+    }
+}
+
+/// Recovers the memo of a shielded output using its outgoing cipher key.
+fn recover_memo<D: Domain<ExtractedCommitmentBytes = [u8; 32], Memo = [u8; 512]>>(
+    domain: &D,
+    ock: Option<&OutgoingCipherKey>,
+    ephemeral_key: &[u8; 32],
+    cmstar: [u8; 32],
+    enc_ciphertext: &[u8; ENC_CIPHERTEXT_SIZE],
+    out_ciphertext: &[u8; OUT_CIPHERTEXT_SIZE],
+) -> Option<MemoBytes> {
+    let output = CiphertextOutput {
+        ephemeral_key: *ephemeral_key,
+        cmstar,
+        enc_ciphertext: *enc_ciphertext,
+    };
+
+    let (_, _, memo) = try_output_recovery_with_ock(domain, ock?, &output, out_ciphertext)?;
+    MemoBytes::from_bytes(&memo).ok()
+}
+
+fn zatoshis(value: u64) -> anyhow::Result<Zatoshis> {
+    Zatoshis::from_u64(value).map_err(|_| anyhow!("Invalid value {} in PCZT", value))
+}
+
+fn transparent_address(network: NetworkType, script_pubkey: &script::PubKey) -> Option<String> {
+    TransparentAddress::from_script_pubkey(script_pubkey).map(|addr| {
+        match addr {
+            TransparentAddress::PublicKeyHash(data) => {
+                ZcashAddress::from_transparent_p2pkh(network, data)
+            }
+            TransparentAddress::ScriptHash(data) => {
+                ZcashAddress::from_transparent_p2sh(network, data)
+            }
+        }
+        .encode()
+    })
+}
+
+fn orchard_address(network: NetworkType, recipient: [u8; 43]) -> Option<String> {
+    use zcash_address::unified::Encoding;
+
+    unified::Address::try_from_items(vec![unified::Receiver::Orchard(recipient)])
+        .ok()
+        .map(|ua| ZcashAddress::from_unified(network, ua).encode())
+}
+
+/// Sapling and Orchard bundles are padded with zero-valued dummy spends and outputs,
+/// which are not shown.
+fn is_dummy(value: Option<u64>, has_metadata: bool) -> bool {
+    value == Some(0) && !has_metadata
+}
+
+/// Returns the fee paid by the given PCZT, or `None` if it cannot be determined.
+///
+/// The fee is the transparent value balance plus the shielded value balances, which the
+/// PCZT commits to even when individual shielded values have been redacted.
+pub(crate) fn pczt_fee(pczt: &Pczt) -> Option<i64> {
+    let tx_data = pczt.clone().into_effects()?;
+    let transparent_in = pczt
+        .transparent()
+        .inputs()
+        .iter()
+        .try_fold(0i64, |acc, i| {
+            acc.checked_add(i64::try_from(*i.value()).ok()?)
+        })?;
+    let transparent_out = pczt
+        .transparent()
+        .outputs()
+        .iter()
+        .try_fold(0i64, |acc, o| {
+            acc.checked_add(i64::try_from(*o.value()).ok()?)
+        })?;
+    let sapling_balance = tx_data
+        .sapling_bundle()
+        .map_or(0, |b| i64::from(*b.value_balance()));
+    let orchard_balance = tx_data
+        .orchard_bundle()
+        .map_or(0, |b| i64::from(*b.value_balance()));
+
+    transparent_in
+        .checked_sub(transparent_out)?
+        .checked_add(sapling_balance)?
+        .checked_add(orchard_balance)
+}
+
+fn describe_transparent(
+    network: NetworkType,
+    bundle: &transparent::pczt::Bundle,
+    spends: &mut Vec<SpendSummary>,
+    outputs: &mut Vec<OutputSummary>,
+) {
+    for (index, input) in bundle.inputs().iter().enumerate() {
+        spends.push(SpendSummary {
+            pool: PoolType::Transparent,
+            index,
+            value: Some(*input.value()),
+            missing_signature: input.script_sig().is_none()
+                && input.partial_signatures().is_empty(),
+            missing_proof: false,
+        });
+    }
+    for (index, output) in bundle.outputs().iter().enumerate() {
+        outputs.push(OutputSummary {
+            pool: PoolType::Transparent,
+            index,
+            kind: OutputKind::from_output_info(output.proprietary().get(PROPRIETARY_OUTPUT_INFO)),
+            address: output
+                .user_address()
+                .clone()
+                .or_else(|| transparent_address(network, output.script_pubkey())),
+            value: Some(*output.value()),
+            memo: None,
+            missing_proof: false,
+        });
+    }
+}
+
+fn describe_sapling(
+    network: NetworkType,
+    bundle: &sapling::pczt::Bundle,
+    spends: &mut Vec<SpendSummary>,
+    outputs: &mut Vec<OutputSummary>,
+) -> anyhow::Result<()> {
+    for (index, spend) in bundle.spends().iter().enumerate() {
+        let value = spend.value().map(|v| v.inner());
+        if is_dummy(value, false) {
+            continue;
+        }
+        spends.push(SpendSummary {
+            pool: PoolType::Shielded(ShieldedProtocol::Sapling),
+            index,
+            value: value.map(zatoshis).transpose()?,
+            missing_signature: spend.spend_auth_sig().is_none(),
+            missing_proof: spend.zkproof().is_none(),
+        });
+    }
+
+    let domain = SaplingDomain::new(Zip212Enforcement::On);
+    for (index, output) in bundle.outputs().iter().enumerate() {
+        let value = output.value().map(|v| v.inner());
+        let output_info = output.proprietary().get(PROPRIETARY_OUTPUT_INFO);
+        if is_dummy(
+            value,
+            output_info.is_some() || output.user_address().is_some(),
+        ) {
+            continue;
+        }
+        outputs.push(OutputSummary {
+            pool: PoolType::Shielded(ShieldedProtocol::Sapling),
+            index,
+            kind: OutputKind::from_output_info(output_info),
+            address: output.user_address().clone().or_else(|| {
+                output.recipient().map(|recipient| {
+                    ZcashAddress::from_sapling(network, recipient.to_bytes()).encode()
+                })
+            }),
+            value: value.map(zatoshis).transpose()?,
+            memo: recover_memo(
+                &domain,
+                output.ock().as_ref(),
+                &output.ephemeral_key().0,
+                output.cmu().to_bytes(),
+                output.enc_ciphertext(),
+                output.out_ciphertext(),
+            ),
+            missing_proof: output.zkproof().is_none(),
+        });
+    }
+
+    Ok(())
+}
+
+fn describe_orchard(
+    network: NetworkType,
+    bundle: &orchard::pczt::Bundle,
+    spends: &mut Vec<SpendSummary>,
+    outputs: &mut Vec<OutputSummary>,
+) -> anyhow::Result<()> {
+    let missing_proof = bundle.zkproof().is_none();
+    for (index, action) in bundle.actions().iter().enumerate() {
+        let spend = action.spend();
+        let value = spend.value().map(|v| v.inner());
+        if !is_dummy(value, false) {
+            spends.push(SpendSummary {
+                pool: PoolType::Shielded(ShieldedProtocol::Orchard),
+                index,
+                value: value.map(zatoshis).transpose()?,
+                missing_signature: spend.spend_auth_sig().is_none(),
+                missing_proof,
+            });
+        }
+
+        let output = action.output();
+        let value = output.value().map(|v| v.inner());
+        let output_info = output.proprietary().get(PROPRIETARY_OUTPUT_INFO);
+        if is_dummy(
+            value,
+            output_info.is_some() || output.user_address().is_some(),
+        ) {
+            continue;
+        }
+        let encrypted_note = output.encrypted_note();
+        outputs.push(OutputSummary {
+            pool: PoolType::Shielded(ShieldedProtocol::Orchard),
+            index,
+            kind: OutputKind::from_output_info(output_info),
+            address: output.user_address().clone().or_else(|| {
+                output
+                    .recipient()
+                    .and_then(|r| orchard_address(network, r.to_raw_address_bytes()))
+            }),
+            value: value.map(zatoshis).transpose()?,
+            memo: recover_memo(
+                &OrchardDomain::for_pczt_action(action),
+                output.ock().as_ref(),
+                &encrypted_note.epk_bytes,
+                output.cmx().to_bytes(),
+                &encrypted_note.enc_ciphertext,
+                &encrypted_note.out_ciphertext,
+            ),
+            missing_proof,
+        });
+    }
+
+    Ok(())
+}
+
+/// Summarizes the effects of the given PCZT.
+pub(crate) fn describe(params: &impl Parameters, pczt: &Pczt) -> anyhow::Result<PcztSummary> {
+    let network = params.network_type();
+    let mut spends = vec![];
+    let mut outputs = vec![];
+
+    Verifier::new(pczt.clone())
+        .with_transparent::<anyhow::Error, _>(|bundle| {
+            describe_transparent(network, bundle, &mut spends, &mut outputs);
+            Ok(())
+        })
+        .map_err(|e| anyhow!("Invalid transparent bundle in PCZT: {:?}", e))?
+        .with_sapling(|bundle| {
+            describe_sapling(network, bundle, &mut spends, &mut outputs)
+                .map_err(SaplingError::Custom)
+        })
+        .map_err(|e| anyhow!("Invalid Sapling bundle in PCZT: {:?}", e))?
+        .with_orchard(|bundle| {
+            describe_orchard(network, bundle, &mut spends, &mut outputs)
+                .map_err(OrchardError::Custom)
+        })
+        .map_err(|e| anyhow!("Invalid Orchard bundle in PCZT: {:?}", e))?;
+
+    let fee = pczt_fee(pczt);
+    if fee.is_some_and(|fee| fee < 0) {
+        return Err(anyhow!("PCZT outputs exceed its inputs"));
+    }
+
+    Ok(PcztSummary {
+        spends,
+        outputs,
+        fee,
+        expiry_height: *pczt.global().expiry_height(),
+        consensus_branch_id: *pczt.global().consensus_branch_id(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pczt::roles::{
+        creator::Creator, redactor::Redactor, signer::Signer, updater::Updater, verifier::Verifier,
+    };
+    use std::convert::Infallible;
+
+    use rand::rngs::OsRng;
+    use sapling::note_encryption::prf_ock;
+    use transparent::{
+        bundle::{OutPoint, TxOut},
+        keys::{AccountPrivKey, NonHardenedChildIndex, TransparentKeyScope},
+    };
+    use zcash_client_backend::{
+        address::{Address, UnifiedAddress},
+        keys::UnifiedSpendingKey,
+    };
+    use zcash_primitives::transaction::{
+        builder::{BuildConfig, Builder},
+        fees::zip317,
+    };
+    use zcash_protocol::consensus::BlockHeight;
+    use zip32::Scope;
+
+    use super::*;
+    use crate::testing::NETWORK;
+
+    const TO: TransparentAddress = TransparentAddress::PublicKeyHash([7; 20]);
+
+    fn memo(text: &str) -> MemoBytes {
+        MemoBytes::from_bytes(text.as_bytes()).unwrap()
+    }
+
+    /// A PCZT spending a transparent input of the account derived from a seed of `1`s, and
+    /// paying 50000 zatoshis to `TO`, 40000 to a Sapling address and 30000 to an Orchard
+    /// address of the account derived from a seed of `2`s, with the rest paid as the fee.
+    struct TestPczt {
+        pczt: Pczt,
+        fee: i64,
+        sapling_address: sapling::PaymentAddress,
+        orchard_address: orchard::Address,
+        orchard_ovk: orchard::keys::OutgoingViewingKey,
+    }
+
+    /// Returns the Sapling bundle with the `ock` of each output recorded, which the
+    /// builder leaves unset.
+    fn with_ock(
+        bundle: sapling::pczt::Bundle,
+        ovk: &sapling::keys::OutgoingViewingKey,
+    ) -> sapling::pczt::Bundle {
+        assert!(bundle.spends().is_empty());
+        let outputs = bundle
+            .outputs()
+            .iter()
+            .map(|output| {
+                let ock = prf_ock(
+                    ovk,
+                    output.cv(),
+                    &output.cmu().to_bytes(),
+                    output.ephemeral_key(),
+                );
+                sapling::pczt::Output::parse(
+                    output.cv().to_bytes(),
+                    output.cmu().to_bytes(),
+                    output.ephemeral_key().0,
+                    output.enc_ciphertext().to_vec(),
+                    output.out_ciphertext().to_vec(),
+                    *output.zkproof(),
+                    output.recipient().map(|r| r.to_bytes()),
+                    output.value().map(|v| v.inner()),
+                    *output.rseed(),
+                    output.rcv().as_ref().map(|rcv| rcv.inner().to_bytes()),
+                    Some(ock.0),
+                    None,
+                    output.user_address().clone(),
+                    output.proprietary().clone(),
+                )
+                .unwrap()
+            })
+            .collect();
+        sapling::pczt::Bundle::parse(
+            vec![],
+            outputs,
+            i64::try_from(*bundle.value_sum()).unwrap().into(),
+            bundle.anchor().to_bytes(),
+            None,
+        )
+        .unwrap()
+    }
+
+    fn account_key() -> AccountPrivKey {
+        AccountPrivKey::from_seed(&NETWORK, &[1; 32], zip32::AccountId::ZERO).unwrap()
+    }
+
+    fn test_pczt() -> TestPczt {
+        let sender = UnifiedSpendingKey::from_seed(&NETWORK, &[1; 32], zip32::AccountId::ZERO)
+            .unwrap()
+            .to_unified_full_viewing_key();
+        let recipient = UnifiedSpendingKey::from_seed(&NETWORK, &[2; 32], zip32::AccountId::ZERO)
+            .unwrap()
+            .to_unified_full_viewing_key();
+        let sapling_ovk = sender.sapling().unwrap().to_ovk(Scope::External);
+        let orchard_ovk = sender.orchard().unwrap().to_ovk(Scope::External);
+        let sapling_address = recipient.sapling().unwrap().default_address().1;
+        let orchard_address = recipient
+            .orchard()
+            .unwrap()
+            .address_at(0u32, Scope::External);
+
+        let pubkey = account_key()
+            .to_account_pubkey()
+            .derive_address_pubkey(TransparentKeyScope::EXTERNAL, NonHardenedChildIndex::ZERO)
+            .unwrap();
+
+        let mut builder = Builder::new(
+            NETWORK,
+            BlockHeight::from_u32(3_000_000),
+            BuildConfig::Standard {
+                sapling_anchor: Some(sapling::Anchor::empty_tree()),
+                orchard_anchor: Some(orchard::Anchor::empty_tree()),
+            },
+        );
+        builder
+            .add_transparent_output(&TO, Zatoshis::const_from_u64(50_000))
+            .unwrap();
+        builder
+            .add_sapling_output::<zip317::FeeError>(
+                Some(sapling_ovk),
+                sapling_address,
+                Zatoshis::const_from_u64(40_000),
+                memo("to Sapling"),
+            )
+            .unwrap();
+        builder
+            .add_orchard_output::<zip317::FeeError>(
+                Some(orchard_ovk.clone()),
+                orchard_address,
+                30_000,
+                memo("to Orchard"),
+            )
+            .unwrap();
+        let fee = builder.get_fee(&zip317::FeeRule::standard()).unwrap();
+        builder
+            .add_transparent_input(
+                pubkey,
+                OutPoint::new([1; 32], 0),
+                TxOut::new(
+                    (Zatoshis::const_from_u64(120_000) + fee).unwrap(),
+                    TransparentAddress::from_pubkey(&pubkey).script().into(),
+                ),
+            )
+            .unwrap();
+
+        let mut parts = builder
+            .build_for_pczt(OsRng, &zip317::FeeRule::standard())
+            .unwrap()
+            .pczt_parts;
+        parts.sapling = parts.sapling.map(|bundle| with_ock(bundle, &sapling_ovk));
+
+        TestPczt {
+            pczt: Creator::build_from_parts(parts).unwrap(),
+            fee: i64::try_from(u64::from(fee)).unwrap(),
+            sapling_address,
+            orchard_address,
+            orchard_ovk,
+        }
+    }
+
+    fn output(summary: &PcztSummary, pool: PoolType) -> &OutputSummary {
+        let mut outputs = summary.outputs.iter().filter(|o| o.pool == pool);
+        let output = outputs.next().unwrap();
+        assert!(outputs.next().is_none());
+        output
+    }
+
+    const SAPLING: PoolType = PoolType::Shielded(ShieldedProtocol::Sapling);
+    const ORCHARD: PoolType = PoolType::Shielded(ShieldedProtocol::Orchard);
+
+    #[test]
+    fn fee_is_the_value_balance() {
+        let TestPczt { pczt, fee, .. } = test_pczt();
+        assert_eq!(pczt_fee(&pczt), Some(fee));
+
+        // The shielded value balances still determine the fee once values are redacted.
+        let redacted = Redactor::new(pczt)
+            .redact_sapling_with(|mut r| r.redact_outputs(|mut o| o.clear_value()))
+            .redact_orchard_with(|mut r| r.redact_actions(|mut a| a.clear_output_value()))
+            .finish();
+        assert_eq!(pczt_fee(&redacted), Some(fee));
+
+        let summary = describe(&NETWORK, &redacted).unwrap();
+        assert_eq!(summary.fee, Some(fee));
+        // Without their values, dummy outputs can no longer be told apart.
+        assert_eq!(summary.outputs.len(), 5);
+        assert!(
+            summary
+                .outputs
+                .iter()
+                .all(|o| o.pool == PoolType::Transparent || o.value.is_none())
+        );
+    }
+
+    #[test]
+    fn describes_inputs_and_outputs_without_dummies() {
+        let TestPczt {
+            pczt,
+            sapling_address,
+            orchard_address,
+            ..
+        } = test_pczt();
+
+        // The builder pads both shielded bundles with dummy spends and outputs.
+        Verifier::new(pczt.clone())
+            .with_sapling::<Infallible, _>(|bundle| {
+                assert_eq!(bundle.outputs().len(), 2);
+                Ok(())
+            })
+            .unwrap()
+            .with_orchard::<Infallible, _>(|bundle| {
+                assert_eq!(bundle.actions().len(), 2);
+                Ok(())
+            })
+            .unwrap();
+
+        let summary = describe(&NETWORK, &pczt).unwrap();
+        assert_eq!(summary.spends.len(), 1);
+        assert_eq!(summary.spends[0].pool, PoolType::Transparent);
+        assert_eq!(summary.outputs.len(), 3);
+
+        // Without a `user_address`, addresses are reconstructed from the raw recipients.
+        let transparent = output(&summary, PoolType::Transparent);
+        assert_eq!(
+            transparent.address,
+            Some(Address::from(TO).encode(&NETWORK))
+        );
+        assert_eq!(transparent.value, Some(Zatoshis::const_from_u64(50_000)));
+        assert_eq!(transparent.kind, OutputKind::Unknown);
+
+        let sapling = output(&summary, SAPLING);
+        assert_eq!(
+            sapling.address,
+            Some(Address::from(sapling_address).encode(&NETWORK))
+        );
+        assert_eq!(sapling.value, Some(Zatoshis::const_from_u64(40_000)));
+
+        let orchard = output(&summary, ORCHARD);
+        let orchard_ua = UnifiedAddress::from_receivers(Some(orchard_address), None, None).unwrap();
+        assert_eq!(
+            orchard.address,
+            Some(Address::from(orchard_ua).encode(&NETWORK))
+        );
+        assert_eq!(orchard.value, Some(Zatoshis::const_from_u64(30_000)));
+
+        // A recorded `user_address` and output info take precedence.
+        let (sapling_index, orchard_index) = (sapling.index, orchard.index);
+        let updated = Updater::new(pczt)
+            .update_sapling_with(|mut updater| {
+                updater.update_output_with(sapling_index, |mut output| {
+                    output.set_user_address("sapling user address".to_owned());
+                    output.set_proprietary(PROPRIETARY_OUTPUT_INFO.to_owned(), vec![2]);
+                    Ok(())
+                })
+            })
+            .unwrap()
+            .update_orchard_with(|mut updater| {
+                updater.update_action_with(orchard_index, |mut action| {
+                    action.set_output_user_address("orchard user address".to_owned());
+                    action.set_output_proprietary(PROPRIETARY_OUTPUT_INFO.to_owned(), vec![0]);
+                    Ok(())
+                })
+            })
+            .unwrap()
+            .finish();
+        let summary = describe(&NETWORK, &updated).unwrap();
+        let sapling = output(&summary, SAPLING);
+        assert_eq!(sapling.address.as_deref(), Some("sapling user address"));
+        assert_eq!(sapling.kind, OutputKind::Internal);
+        let orchard = output(&summary, ORCHARD);
+        assert_eq!(orchard.address.as_deref(), Some("orchard user address"));
+        assert_eq!(orchard.kind, OutputKind::External);
+    }
+
+    #[test]
+    fn recovers_memos_with_the_outgoing_cipher_key() {
+        let TestPczt {
+            pczt, orchard_ovk, ..
+        } = test_pczt();
+
+        let summary = describe(&NETWORK, &pczt).unwrap();
+        assert_eq!(output(&summary, SAPLING).memo, Some(memo("to Sapling")));
+        // The builder does not record the `ock` of Orchard outputs.
+        assert_eq!(output(&summary, ORCHARD).memo, None);
+
+        let mut orchard_memo = None;
+        Verifier::new(pczt.clone())
+            .with_orchard::<Infallible, _>(|bundle| {
+                let action = &bundle.actions()[output(&summary, ORCHARD).index];
+                let encrypted_note = action.output().encrypted_note();
+                let ock = OrchardDomain::derive_ock(
+                    &orchard_ovk,
+                    action.cv_net(),
+                    &action.output().cmx().to_bytes(),
+                    &EphemeralKeyBytes(encrypted_note.epk_bytes),
+                );
+                orchard_memo = recover_memo(
+                    &OrchardDomain::for_pczt_action(action),
+                    Some(&ock),
+                    &encrypted_note.epk_bytes,
+                    action.output().cmx().to_bytes(),
+                    &encrypted_note.enc_ciphertext,
+                    &encrypted_note.out_ciphertext,
+                );
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(orchard_memo, Some(memo("to Orchard")));
+
+        let redacted = Redactor::new(pczt)
+            .redact_sapling_with(|mut r| r.redact_outputs(|mut o| o.clear_ock()))
+            .finish();
+        let summary = describe(&NETWORK, &redacted).unwrap();
+        assert_eq!(output(&summary, SAPLING).memo, None);
+    }
+
+    #[test]
+    fn reports_missing_signatures_and_proofs() {
+        let TestPczt { pczt, .. } = test_pczt();
+
+        let summary = describe(&NETWORK, &pczt).unwrap();
+        assert!(summary.spends[0].missing_signature);
+        assert!(!summary.spends[0].missing_proof);
+        assert!(!output(&summary, PoolType::Transparent).missing_proof);
+        assert!(output(&summary, SAPLING).missing_proof);
+        assert!(output(&summary, ORCHARD).missing_proof);
+
+        let mut signer = Signer::new(pczt).unwrap();
+        let input_key = account_key()
+            .derive_external_secret_key(NonHardenedChildIndex::ZERO)
+            .unwrap();
+        signer.sign_transparent(0, &input_key).unwrap();
+        let summary = describe(&NETWORK, &signer.finish()).unwrap();
+        assert!(!summary.spends[0].missing_signature);
+    }
+}
@@ -0,0 +1,299 @@
+//! BC-UR encoding of PCZTs, for exchange with air-gapped signers via animated QR codes.
+//!
+//! A PCZT is sent as a `zcash-pczt` UR, whose CBOR payload is a map with the serialized
+//! PCZT under key 1. PCZTs that do not fit in a single QR code are split into fountain-
+//! coded fragments, which can be scanned in any order; once enough fragments have been
+//! received, the decoder reassembles the PCZT.
+
+use std::collections::BTreeSet;
+
+use anyhow::anyhow;
+
+/// The UR type of a PCZT.
+pub(crate) const UR_TYPE: &str = "zcash-pczt";
+
+/// The CBOR map key under which the serialized PCZT is stored.
+const PCZT_KEY: u8 = 1;
+
+/// Wraps a serialized PCZT in its CBOR payload.
+fn to_cbor(pczt: &[u8]) -> Vec<u8> {
+    // A map with a single entry, whose key is an unsigned integer.
+    let mut cbor = vec![0xa1, PCZT_KEY];
+
+    // The PCZT as a byte string (major type 2).
+    let len = pczt.len();
+    if len < 24 {
+        cbor.push(0x40 | len as u8);
+    } else if let Ok(len) = u8::try_from(len) {
+        cbor.extend_from_slice(&[0x58, len]);
+    } else if let Ok(len) = u16::try_from(len) {
+        cbor.push(0x59);
+        cbor.extend_from_slice(&len.to_be_bytes());
+    } else {
+        cbor.push(0x5a);
+        cbor.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+    cbor.extend_from_slice(pczt);
+    cbor
+}
+
+/// Extracts the serialized PCZT from its CBOR payload.
+fn from_cbor(cbor: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let invalid = || anyhow!("Invalid {} payload", UR_TYPE);
+
+    let (header, rest) = match cbor {
+        [0xa1, PCZT_KEY, header, rest @ ..] => (*header, rest),
+        _ => return Err(invalid()),
+    };
+    let (len, rest) = match header {
+        0x40..=0x57 => (usize::from(header & 0x1f), rest),
+        0x58 => match rest {
+            [len, rest @ ..] => (usize::from(*len), rest),
+            _ => return Err(invalid()),
+        },
+        0x59 => match rest {
+            [a, b, rest @ ..] => (usize::from(u16::from_be_bytes([*a, *b])), rest),
+            _ => return Err(invalid()),
+        },
+        0x5a => match rest {
+            [a, b, c, d, rest @ ..] => {
+                (usize::try_from(u32::from_be_bytes([*a, *b, *c, *d]))?, rest)
+            }
+            _ => return Err(invalid()),
+        },
+        _ => return Err(invalid()),
+    };
+
+    if rest.len() == len {
+        Ok(rest.to_vec())
+    } else {
+        Err(invalid())
+    }
+}
+
+/// Encodes a serialized PCZT as a sequence of UR parts.
+///
+/// If the PCZT fits within `max_fragment_length` bytes, this returns a single-part UR.
+/// Otherwise it returns the first `part_count` parts of the fountain-coded sequence, and
+/// at least enough parts to contain every fragment once.
+pub(crate) fn encode(
+    pczt: &[u8],
+    max_fragment_length: usize,
+    part_count: usize,
+) -> anyhow::Result<Vec<String>> {
+    let cbor = to_cbor(pczt);
+    let mut encoder = ur::Encoder::new(&cbor, max_fragment_length, UR_TYPE)
+        .map_err(|e| anyhow!("Failed to encode PCZT as UR: {:?}", e))?;
+
+    if encoder.fragment_count() == 1 {
+        return Ok(vec![ur::encode(&cbor, UR_TYPE)]);
+    }
+
+    (0..part_count.max(encoder.fragment_count()))
+        .map(|_| {
+            encoder
+                .next_part()
+                .map_err(|e| anyhow!("Failed to encode PCZT as UR: {:?}", e))
+        })
+        .collect()
+}
+
+/// The state of a [`PcztUrDecoder`].
+pub(crate) struct Progress {
+    /// The number of fragments recovered so far, which reaches `expected_parts` once the
+    /// PCZT has been reassembled.
+    pub(crate) received_parts: usize,
+    /// The number of fragments the PCZT was split into, once known.
+    pub(crate) expected_parts: Option<usize>,
+    /// The reassembled PCZT, once enough parts have been received.
+    pub(crate) pczt: Option<Vec<u8>>,
+}
+
+/// The fragments of a PCZT that the fountain decoder has recovered.
+///
+/// `ur::Decoder` does not expose how many fragments it has recovered, so we repeat its
+/// bookkeeping on the fragment indexes of each part: a part that mixes a single fragment
+/// not yet recovered recovers that fragment, which is then removed from every part that
+/// mixes it.
+#[derive(Default)]
+struct Fragments {
+    recovered: BTreeSet<usize>,
+    mixed: Vec<BTreeSet<usize>>,
+}
+
+impl Fragments {
+    fn receive(&mut self, indexes: Vec<usize>) {
+        let mut queue = vec![indexes.into_iter().collect::<BTreeSet<_>>()];
+        while let Some(mut indexes) = queue.pop() {
+            indexes.retain(|index| !self.recovered.contains(index));
+            match indexes.first() {
+                Some(&index) if indexes.len() == 1 => {
+                    self.recovered.insert(index);
+                    let (waiting, mixed) = std::mem::take(&mut self.mixed)
+                        .into_iter()
+                        .partition(|mixed| mixed.contains(&index));
+                    self.mixed = mixed;
+                    queue.extend(waiting);
+                }
+                Some(_) => self.mixed.push(indexes),
+                None => {}
+            }
+        }
+    }
+}
+
+/// Reassembles a PCZT from UR parts scanned in any order.
+#[derive(Default)]
+pub(crate) struct PcztUrDecoder {
+    decoder: ur::Decoder,
+    expected_parts: Option<usize>,
+    fragments: Fragments,
+    pczt: Option<Vec<u8>>,
+}
+
+impl PcztUrDecoder {
+    /// Returns the sequence length of a multi-part UR.
+    fn sequence_length(part: &str) -> Option<usize> {
+        let (_, seq_len) = part.split('/').nth(1)?.split_once('-')?;
+        seq_len.parse().ok()
+    }
+
+    /// Adds a scanned part to the decoder.
+    ///
+    /// Parts that were already received, or that mix only fragments that have already
+    /// been recovered, do not advance the progress.
+    pub(crate) fn receive(&mut self, part: &str) -> anyhow::Result<Progress> {
+        let part = part.trim().to_ascii_lowercase();
+        if !part.starts_with(&format!("ur:{}/", UR_TYPE)) {
+            return Err(anyhow!("Scanned code is not a {} UR", UR_TYPE));
+        }
+
+        if self.pczt.is_none() {
+            match Self::sequence_length(&part) {
+                None => {
+                    let (_, cbor) =
+                        ur::decode(&part).map_err(|e| anyhow!("Invalid UR: {:?}", e))?;
+                    self.expected_parts = Some(1);
+                    self.fragments.recovered.insert(0);
+                    self.pczt = Some(from_cbor(&cbor)?);
+                }
+                Some(seq_len) => {
+                    if self
+                        .expected_parts
+                        .is_some_and(|expected| expected != seq_len)
+                    {
+                        return Err(anyhow!("Scanned part belongs to a different PCZT"));
+                    }
+                    let (_, cbor) =
+                        ur::decode(&part).map_err(|e| anyhow!("Invalid UR part: {:?}", e))?;
+                    let fountain_part: ur::fountain::Part =
+                        minicbor::decode(&cbor).map_err(|e| anyhow!("Invalid UR part: {:?}", e))?;
+                    self.decoder
+                        .receive(&part)
+                        .map_err(|e| anyhow!("Invalid UR part: {:?}", e))?;
+                    self.fragments.receive(fountain_part.indexes());
+                    self.expected_parts = Some(seq_len);
+                    if self.decoder.complete() {
+                        let cbor = self
+                            .decoder
+                            .message()
+                            .map_err(|e| anyhow!("Failed to reassemble PCZT: {:?}", e))?
+                            .ok_or_else(|| anyhow!("Failed to reassemble PCZT"))?;
+                        self.pczt = Some(from_cbor(&cbor)?);
+                    }
+                }
+            }
+        }
+
+        Ok(Progress {
+            received_parts: self.fragments.recovered.len(),
+            expected_parts: self.expected_parts,
+            pczt: self.pczt.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cbor_round_trip() {
+        for len in [0, 23, 24, 255, 256, 65535, 65536] {
+            let pczt = vec![0x5a; len];
+            assert_eq!(from_cbor(&to_cbor(&pczt)).unwrap(), pczt);
+        }
+    }
+
+    #[test]
+    fn cbor_rejects_truncated_payload() {
+        let mut cbor = to_cbor(&[1; 100]);
+        cbor.pop();
+        assert!(from_cbor(&cbor).is_err());
+    }
+
+    #[test]
+    fn single_part_round_trip() {
+        let pczt = vec![7; 50];
+        let parts = encode(&pczt, 1000, 1).unwrap();
+        assert_eq!(parts.len(), 1);
+
+        let mut decoder = PcztUrDecoder::default();
+        let progress = decoder.receive(&parts[0]).unwrap();
+        assert_eq!(progress.received_parts, 1);
+        assert_eq!(progress.expected_parts, Some(1));
+        assert_eq!(progress.pczt, Some(pczt));
+    }
+
+    #[test]
+    fn multi_part_round_trip_out_of_order() {
+        let pczt = (0..2000).map(|i| i as u8).collect::<Vec<_>>();
+        let parts = encode(&pczt, 200, 0).unwrap();
+        assert!(parts.len() > 1);
+
+        let mut decoder = PcztUrDecoder::default();
+        let mut progress = None;
+        for part in parts.iter().rev() {
+            let p = decoder.receive(part).unwrap();
+            assert_eq!(p.expected_parts, Some(parts.len()));
+            assert_eq!(p.received_parts == parts.len(), p.pczt.is_some());
+            progress = Some(p);
+        }
+        assert_eq!(progress.unwrap().pczt, Some(pczt));
+    }
+
+    #[test]
+    fn progress_counts_recovered_fragments() {
+        let pczt = (0..2000).map(|i| i as u8).collect::<Vec<_>>();
+        let parts = encode(&pczt, 200, 40).unwrap();
+        let fragment_count = ur::Encoder::new(&to_cbor(&pczt), 200, UR_TYPE)
+            .unwrap()
+            .fragment_count();
+
+        // The first `fragment_count` parts each carry a single fragment.
+        let mut decoder = PcztUrDecoder::default();
+        for (i, part) in parts[..fragment_count - 1].iter().enumerate() {
+            assert_eq!(decoder.receive(part).unwrap().received_parts, i + 1);
+            // Receiving the same part again does not advance the progress.
+            assert_eq!(decoder.receive(part).unwrap().received_parts, i + 1);
+        }
+
+        // Later parts mix several fragments, and can recover the missing one.
+        let mut recovered = fragment_count - 1;
+        for part in &parts[fragment_count..] {
+            let progress = decoder.receive(part).unwrap();
+            assert!((recovered..=fragment_count).contains(&progress.received_parts));
+            recovered = progress.received_parts;
+            if progress.pczt.is_some() {
+                break;
+            }
+        }
+        assert_eq!(recovered, fragment_count);
+    }
+
+    #[test]
+    fn rejects_other_ur_types() {
+        let mut decoder = PcztUrDecoder::default();
+        assert!(decoder.receive(&ur::encode(&[0x40], "bytes")).is_err());
+    }
+}
@@ -0,0 +1,323 @@
+//! Verification of a finished PCZT against the PCZT it was created from.
+//!
+//! The Combiner only rejects fields that conflict between the PCZTs it is given. A field
+//! that was redacted before the PCZT was sent to an external signer can be set to any
+//! value by that signer, so before extracting a transaction we check that every field
+//! that affects the transaction is unchanged from the Creator's PCZT.
+
+use std::convert::Infallible;
+use std::fmt;
+
+use anyhow::anyhow;
+use pczt::{Pczt, roles::verifier::Verifier};
+
+use crate::{pczt_chain::pczt_txid, pczt_summary::pczt_fee};
+
+/// The fields of a finished PCZT that differ from the PCZT it was created from.
+#[derive(Default)]
+pub(crate) struct Differences(Vec<String>);
+
+impl Differences {
+    /// Records a difference in a field whose values are shown.
+    fn value<T: PartialEq + fmt::Debug>(
+        &mut self,
+        field: fmt::Arguments,
+        original: &T,
+        actual: &T,
+    ) {
+        if original != actual {
+            self.0.push(format!(
+                "{}: expected {:?}, got {:?}",
+                field, original, actual
+            ));
+        }
+    }
+
+    /// Records a difference in a field whose values are too long to show.
+    fn bytes<T: AsRef<[u8]> + ?Sized>(&mut self, field: fmt::Arguments, original: &T, actual: &T) {
+        if original.as_ref() != actual.as_ref() {
+            self.0.push(format!("{}: differs", field));
+        }
+    }
+
+    /// Records a difference in the number of elements of a list.
+    ///
+    /// Returns `true` if the lists have the same length, and so can be compared element
+    /// by element.
+    fn count(&mut self, field: &str, original: usize, actual: usize) -> bool {
+        self.value(format_args!("{} count", field), &original, &actual);
+        original == actual
+    }
+
+    pub(crate) fn into_vec(self) -> Vec<String> {
+        self.0
+    }
+
+    /// Returns an error describing every difference, if there are any.
+    pub(crate) fn into_result(self) -> anyhow::Result<()> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "PCZT does not match the PCZT it was created from: {}",
+                self.0.join("; ")
+            ))
+        }
+    }
+}
+
+/// Returns the lock time of the transaction that the given PCZT will produce, which is
+/// determined by the fallback lock time and the inputs' required lock times.
+fn lock_time(pczt: &Pczt) -> Option<u32> {
+    pczt.clone()
+        .into_effects()
+        .map(|tx_data| tx_data.lock_time())
+}
+
+/// Returns the sighash type of each transparent input of the given PCZT, or an empty list
+/// if its transparent bundle cannot be parsed.
+fn sighash_types(pczt: &Pczt) -> Vec<u8> {
+    let mut sighash_types = vec![];
+    // A PCZT whose bundle cannot be parsed also has no txid, which is reported below.
+    let _ = Verifier::new(pczt.clone()).with_transparent::<Infallible, _>(|bundle| {
+        sighash_types = bundle
+            .inputs()
+            .iter()
+            .map(|input| input.sighash_type().encode())
+            .collect();
+        Ok(())
+    });
+    sighash_types
+}
+
+/// Compares the transaction effects of `actual` against those of `original`.
+pub(crate) fn compare(original: &Pczt, actual: &Pczt) -> Differences {
+    let mut diffs = Differences::default();
+
+    let (og, ag) = (original.global(), actual.global());
+    diffs.value(format_args!("tx version"), og.tx_version(), ag.tx_version());
+    diffs.value(
+        format_args!("version group ID"),
+        og.version_group_id(),
+        ag.version_group_id(),
+    );
+    diffs.value(
+        format_args!("consensus branch ID"),
+        og.consensus_branch_id(),
+        ag.consensus_branch_id(),
+    );
+    diffs.value(
+        format_args!("lock time"),
+        &lock_time(original),
+        &lock_time(actual),
+    );
+    diffs.value(
+        format_args!("expiry height"),
+        og.expiry_height(),
+        ag.expiry_height(),
+    );
+
+    let (ot, at) = (original.transparent(), actual.transparent());
+    let (osighash, asighash) = (sighash_types(original), sighash_types(actual));
+    if diffs.count("transparent input", ot.inputs().len(), at.inputs().len()) {
+        for (i, (o, a)) in ot.inputs().iter().zip(at.inputs()).enumerate() {
+            diffs.bytes(
+                format_args!("transparent input {} prevout txid", i),
+                o.prevout_txid(),
+                a.prevout_txid(),
+            );
+            diffs.value(
+                format_args!("transparent input {} prevout index", i),
+                o.prevout_index(),
+                a.prevout_index(),
+            );
+            diffs.value(
+                format_args!("transparent input {} value", i),
+                o.value(),
+                a.value(),
+            );
+            diffs.bytes(
+                format_args!("transparent input {} script", i),
+                o.script_pubkey(),
+                a.script_pubkey(),
+            );
+            diffs.value(
+                format_args!("transparent input {} sighash type", i),
+                &osighash.get(i),
+                &asighash.get(i),
+            );
+        }
+    }
+    if diffs.count("transparent output", ot.outputs().len(), at.outputs().len()) {
+        for (i, (o, a)) in ot.outputs().iter().zip(at.outputs()).enumerate() {
+            diffs.value(
+                format_args!("transparent output {} value", i),
+                o.value(),
+                a.value(),
+            );
+            diffs.bytes(
+                format_args!("transparent output {} script", i),
+                o.script_pubkey(),
+                a.script_pubkey(),
+            );
+            diffs.value(
+                format_args!("transparent output {} recipient", i),
+                o.user_address(),
+                a.user_address(),
+            );
+        }
+    }
+
+    let (os, as_) = (original.sapling(), actual.sapling());
+    diffs.bytes(format_args!("Sapling anchor"), os.anchor(), as_.anchor());
+    if diffs.count("Sapling spend", os.spends().len(), as_.spends().len()) {
+        for (i, (o, a)) in os.spends().iter().zip(as_.spends()).enumerate() {
+            diffs.bytes(
+                format_args!("Sapling spend {} nullifier", i),
+                o.nullifier(),
+                a.nullifier(),
+            );
+            diffs.bytes(format_args!("Sapling spend {} rk", i), o.rk(), a.rk());
+            diffs.bytes(
+                format_args!("Sapling spend {} value commitment", i),
+                o.cv(),
+                a.cv(),
+            );
+        }
+    }
+    if diffs.count("Sapling output", os.outputs().len(), as_.outputs().len()) {
+        for (i, (o, a)) in os.outputs().iter().zip(as_.outputs()).enumerate() {
+            diffs.bytes(
+                format_args!("Sapling output {} note commitment", i),
+                o.cmu(),
+                a.cmu(),
+            );
+            diffs.bytes(
+                format_args!("Sapling output {} value commitment", i),
+                o.cv(),
+                a.cv(),
+            );
+            diffs.bytes(
+                format_args!("Sapling output {} ephemeral key", i),
+                o.ephemeral_key(),
+                a.ephemeral_key(),
+            );
+            diffs.bytes(
+                format_args!("Sapling output {} note ciphertext", i),
+                o.enc_ciphertext(),
+                a.enc_ciphertext(),
+            );
+            diffs.bytes(
+                format_args!("Sapling output {} outgoing ciphertext", i),
+                o.out_ciphertext(),
+                a.out_ciphertext(),
+            );
+            diffs.value(
+                format_args!("Sapling output {} recipient", i),
+                o.user_address(),
+                a.user_address(),
+            );
+        }
+    }
+
+    let (oo, ao) = (original.orchard(), actual.orchard());
+    diffs.bytes(format_args!("Orchard anchor"), oo.anchor(), ao.anchor());
+    diffs.value(format_args!("Orchard flags"), oo.flags(), ao.flags());
+    if diffs.count("Orchard action", oo.actions().len(), ao.actions().len()) {
+        for (i, (o, a)) in oo.actions().iter().zip(ao.actions()).enumerate() {
+            diffs.bytes(
+                format_args!("Orchard action {} nullifier", i),
+                o.spend().nullifier(),
+                a.spend().nullifier(),
+            );
+            diffs.bytes(
+                format_args!("Orchard action {} rk", i),
+                o.spend().rk(),
+                a.spend().rk(),
+            );
+            diffs.bytes(
+                format_args!("Orchard action {} value commitment", i),
+                o.cv_net(),
+                a.cv_net(),
+            );
+            diffs.bytes(
+                format_args!("Orchard action {} note commitment", i),
+                o.output().cmx(),
+                a.output().cmx(),
+            );
+            diffs.bytes(
+                format_args!("Orchard action {} ephemeral key", i),
+                o.output().ephemeral_key(),
+                a.output().ephemeral_key(),
+            );
+            diffs.bytes(
+                format_args!("Orchard action {} note ciphertext", i),
+                o.output().enc_ciphertext(),
+                a.output().enc_ciphertext(),
+            );
+            diffs.bytes(
+                format_args!("Orchard action {} outgoing ciphertext", i),
+                o.output().out_ciphertext(),
+                a.output().out_ciphertext(),
+            );
+            diffs.value(
+                format_args!("Orchard action {} recipient", i),
+                o.output().user_address(),
+                a.output().user_address(),
+            );
+        }
+    }
+
+    diffs.value(format_args!("fee"), &pczt_fee(original), &pczt_fee(actual));
+
+    // The txid commits to every effect of the transaction, so this catches anything not
+    // compared above.
+    if diffs.0.is_empty() {
+        match (pczt_txid(original), pczt_txid(actual)) {
+            (Ok(o), Ok(a)) => diffs.value(format_args!("txid"), &o, &a),
+            (Err(e), _) | (_, Err(e)) => diffs.0.push(format!("txid: {}", e)),
+        }
+    }
+
+    diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use transparent::address::TransparentAddress;
+
+    use super::*;
+    use crate::testing::transparent_pczt;
+
+    const TO: TransparentAddress = TransparentAddress::PublicKeyHash([7; 20]);
+
+    #[test]
+    fn unchanged_pczt_has_no_differences() {
+        let pczt = transparent_pczt(1, &TO, 50_000);
+        assert!(compare(&pczt, &pczt.clone()).into_vec().is_empty());
+        assert!(compare(&pczt, &pczt).into_result().is_ok());
+    }
+
+    #[test]
+    fn reports_changed_effects() {
+        let original = transparent_pczt(1, &TO, 50_000);
+
+        let redirected = transparent_pczt(1, &TransparentAddress::PublicKeyHash([8; 20]), 50_000);
+        assert_eq!(
+            compare(&original, &redirected).into_vec(),
+            ["transparent output 0 script: differs"]
+        );
+
+        assert_eq!(
+            compare(&original, &transparent_pczt(1, &TO, 40_000)).into_vec(),
+            [
+                "transparent input 0 value: expected 60000, got 50000",
+                "transparent output 0 value: expected 50000, got 40000",
+            ]
+        );
+
+        let diffs = compare(&original, &transparent_pczt(2, &TO, 50_000)).into_vec();
+        assert!(diffs.contains(&"transparent input 0 prevout txid: differs".to_owned()));
+        assert!(compare(&original, &redirected).into_result().is_err());
+    }
+}
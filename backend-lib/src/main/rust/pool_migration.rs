@@ -0,0 +1,658 @@
+//! Sapling-to-Orchard pool migration.
+//!
+//! The value moving between shielded pools is public, so moving a Sapling balance to
+//! Orchard in a single transaction reveals the whole amount. Like zcashd's
+//! Sprout-to-Sapling migration (ZIP 308), we instead plan a series of transactions, each
+//! moving a standard denomination, at randomized heights. The plan is kept in a table of
+//! our own in the wallet database.
+//!
+//! Each step pays its denomination to the account's Orchard receiver, and returns the
+//! rest of the Sapling notes it spends to the account's Sapling receiver, so that only
+//! the denomination and the fee cross pools.
+
+use anyhow::anyhow;
+use rand::{Rng, rngs::OsRng, seq::SliceRandom};
+use rusqlite::{Connection, named_params};
+use zcash_client_backend::{
+    address::Address,
+    data_api::{Account, WalletRead, wallet::ConfirmationsPolicy},
+    fees::{StandardFeeRule, sapling as sapling_fees},
+    keys::{ReceiverRequirement, UnifiedAddressRequest},
+    proposal::Proposal,
+    zip321::{Payment, TransactionRequest},
+};
+use zcash_client_sqlite::{AccountUuid, ReceivedNoteId};
+use zcash_primitives::transaction::{TxId, fees::zip317};
+use zcash_protocol::{
+    PoolType,
+    consensus::{BlockHeight, Network},
+    value::Zatoshis,
+};
+use zip32::DiversifierIndex;
+
+use crate::{
+    coin_control::{self, Db, OutputRef, SpendableInputs},
+    unmined::is_expired,
+};
+
+/// The smallest amount moved by a step, 0.001 ZEC.
+const MIN_DENOMINATION: u64 = 100_000;
+
+/// The fee set aside for each step when planning, which allows each step to spend a few
+/// notes. Any of it that is not needed remains in the Sapling pool.
+const STEP_FEE_RESERVE: u64 = 30_000;
+
+/// The state of a migration step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Status {
+    /// No transaction has been created for the step, or its transaction expired
+    /// without being mined.
+    Pending = 0,
+    /// The step's transaction has been created but not yet mined.
+    Submitted = 1,
+    Mined = 2,
+}
+
+/// A step of a migration plan.
+pub(crate) struct Step {
+    pub(crate) index: u32,
+    pub(crate) value: Zatoshis,
+    /// The height from which the step may be carried out.
+    pub(crate) due_height: BlockHeight,
+    pub(crate) txid: Option<TxId>,
+    pub(crate) status: Status,
+}
+
+/// Creates the table of migration steps. This is called by [`sdk_schema::init`].
+///
+/// [`sdk_schema::init`]: crate::sdk_schema::init
+pub(crate) fn init_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS sdk_pool_migration_steps (
+            account_uuid BLOB NOT NULL,
+            step_index INTEGER NOT NULL,
+            value INTEGER NOT NULL,
+            due_height INTEGER NOT NULL,
+            txid BLOB,
+            PRIMARY KEY (account_uuid, step_index)
+        )",
+    )
+}
+
+/// Returns the largest denomination, of the form 1, 2 or 5 times a power of ten
+/// zatoshis, that is no greater than `value`.
+fn largest_denomination(value: u64) -> Option<u64> {
+    let mut largest = None;
+    let mut power = MIN_DENOMINATION;
+    loop {
+        for d in [1, 2, 5] {
+            match power.checked_mul(d) {
+                Some(denomination) if denomination <= value => largest = Some(denomination),
+                _ => return largest,
+            }
+        }
+        power = power.checked_mul(10)?;
+    }
+}
+
+/// Splits `balance` into standard denominations, setting aside a fee for each.
+fn denominations(mut balance: u64) -> Vec<u64> {
+    let mut denominations = vec![];
+    while let Some(denomination) = balance
+        .checked_sub(STEP_FEE_RESERVE)
+        .and_then(largest_denomination)
+    {
+        denominations.push(denomination);
+        balance -= denomination + STEP_FEE_RESERVE;
+    }
+    denominations
+}
+
+/// Replaces any migration plan for the account with a new plan that moves its spendable
+/// Sapling balance to Orchard.
+///
+/// Steps become due at randomized intervals of between 1 and `2 * interval` blocks,
+/// starting from the chain tip, and move their denominations in a random order.
+///
+/// Returns an error if a step of the existing plan has a transaction that may still be
+/// mined, as the new plan would lose track of it.
+pub(crate) fn plan(
+    conn: &mut Connection,
+    db_data: &Db,
+    account_uuid: AccountUuid,
+    interval: u32,
+) -> anyhow::Result<Vec<Step>> {
+    let tip = db_data
+        .chain_height()?
+        .ok_or_else(|| anyhow!("Chain height not available; scan required."))?;
+
+    ensure_none_submitted(conn, account_uuid, crate::fully_scanned_height(db_data)?)?;
+
+    let inputs = SpendableInputs::fetch(db_data, account_uuid, ConfirmationsPolicy::default())?;
+    let balance = inputs
+        .sapling
+        .iter()
+        .map(|n| u64::from(sapling_fees::InputView::value(n)))
+        .sum::<u64>();
+
+    let mut denominations = denominations(balance);
+    if denominations.is_empty() {
+        return Err(anyhow!("The Sapling balance is too small to migrate"));
+    }
+    denominations.shuffle(&mut OsRng);
+
+    let mut due_height = tip;
+    let steps = denominations
+        .into_iter()
+        .zip(0..)
+        .map(|(value, index)| {
+            due_height = due_height + OsRng.gen_range(1..=interval.max(1) * 2);
+            Ok(Step {
+                index,
+                value: Zatoshis::from_u64(value)?,
+                due_height,
+                txid: None,
+                status: Status::Pending,
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let account = *account_uuid.expose_uuid().as_bytes();
+    let tx = conn.transaction()?;
+    tx.execute(
+        "DELETE FROM sdk_pool_migration_steps WHERE account_uuid = :account_uuid",
+        named_params! {":account_uuid": account},
+    )?;
+    for step in &steps {
+        tx.execute(
+            "INSERT INTO sdk_pool_migration_steps (account_uuid, step_index, value, due_height)
+             VALUES (:account_uuid, :step_index, :value, :due_height)",
+            named_params! {
+                ":account_uuid": account,
+                ":step_index": step.index,
+                ":value": u64::from(step.value),
+                ":due_height": u32::from(step.due_height),
+            },
+        )?;
+    }
+    tx.commit()?;
+
+    Ok(steps)
+}
+
+/// Returns an error if a step of the account's migration plan has a transaction that
+/// may still be mined.
+fn ensure_none_submitted(
+    conn: &Connection,
+    account_uuid: AccountUuid,
+    fully_scanned: Option<BlockHeight>,
+) -> anyhow::Result<()> {
+    match steps(conn, account_uuid, fully_scanned)?
+        .into_iter()
+        .find(|s| s.status == Status::Submitted)
+    {
+        Some(step) => Err(anyhow!(
+            "Migration step {} has a transaction that has not been mined or expired",
+            step.index
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Returns the steps of the account's migration plan, ordered by due height.
+///
+/// A step's transaction is only treated as expired once the wallet has scanned every
+/// block up to its expiry height; `fully_scanned` is the height up to which it has.
+pub(crate) fn steps(
+    conn: &Connection,
+    account_uuid: AccountUuid,
+    fully_scanned: Option<BlockHeight>,
+) -> anyhow::Result<Vec<Step>> {
+    let mut stmt = conn.prepare(
+        "SELECT s.step_index, s.value, s.due_height, s.txid, t.mined_height, t.expiry_height
+         FROM sdk_pool_migration_steps s
+         LEFT JOIN transactions t ON t.txid = s.txid
+         WHERE s.account_uuid = :account_uuid
+         ORDER BY s.due_height, s.step_index",
+    )?;
+
+    stmt.query_and_then(
+        named_params! {":account_uuid": account_uuid.expose_uuid().as_bytes()},
+        |row| -> anyhow::Result<_> {
+            let txid = row
+                .get::<_, Option<[u8; 32]>>("txid")?
+                .map(TxId::from_bytes);
+            let mined_height = row.get::<_, Option<u32>>("mined_height")?;
+            let expiry_height = row
+                .get::<_, Option<u32>>("expiry_height")?
+                .filter(|h| *h != 0)
+                .map(BlockHeight::from_u32);
+
+            let status = match (txid, mined_height) {
+                (Some(_), Some(_)) => Status::Mined,
+                (Some(_), None) if !is_expired(expiry_height, fully_scanned) => Status::Submitted,
+                _ => Status::Pending,
+            };
+
+            Ok(Step {
+                index: row.get("step_index")?,
+                value: Zatoshis::from_u64(row.get("value")?)?,
+                due_height: BlockHeight::from_u32(row.get("due_height")?),
+                txid,
+                status,
+            })
+        },
+    )?
+    .collect()
+}
+
+/// Returns the first pending step of the account's migration plan that is due, with a
+/// proposal that carries it out.
+pub(crate) fn propose_next_step(
+    conn: &Connection,
+    db_data: &Db,
+    params: &Network,
+    account_uuid: AccountUuid,
+) -> anyhow::Result<Option<(Step, Proposal<StandardFeeRule, ReceivedNoteId>)>> {
+    let tip = db_data.chain_height()?;
+    let Some(step) = steps(conn, account_uuid, crate::fully_scanned_height(db_data)?)?
+        .into_iter()
+        .find(|s| s.status == Status::Pending && tip.is_some_and(|tip| s.due_height <= tip))
+    else {
+        return Ok(None);
+    };
+
+    let proposal = propose_step(db_data, params, account_uuid, step.value)?;
+    Ok(Some((step, proposal)))
+}
+
+/// Returns the ZIP 317 fee for a step spending `spends` Sapling notes.
+fn step_fee(spends: usize) -> Option<Zatoshis> {
+    // The Sapling outputs are padded to two, as is the single Orchard action.
+    zip317::MARGINAL_FEE * (spends.max(2) + 2).max(zip317::GRACE_ACTIONS)
+}
+
+fn propose_step(
+    db_data: &Db,
+    params: &Network,
+    account_uuid: AccountUuid,
+    value: Zatoshis,
+) -> anyhow::Result<Proposal<StandardFeeRule, ReceivedNoteId>> {
+    let confirmations_policy = ConfirmationsPolicy::default();
+    let ufvk = db_data
+        .get_account(account_uuid)?
+        .ok_or_else(|| anyhow!("Unknown account"))?
+        .ufvk()
+        .cloned()
+        .ok_or_else(|| anyhow!("Account has no viewing key"))?;
+    let receiver = |orchard, sapling| -> anyhow::Result<_> {
+        let request = UnifiedAddressRequest::custom(orchard, sapling, ReceiverRequirement::Omit)
+            .map_err(|_| anyhow!("Invalid address request"))?;
+        let (ua, _) = ufvk
+            .find_address(DiversifierIndex::new(), request)
+            .map_err(|e| anyhow!("Error generating address: {}", e))?;
+        Ok(Address::Unified(ua).to_zcash_address(params))
+    };
+    let orchard_receiver = receiver(ReceiverRequirement::Require, ReceiverRequirement::Omit)?;
+    let sapling_receiver = receiver(ReceiverRequirement::Omit, ReceiverRequirement::Require)?;
+
+    // Spend the largest notes first, to keep the fee down.
+    let mut notes = SpendableInputs::fetch(db_data, account_uuid, confirmations_policy)?.sapling;
+    notes.sort_by_key(|n| std::cmp::Reverse(sapling_fees::InputView::value(n)));
+
+    let mut selected = vec![];
+    let mut total = Zatoshis::ZERO;
+    let mut remainder = None;
+    for note in notes {
+        let note_value = sapling_fees::InputView::value(&note);
+        if note_value <= zip317::MARGINAL_FEE {
+            break;
+        }
+        selected.push(OutputRef {
+            txid: *note.txid(),
+            pool: PoolType::SAPLING,
+            output_index: note.output_index().into(),
+        });
+        total = (total + note_value).ok_or_else(|| anyhow!("Balance overflow"))?;
+
+        let required = step_fee(selected.len())
+            .and_then(|fee| fee + value)
+            .ok_or_else(|| anyhow!("Fee overflow"))?;
+        if let Some(r) = total - required {
+            remainder = Some(r);
+            break;
+        }
+    }
+    let mut remainder = remainder.ok_or_else(|| {
+        anyhow!(
+            "The Sapling balance is insufficient for a migration step of {} zatoshis",
+            u64::from(value)
+        )
+    })?;
+
+    let (change_strategy, _) = crate::zip317_helper(None);
+    // Folding the change into the remainder does not alter the fee, so one retry suffices.
+    for _ in 0..2 {
+        let mut payments = vec![Payment::without_memo(orchard_receiver.clone(), value)];
+        if remainder > Zatoshis::ZERO {
+            payments.push(Payment::without_memo(sapling_receiver.clone(), remainder));
+        }
+        let request = TransactionRequest::new(payments)
+            .map_err(|e| anyhow!("Error creating transaction request: {:?}", e))?;
+
+        let proposal = coin_control::propose_transfer_from_inputs(
+            db_data,
+            params,
+            account_uuid,
+            &change_strategy,
+            request,
+            &selected,
+            confirmations_policy,
+        )?;
+
+        // Change would go to Orchard and reveal the value of the notes spent, so return
+        // any surplus to the Sapling receiver instead.
+        let change = proposal
+            .steps()
+            .head
+            .balance()
+            .proposed_change()
+            .iter()
+            .try_fold(Zatoshis::ZERO, |acc, c| acc + c.value())
+            .ok_or_else(|| anyhow!("Change overflow"))?;
+        if change == Zatoshis::ZERO {
+            return Ok(proposal);
+        }
+        remainder = (remainder + change).ok_or_else(|| anyhow!("Balance overflow"))?;
+    }
+    Err(anyhow!("Unable to propose a migration step without change"))
+}
+
+/// Records the transaction created for a step of the account's migration plan.
+///
+/// Returns an error unless the step is pending, as recording another transaction for a
+/// step that has been carried out would lose track of its transaction.
+pub(crate) fn record_step(
+    conn: &Connection,
+    account_uuid: AccountUuid,
+    index: u32,
+    txid: &TxId,
+    fully_scanned: Option<BlockHeight>,
+) -> anyhow::Result<()> {
+    let step = steps(conn, account_uuid, fully_scanned)?
+        .into_iter()
+        .find(|s| s.index == index)
+        .ok_or_else(|| anyhow!("Migration step {} not found", index))?;
+    match step.status {
+        Status::Pending => (),
+        Status::Submitted => {
+            return Err(anyhow!(
+                "Migration step {} has a transaction that has not been mined or expired",
+                index
+            ));
+        }
+        Status::Mined => {
+            return Err(anyhow!("Migration step {} has already been mined", index));
+        }
+    }
+
+    conn.execute(
+        "UPDATE sdk_pool_migration_steps SET txid = :txid
+         WHERE account_uuid = :account_uuid AND step_index = :step_index",
+        named_params! {
+            ":txid": txid.as_ref(),
+            ":account_uuid": account_uuid.expose_uuid().as_bytes(),
+            ":step_index": index,
+        },
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use zcash_client_backend::keys::UnifiedSpendingKey;
+    use zcash_client_sqlite::{WalletDb, util::SystemClock};
+    use zcash_protocol::ShieldedProtocol;
+
+    use super::*;
+    use crate::testing::{
+        NETWORK, add_account, insert_block, insert_tx, mark_scanned, txid, wallet_db,
+    };
+
+    fn insert_step(conn: &Connection, account_uuid: AccountUuid, index: u32, txid: Option<TxId>) {
+        conn.execute(
+            "INSERT INTO sdk_pool_migration_steps (account_uuid, step_index, value, due_height, txid)
+             VALUES (:account_uuid, :step_index, 100000, :due_height, :txid)",
+            named_params! {
+                ":account_uuid": account_uuid.expose_uuid().as_bytes(),
+                ":step_index": index,
+                ":due_height": 3_000_000 + index,
+                ":txid": txid.as_ref().map(|t| t.as_ref()),
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn step_status_follows_transaction() {
+        let mut conn = wallet_db();
+        let account = add_account(&mut conn, 1, 3_000_000);
+        insert_tx(&conn, 1, Some(3_000_050), 3_000_060);
+        insert_tx(&conn, 2, None, 3_000_090);
+        insert_tx(&conn, 3, None, 3_000_200);
+        insert_step(&conn, account, 0, Some(txid(1)));
+        insert_step(&conn, account, 1, Some(txid(2)));
+        insert_step(&conn, account, 2, Some(txid(3)));
+        insert_step(&conn, account, 3, None);
+
+        let statuses = steps(&conn, account, Some(BlockHeight::from_u32(3_000_100)))
+            .unwrap()
+            .into_iter()
+            .map(|s| s.status)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            [
+                Status::Mined,
+                Status::Pending,
+                Status::Submitted,
+                Status::Pending
+            ]
+        );
+    }
+
+    #[test]
+    fn submitted_step_prevents_replanning() {
+        let mut conn = wallet_db();
+        let account = add_account(&mut conn, 1, 3_000_000);
+        insert_tx(&conn, 1, None, 3_000_200);
+        insert_step(&conn, account, 0, None);
+        insert_step(&conn, account, 1, Some(txid(1)));
+
+        let err = ensure_none_submitted(&conn, account, Some(BlockHeight::from_u32(3_000_100)))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Migration step 1 has a transaction that has not been mined or expired"
+        );
+
+        // Once the transaction has expired, the step can be planned again.
+        assert!(
+            ensure_none_submitted(&conn, account, Some(BlockHeight::from_u32(3_000_200))).is_ok()
+        );
+    }
+
+    #[test]
+    fn only_pending_steps_can_be_recorded() {
+        let mut conn = wallet_db();
+        let account = add_account(&mut conn, 1, 3_000_000);
+        insert_tx(&conn, 1, Some(3_000_050), 3_000_060);
+        insert_tx(&conn, 2, None, 3_000_090);
+        insert_tx(&conn, 3, None, 3_000_200);
+        insert_step(&conn, account, 0, Some(txid(1)));
+        insert_step(&conn, account, 1, Some(txid(2)));
+        insert_step(&conn, account, 2, Some(txid(3)));
+        insert_step(&conn, account, 3, None);
+        let fully_scanned = Some(BlockHeight::from_u32(3_000_100));
+        let record = |index| record_step(&conn, account, index, &txid(4), fully_scanned);
+
+        assert_eq!(
+            record(0).unwrap_err().to_string(),
+            "Migration step 0 has already been mined"
+        );
+        assert_eq!(
+            record(2).unwrap_err().to_string(),
+            "Migration step 2 has a transaction that has not been mined or expired"
+        );
+        assert_eq!(
+            record(4).unwrap_err().to_string(),
+            "Migration step 4 not found"
+        );
+
+        // A step whose transaction expired can be carried out again.
+        record(1).unwrap();
+        record(3).unwrap();
+        let txids = steps(&conn, account, fully_scanned)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.txid)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            txids,
+            [Some(txid(1)), Some(txid(4)), Some(txid(3)), Some(txid(4))]
+        );
+    }
+
+    /// Returns a wallet, scanned up to height 3000100, with an account holding spendable
+    /// Sapling notes of the given values at its default address.
+    fn sapling_funded_db(values: &[u64]) -> (Db, AccountUuid) {
+        let mut conn = wallet_db();
+        let account = add_account(&mut conn, 1, 3_000_000);
+        mark_scanned(&conn, 3_000_000, 3_000_100);
+        insert_block(&conn, 3_000_050, 0);
+        conn.execute_batch(
+            "INSERT INTO sapling_tree_checkpoints (checkpoint_id) VALUES (3000080);
+             INSERT INTO sapling_tree_shards (shard_index, contains_marked) VALUES (0, 1);",
+        )
+        .unwrap();
+
+        let (_, address) =
+            UnifiedSpendingKey::from_seed(&NETWORK, &[1; 32], zip32::AccountId::ZERO)
+                .unwrap()
+                .to_unified_full_viewing_key()
+                .sapling()
+                .unwrap()
+                .default_address();
+        let tx = insert_tx(&conn, 1, Some(3_000_050), 0);
+        conn.execute(
+            "UPDATE transactions SET block = mined_height WHERE id_tx = :tx",
+            named_params! {":tx": tx},
+        )
+        .unwrap();
+        for (value, output_index) in values.iter().zip(0u8..) {
+            conn.execute(
+                "INSERT INTO sapling_received_notes
+                    (transaction_id, output_index, account_id, diversifier, value, rcm, nf,
+                     is_change, commitment_tree_position, recipient_key_scope)
+                 SELECT :tx, :output_index, id, :diversifier, :value, :rcm, :nf,
+                     0, :output_index, 0
+                 FROM accounts",
+                named_params! {
+                    ":tx": tx,
+                    ":output_index": output_index,
+                    ":diversifier": address.diversifier().0,
+                    ":value": value,
+                    ":rcm": [0u8; 32],
+                    ":nf": [output_index; 32],
+                },
+            )
+            .unwrap();
+        }
+
+        (
+            WalletDb::from_connection(conn, NETWORK, SystemClock, OsRng),
+            account,
+        )
+    }
+
+    #[test]
+    fn step_moves_exactly_its_denomination() {
+        let (db_data, account) = sapling_funded_db(&[250_000, 50_000, 300_000]);
+        let value = Zatoshis::const_from_u64(500_000);
+
+        let proposal = propose_step(&db_data, &NETWORK, account, value).unwrap();
+        let step = &proposal.steps().head;
+
+        // The two largest notes cover the denomination and the fee for spending two notes.
+        let mut spent = step
+            .shielded_inputs()
+            .unwrap()
+            .notes()
+            .iter()
+            .map(|n| u64::from(n.note().value()))
+            .collect::<Vec<_>>();
+        spent.sort();
+        assert_eq!(spent, [250_000, 300_000]);
+        assert_eq!(step.balance().fee_required(), step_fee(2).unwrap());
+
+        // Only the denomination goes to Orchard; the rest returns to Sapling, with no change.
+        let mut payments = step
+            .transaction_request()
+            .payments()
+            .iter()
+            .map(|(index, payment)| (step.payment_pools()[index], u64::from(payment.amount())))
+            .collect::<Vec<_>>();
+        payments.sort();
+        assert_eq!(
+            payments,
+            [
+                (PoolType::Shielded(ShieldedProtocol::Sapling), 30_000),
+                (PoolType::Shielded(ShieldedProtocol::Orchard), 500_000),
+            ]
+        );
+        // The change strategy may add a zero-valued change output, which takes the place of
+        // a dummy Orchard output.
+        assert!(
+            step.balance()
+                .proposed_change()
+                .iter()
+                .all(|c| c.value() == Zatoshis::ZERO)
+        );
+    }
+
+    #[test]
+    fn largest_denomination_uses_1_2_5_series() {
+        assert_eq!(largest_denomination(MIN_DENOMINATION - 1), None);
+        assert_eq!(largest_denomination(MIN_DENOMINATION), Some(100_000));
+        assert_eq!(largest_denomination(499_999), Some(200_000));
+        assert_eq!(largest_denomination(12_345_678_900), Some(10_000_000_000));
+        assert_eq!(
+            largest_denomination(u64::MAX),
+            Some(10_000_000_000_000_000_000)
+        );
+    }
+
+    #[test]
+    fn denominations_reserve_a_fee_per_step() {
+        let balance = 1_234_567_890;
+        let denominations = denominations(balance);
+        assert_eq!(
+            denominations,
+            [
+                1_000_000_000,
+                200_000_000,
+                20_000_000,
+                10_000_000,
+                2_000_000,
+                2_000_000,
+                200_000,
+                100_000
+            ]
+        );
+
+        let moved =
+            denominations.iter().sum::<u64>() + STEP_FEE_RESERVE * denominations.len() as u64;
+        assert!(moved <= balance);
+        assert!(balance - moved < MIN_DENOMINATION + STEP_FEE_RESERVE);
+    }
+}
@@ -0,0 +1,310 @@
+//! Proving support: process-wide caches of the proving and verifying keys, and progress
+//! reporting and cancellation while proofs are created.
+//!
+//! Building the Orchard proving key takes several seconds and a lot of memory on phones,
+//! and loading the Sapling parameters requires reading and checking ~50 MB from disk, so
+//! each is done at most once per process rather than for every transaction.
+
+use std::path::{Path, PathBuf};
+use std::sync::{
+    Arc, Mutex, OnceLock,
+    atomic::{AtomicBool, Ordering},
+    mpsc,
+};
+
+use anyhow::anyhow;
+use bellman::groth16;
+use bls12_381::{Bls12, G1Affine, G2Affine};
+use orchard::circuit::{ProvingKey, VerifyingKey};
+use rand::RngCore;
+use sapling::{
+    Diversifier, MerklePath, PaymentAddress, ProofGenerationKey, Rseed,
+    bundle::GrothProofBytes,
+    keys::EphemeralSecretKey,
+    prover::{OutputProver, SpendProver},
+    value::{NoteValue, ValueCommitTrapdoor},
+};
+use tracing::debug;
+use zcash_proofs::prover::LocalTxProver;
+
+static ORCHARD_PROVING_KEY: OnceLock<ProvingKey> = OnceLock::new();
+static ORCHARD_VERIFYING_KEY: OnceLock<VerifyingKey> = OnceLock::new();
+static SAPLING_PROVER: Mutex<Option<CachedProver>> = Mutex::new(None);
+
+struct CachedProver {
+    spend_path: PathBuf,
+    output_path: PathBuf,
+    prover: Arc<LocalTxProver>,
+}
+
+/// Returns the Orchard proving key, building it if necessary.
+pub(crate) fn orchard_proving_key() -> &'static ProvingKey {
+    ORCHARD_PROVING_KEY.get_or_init(|| {
+        let _span = tracing::info_span!("proving::build_orchard_proving_key").entered();
+        ProvingKey::build()
+    })
+}
+
+/// Returns the Orchard verifying key, building it if necessary.
+pub(crate) fn orchard_verifying_key() -> &'static VerifyingKey {
+    ORCHARD_VERIFYING_KEY.get_or_init(|| {
+        let _span = tracing::info_span!("proving::build_orchard_verifying_key").entered();
+        VerifyingKey::build()
+    })
+}
+
+/// Returns a prover using the Sapling parameters at the given paths, loading them if
+/// they have not already been loaded from those paths.
+///
+/// # Panics
+///
+/// Panics if the paths do not point to valid parameter files, as [`LocalTxProver::new`]
+/// does.
+pub(crate) fn sapling_prover(spend_path: &Path, output_path: &Path) -> Arc<LocalTxProver> {
+    // A panic while loading the parameters leaves the cache empty, so it is safe to
+    // continue using it.
+    let mut cached = SAPLING_PROVER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    match cached.as_ref() {
+        Some(c) if c.spend_path == spend_path && c.output_path == output_path => c.prover.clone(),
+        _ => {
+            let _span = tracing::info_span!("proving::load_sapling_parameters").entered();
+            let prover = Arc::new(LocalTxProver::new(spend_path, output_path));
+            *cached = Some(CachedProver {
+                spend_path: spend_path.to_path_buf(),
+                output_path: output_path.to_path_buf(),
+                prover: prover.clone(),
+            });
+            prover
+        }
+    }
+}
+
+/// Builds the Orchard keys on the Rayon thread pool, so that they are ready by the time
+/// the first transaction is created.
+pub(crate) fn warm_orchard_keys() {
+    rayon::spawn(|| {
+        orchard_verifying_key();
+        orchard_proving_key();
+        debug!("Orchard proving and verifying keys are ready");
+    });
+}
+
+/// The progress of creating the proofs for one or more transactions.
+///
+/// Each Sapling spend or output has its own proof, while a single proof covers every
+/// action of an Orchard bundle. The Orchard proof takes longer the more actions it
+/// covers, so it is counted as one proof per action.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Progress {
+    pub(crate) completed_proofs: u32,
+    pub(crate) total_proofs: u32,
+    /// The index of the transaction whose proofs are being created.
+    pub(crate) step: u32,
+    pub(crate) step_count: u32,
+}
+
+/// Tracks the proofs created for a sequence of transactions, and whether proving has
+/// been cancelled.
+pub(crate) struct ProvingTracker {
+    /// The expected number of proofs for each transaction, counted as in [`Progress`].
+    step_proofs: Vec<u32>,
+    completed_proofs: Mutex<u32>,
+    cancelled: AtomicBool,
+    updates: mpsc::Sender<Progress>,
+}
+
+impl ProvingTracker {
+    pub(crate) fn new(step_proofs: Vec<u32>, updates: mpsc::Sender<Progress>) -> Self {
+        ProvingTracker {
+            step_proofs,
+            completed_proofs: Mutex::new(0),
+            cancelled: AtomicBool::new(false),
+            updates,
+        }
+    }
+
+    pub(crate) fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Returns an error if proving has been cancelled.
+    ///
+    /// This must be checked after proving and before anything is stored, because the
+    /// proofs created by a [`TrackingProver`] after cancellation are not valid.
+    pub(crate) fn check_cancelled(&self) -> anyhow::Result<()> {
+        if self.is_cancelled() {
+            Err(anyhow!("Proving was cancelled"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Records that the given number of proofs have been created.
+    pub(crate) fn proofs_created(&self, count: u32) {
+        let mut completed = self
+            .completed_proofs
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *completed += count;
+
+        let step_count = u32::try_from(self.step_proofs.len()).expect("step count fits in u32");
+        let mut step = 0;
+        let mut step_end = 0;
+        for proofs in &self.step_proofs {
+            step_end += proofs;
+            if *completed < step_end || step + 1 == step_count {
+                break;
+            }
+            step += 1;
+        }
+
+        // The expected proof counts are estimates, so the total is never reported as less
+        // than the number of proofs created. The receiver may have gone away, which is fine.
+        let _ = self.updates.send(Progress {
+            completed_proofs: *completed,
+            total_proofs: self.step_proofs.iter().sum::<u32>().max(*completed),
+            step,
+            step_count,
+        });
+    }
+}
+
+/// A Sapling prover that reports each proof it creates to a [`ProvingTracker`].
+///
+/// The Sapling provers cannot fail, so once proving has been cancelled the remaining
+/// proofs are skipped and replaced with invalid placeholders, letting the caller's
+/// builder return quickly. The caller must then discard its result, as reported by
+/// [`ProvingTracker::check_cancelled`].
+pub(crate) struct TrackingProver<'a, P> {
+    inner: &'a P,
+    tracker: &'a ProvingTracker,
+}
+
+impl<'a, P> TrackingProver<'a, P> {
+    pub(crate) fn new(inner: &'a P, tracker: &'a ProvingTracker) -> Self {
+        TrackingProver { inner, tracker }
+    }
+
+    fn create_proof(
+        &self,
+        create: impl FnOnce() -> groth16::Proof<Bls12>,
+    ) -> groth16::Proof<Bls12> {
+        if self.tracker.is_cancelled() {
+            return groth16::Proof {
+                a: G1Affine::identity(),
+                b: G2Affine::identity(),
+                c: G1Affine::identity(),
+            };
+        }
+        let proof = create();
+        self.tracker.proofs_created(1);
+        proof
+    }
+}
+
+impl<P: SpendProver<Proof = groth16::Proof<Bls12>>> SpendProver for TrackingProver<'_, P> {
+    type Proof = P::Proof;
+
+    fn prepare_circuit(
+        proof_generation_key: ProofGenerationKey,
+        diversifier: Diversifier,
+        rseed: Rseed,
+        value: NoteValue,
+        alpha: jubjub::Fr,
+        rcv: ValueCommitTrapdoor,
+        anchor: bls12_381::Scalar,
+        merkle_path: MerklePath,
+    ) -> Option<sapling::circuit::Spend> {
+        P::prepare_circuit(
+            proof_generation_key,
+            diversifier,
+            rseed,
+            value,
+            alpha,
+            rcv,
+            anchor,
+            merkle_path,
+        )
+    }
+
+    fn create_proof<R: RngCore>(
+        &self,
+        circuit: sapling::circuit::Spend,
+        rng: &mut R,
+    ) -> Self::Proof {
+        self.create_proof(|| self.inner.create_proof(circuit, rng))
+    }
+
+    fn encode_proof(proof: Self::Proof) -> GrothProofBytes {
+        P::encode_proof(proof)
+    }
+}
+
+impl<P: OutputProver<Proof = groth16::Proof<Bls12>>> OutputProver for TrackingProver<'_, P> {
+    type Proof = P::Proof;
+
+    fn prepare_circuit(
+        esk: &EphemeralSecretKey,
+        payment_address: PaymentAddress,
+        rcm: jubjub::Fr,
+        value: NoteValue,
+        rcv: ValueCommitTrapdoor,
+    ) -> sapling::circuit::Output {
+        P::prepare_circuit(esk, payment_address, rcm, value, rcv)
+    }
+
+    fn create_proof<R: RngCore>(
+        &self,
+        circuit: sapling::circuit::Output,
+        rng: &mut R,
+    ) -> Self::Proof {
+        self.create_proof(|| self.inner.create_proof(circuit, rng))
+    }
+
+    fn encode_proof(proof: Self::Proof) -> GrothProofBytes {
+        P::encode_proof(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_progress_through_steps() {
+        let (updates, progress) = mpsc::channel();
+        let tracker = ProvingTracker::new(vec![2, 3], updates);
+
+        tracker.proofs_created(1);
+        tracker.proofs_created(1);
+        tracker.proofs_created(3);
+        // More proofs than expected are reported against the last step.
+        tracker.proofs_created(1);
+
+        let reported = progress
+            .try_iter()
+            .map(|p| (p.completed_proofs, p.total_proofs, p.step, p.step_count))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            reported,
+            [(1, 5, 0, 2), (2, 5, 1, 2), (5, 5, 1, 2), (6, 6, 1, 2)]
+        );
+    }
+
+    #[test]
+    fn cancellation_is_reported_as_an_error() {
+        let (updates, _progress) = mpsc::channel();
+        let tracker = ProvingTracker::new(vec![1], updates);
+        assert!(tracker.check_cancelled().is_ok());
+
+        tracker.cancel();
+        assert!(tracker.check_cancelled().is_err());
+    }
+}
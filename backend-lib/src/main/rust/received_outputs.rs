@@ -0,0 +1,417 @@
+//! Queries over the individual outputs that the wallet has received.
+//!
+//! `zcash_client_backend` only exposes received notes in aggregate (via the wallet
+//! summary) or as spendable inputs. This module reads the wallet database directly so
+//! that every received output can be listed, along with its spend status.
+
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use rusqlite::{Connection, named_params};
+use transparent::keys::TransparentKeyScope;
+use zcash_client_backend::{
+    data_api::{WalletRead, wallet::ConfirmationsPolicy},
+    encoding::AddressCodec,
+};
+use zcash_client_sqlite::AccountUuid;
+use zcash_primitives::transaction::TxId;
+use zcash_protocol::{
+    consensus::{BlockHeight, Network},
+    value::Zatoshis,
+};
+
+use crate::{
+    coin_control::{Db, OutputRef, SpendableInputs},
+    parse_pool,
+};
+
+/// The spend status of a received output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SpendStatus {
+    /// No transaction spends the output.
+    Unspent,
+    /// The output is spent by a transaction that has not been mined and has not expired.
+    PendingSpend,
+    /// The output is spent by a mined transaction.
+    Spent,
+}
+
+impl SpendStatus {
+    pub(crate) fn code(self) -> i32 {
+        match self {
+            SpendStatus::Unspent => 0,
+            SpendStatus::PendingSpend => 1,
+            SpendStatus::Spent => 2,
+        }
+    }
+}
+
+/// An output received by an account, in any pool.
+pub(crate) struct ReceivedOutput {
+    pub(crate) output: OutputRef,
+    pub(crate) value: Zatoshis,
+    pub(crate) mined_height: Option<BlockHeight>,
+    pub(crate) confirmations: u32,
+    pub(crate) spend_status: SpendStatus,
+    pub(crate) has_memo: bool,
+    pub(crate) is_change: bool,
+    pub(crate) is_spendable: bool,
+}
+
+/// Returns a page of the outputs received by the given account, most recent first.
+///
+/// Unmined outputs are listed before mined ones. `offset` and `limit` select the page.
+pub(crate) fn list_received_outputs(
+    conn: &Connection,
+    db_data: &Db,
+    account_uuid: AccountUuid,
+    offset: u32,
+    limit: u32,
+) -> anyhow::Result<Vec<ReceivedOutput>> {
+    let chain_tip = db_data.chain_height()?;
+    let spendable = match chain_tip {
+        Some(_) => Some(SpendableInputs::fetch(
+            db_data,
+            account_uuid,
+            ConfirmationsPolicy::default(),
+        )?),
+        None => None,
+    };
+
+    // The empty memo is stored by `zcash_client_sqlite` as the single byte 0xF6.
+    let mut stmt = conn.prepare(
+        "SELECT t.txid, ro.pool, ro.output_index, ro.value, t.mined_height, ro.is_change,
+                ro.memo IS NOT NULL AND ro.memo != X'F6' AS has_memo,
+                EXISTS (
+                    SELECT 1
+                    FROM v_received_output_spends s
+                    JOIN transactions st ON st.id_tx = s.transaction_id
+                    WHERE s.pool = ro.pool
+                    AND s.received_output_id = ro.id_within_pool_table
+                    AND st.mined_height IS NOT NULL
+                ) AS spent,
+                EXISTS (
+                    SELECT 1
+                    FROM v_received_output_spends s
+                    JOIN transactions st ON st.id_tx = s.transaction_id
+                    WHERE s.pool = ro.pool
+                    AND s.received_output_id = ro.id_within_pool_table
+                    AND st.mined_height IS NULL
+                    AND (
+                        :target_height IS NULL
+                        OR st.expiry_height IS NULL
+                        OR st.expiry_height = 0
+                        OR st.expiry_height >= :target_height
+                    )
+                ) AS pending_spend
+         FROM v_received_outputs ro
+         JOIN transactions t ON t.id_tx = ro.transaction_id
+         JOIN accounts a ON a.id = ro.account_id
+         WHERE a.uuid = :account_uuid
+         ORDER BY t.mined_height IS NOT NULL, t.mined_height DESC, t.id_tx DESC,
+                  ro.pool, ro.output_index
+         LIMIT :limit OFFSET :offset",
+    )?;
+
+    let rows = stmt.query_map(
+        named_params! {
+            ":account_uuid": account_uuid.expose_uuid().as_bytes(),
+            ":target_height": chain_tip.map(|h| u32::from(h + 1)),
+            ":limit": limit,
+            ":offset": offset,
+        },
+        |row| {
+            Ok((
+                row.get::<_, [u8; 32]>("txid")?,
+                row.get::<_, i32>("pool")?,
+                row.get::<_, u32>("output_index")?,
+                row.get::<_, i64>("value")?,
+                row.get::<_, Option<u32>>("mined_height")?,
+                row.get::<_, bool>("is_change")?,
+                row.get::<_, bool>("has_memo")?,
+                row.get::<_, bool>("spent")?,
+                row.get::<_, bool>("pending_spend")?,
+            ))
+        },
+    )?;
+
+    rows.map(|row| {
+        let (txid, pool, output_index, value, mined_height, is_change, has_memo, spent, pending) =
+            row?;
+
+        let pool = parse_pool(pool)?;
+        let output = OutputRef {
+            txid: TxId::from_bytes(txid),
+            pool,
+            output_index,
+        };
+        let mined_height = mined_height.map(BlockHeight::from_u32);
+        let confirmations = match (chain_tip, mined_height) {
+            (Some(tip), Some(h)) if tip >= h => u32::from(tip) - u32::from(h) + 1,
+            _ => 0,
+        };
+        let spend_status = if spent {
+            SpendStatus::Spent
+        } else if pending {
+            SpendStatus::PendingSpend
+        } else {
+            SpendStatus::Unspent
+        };
+
+        Ok(ReceivedOutput {
+            output,
+            value: Zatoshis::from_nonnegative_i64(value)
+                .map_err(|_| anyhow!("Invalid output value {}", value))?,
+            mined_height,
+            confirmations,
+            spend_status,
+            has_memo,
+            is_change,
+            is_spendable: spend_status == SpendStatus::Unspent
+                && spendable.as_ref().is_some_and(|s| s.contains(&output)),
+        })
+    })
+    .collect()
+}
+
+/// The funds held by one of an account's transparent receivers.
+pub(crate) struct TransparentReceiverBalance {
+    pub(crate) address: String,
+    pub(crate) scope: TransparentKeyScope,
+    pub(crate) spendable: Zatoshis,
+    /// Value that is not yet spendable because it is unconfirmed.
+    pub(crate) pending: Zatoshis,
+    /// Value that `get_transparent_balances` holds back as change pending confirmation.
+    pub(crate) immature: Zatoshis,
+    /// The number of unspent outputs held by the receiver.
+    pub(crate) utxo_count: u32,
+}
+
+/// Returns the number of unspent outputs held by each of the account's transparent
+/// receivers, keyed by encoded address.
+fn transparent_utxo_counts(
+    conn: &Connection,
+    account_uuid: AccountUuid,
+    target_height: BlockHeight,
+) -> anyhow::Result<HashMap<String, u32>> {
+    let mut stmt = conn.prepare(
+        "SELECT tro.address, COUNT(*) AS utxo_count
+         FROM transparent_received_outputs tro
+         JOIN transactions t ON t.id_tx = tro.transaction_id
+         JOIN accounts a ON a.id = tro.account_id
+         WHERE a.uuid = :account_uuid
+         AND (
+            t.mined_height IS NOT NULL
+            OR t.expiry_height IS NULL
+            OR t.expiry_height = 0
+            OR t.expiry_height >= :target_height
+         )
+         AND NOT EXISTS (
+            SELECT 1
+            FROM v_received_output_spends s
+            JOIN transactions st ON st.id_tx = s.transaction_id
+            WHERE s.pool = 0
+            AND s.received_output_id = tro.id
+            AND (
+                st.mined_height IS NOT NULL
+                OR st.expiry_height IS NULL
+                OR st.expiry_height = 0
+                OR st.expiry_height >= :target_height
+            )
+         )
+         GROUP BY tro.address",
+    )?;
+
+    stmt.query_and_then(
+        named_params! {
+            ":account_uuid": account_uuid.expose_uuid().as_bytes(),
+            ":target_height": u32::from(target_height),
+        },
+        |row| -> anyhow::Result<_> {
+            Ok((
+                row.get::<_, String>("address")?,
+                row.get::<_, u32>("utxo_count")?,
+            ))
+        },
+    )?
+    .collect()
+}
+
+/// Returns the funds held by each of the account's transparent receivers, including
+/// change and ephemeral receivers, ordered by address.
+pub(crate) fn transparent_receiver_balances(
+    conn: &Connection,
+    db_data: &Db,
+    network: &Network,
+    account_uuid: AccountUuid,
+) -> anyhow::Result<Vec<TransparentReceiverBalance>> {
+    let confirmations_policy = ConfirmationsPolicy::default();
+    let (target_height, _) = db_data
+        .get_target_and_anchor_heights(confirmations_policy.untrusted())
+        .map_err(|e| anyhow!("Error while fetching target height: {}", e))?
+        .ok_or_else(|| anyhow!("Target height not available; scan required."))?;
+
+    let utxo_counts = transparent_utxo_counts(conn, account_uuid, target_height.into())?;
+
+    let mut balances = db_data
+        .get_transparent_balances(account_uuid, target_height, confirmations_policy)
+        .map_err(|e| anyhow!("Error while fetching transparent balances: {}", e))?
+        .into_iter()
+        .map(|(address, (scope, balance))| {
+            let address = address.encode(network);
+            let utxo_count = utxo_counts.get(&address).copied().unwrap_or_default();
+            TransparentReceiverBalance {
+                address,
+                scope,
+                spendable: balance.spendable_value(),
+                pending: balance.value_pending_spendability(),
+                immature: balance.change_pending_confirmation(),
+                utxo_count,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    balances.sort_by(|a, b| a.address.cmp(&b.address));
+    Ok(balances)
+}
+
+#[cfg(test)]
+mod tests {
+    use zcash_client_backend::data_api::WalletWrite;
+    use zcash_protocol::PoolType;
+
+    use super::*;
+    use crate::testing::{
+        SharedWalletDb, add_account, insert_sapling_note, insert_tx, insert_utxo,
+        spend_sapling_note, spend_utxo, txid, wallet_db,
+    };
+
+    /// Returns the wallet's view of the database, with the chain tip at height 3_000_100.
+    fn scanned(db: &SharedWalletDb) -> Db {
+        db.conn
+            .execute(
+                "INSERT INTO sapling_tree_checkpoints (checkpoint_id) VALUES (3000080)",
+                [],
+            )
+            .unwrap();
+        let mut db_data = db.wallet();
+        db_data
+            .update_chain_tip(BlockHeight::from_u32(3_000_100))
+            .unwrap();
+        db_data
+    }
+
+    #[test]
+    fn lists_spend_status_and_memos() {
+        let mut db = SharedWalletDb::new();
+        let account = add_account(&mut db.conn, 1, 3_000_000);
+        let conn = &db.conn;
+
+        let received = insert_tx(conn, 1, Some(3_000_010), 0);
+        insert_sapling_note(conn, received, 0, account, 10_000, false, None);
+        let spent = insert_sapling_note(conn, received, 1, account, 20_000, false, Some(&[0xF6]));
+        let pending = insert_sapling_note(conn, received, 2, account, 30_000, true, Some(b"hi"));
+        let expired = insert_sapling_note(conn, received, 3, account, 40_000, false, None);
+        spend_sapling_note(conn, spent, insert_tx(conn, 2, Some(3_000_020), 0));
+        spend_sapling_note(conn, pending, insert_tx(conn, 3, None, 3_000_200));
+        spend_sapling_note(conn, expired, insert_tx(conn, 4, None, 3_000_050));
+        let db_data = scanned(&db);
+
+        let outputs = list_received_outputs(&db.conn, &db_data, account, 0, 10).unwrap();
+        assert_eq!(
+            outputs
+                .iter()
+                .map(|o| (
+                    o.output.output_index,
+                    o.spend_status,
+                    o.has_memo,
+                    o.is_change
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, SpendStatus::Unspent, false, false),
+                (1, SpendStatus::Spent, false, false),
+                (2, SpendStatus::PendingSpend, true, true),
+                (3, SpendStatus::Unspent, false, false),
+            ]
+        );
+        assert!(outputs.iter().all(|o| o.output.txid == txid(1)
+            && o.mined_height == Some(BlockHeight::from_u32(3_000_010))
+            && o.confirmations == 91));
+    }
+
+    #[test]
+    fn pages_unmined_outputs_first_then_most_recent() {
+        let mut db = SharedWalletDb::new();
+        let account = add_account(&mut db.conn, 1, 3_000_000);
+        let other = add_account(&mut db.conn, 2, 3_000_000);
+        let conn = &db.conn;
+
+        for (byte, mined_height) in [(1, Some(3_000_010)), (2, None), (3, Some(3_000_030))] {
+            let tx = insert_tx(conn, byte, mined_height, 0);
+            insert_sapling_note(conn, tx, 0, account, 10_000, false, None);
+            insert_utxo(conn, tx, 1, account, "tA", 10_000);
+            insert_sapling_note(conn, tx, 2, other, 10_000, false, None);
+        }
+        let db_data = scanned(&db);
+
+        let page = |offset, limit| {
+            list_received_outputs(&db.conn, &db_data, account, offset, limit)
+                .unwrap()
+                .into_iter()
+                .map(|o| (o.output.txid, o.output.pool, o.output.output_index))
+                .collect::<Vec<_>>()
+        };
+        let all = page(0, 10);
+        assert_eq!(
+            all,
+            vec![
+                (txid(2), PoolType::TRANSPARENT, 1),
+                (txid(2), PoolType::SAPLING, 0),
+                (txid(3), PoolType::TRANSPARENT, 1),
+                (txid(3), PoolType::SAPLING, 0),
+                (txid(1), PoolType::TRANSPARENT, 1),
+                (txid(1), PoolType::SAPLING, 0),
+            ]
+        );
+        assert_eq!(page(0, 4), all[..4]);
+        assert_eq!(page(4, 4), all[4..]);
+        assert!(page(6, 4).is_empty());
+    }
+
+    #[test]
+    fn counts_unspent_utxos_per_address() {
+        let mut conn = wallet_db();
+        let account = add_account(&mut conn, 1, 3_000_000);
+        let other = add_account(&mut conn, 2, 3_000_000);
+
+        let mined = insert_tx(&conn, 1, Some(3_000_010), 3_000_050);
+        insert_utxo(&conn, mined, 0, account, "tA", 50_000);
+        insert_utxo(&conn, mined, 1, account, "tA", 60_000);
+        let spent = insert_utxo(&conn, mined, 2, account, "tB", 70_000);
+        insert_utxo(&conn, mined, 3, other, "tC", 80_000);
+        spend_utxo(
+            &conn,
+            spent,
+            insert_tx(&conn, 2, Some(3_000_020), 3_000_060),
+        );
+
+        // An unmined output is counted until its transaction expires.
+        let unmined = insert_tx(&conn, 3, None, 3_000_100);
+        insert_utxo(&conn, unmined, 0, account, "tD", 90_000);
+        // An output spent by an expired transaction is unspent again.
+        let respendable = insert_utxo(&conn, mined, 4, account, "tE", 100_000);
+        spend_utxo(&conn, respendable, insert_tx(&conn, 4, None, 3_000_090));
+
+        let counts =
+            transparent_utxo_counts(&conn, account, BlockHeight::from_u32(3_000_100)).unwrap();
+        assert_eq!(
+            counts,
+            HashMap::from([("tA".into(), 2), ("tD".into(), 1), ("tE".into(), 1)])
+        );
+
+        let counts =
+            transparent_utxo_counts(&conn, account, BlockHeight::from_u32(3_000_101)).unwrap();
+        assert_eq!(counts, HashMap::from([("tA".into(), 2), ("tE".into(), 1)]));
+    }
+}
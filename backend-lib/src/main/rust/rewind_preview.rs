@@ -0,0 +1,241 @@
+//! Previews of the effect of rewinding the wallet.
+//!
+//! `WalletDb::truncate_to_height` rewinds to the most recent block at or below the
+//! requested height for which both note commitment trees have a checkpoint, marks the
+//! transactions mined above that height as unmined, and discards the scanned blocks
+//! above it. This module performs the same height selection read-only, and reports what
+//! the truncation would discard.
+
+use anyhow::anyhow;
+use rusqlite::{Connection, named_params};
+use uuid::Uuid;
+use zcash_client_sqlite::AccountUuid;
+use zcash_primitives::transaction::TxId;
+use zcash_protocol::{PoolType, consensus::BlockHeight, value::Zatoshis};
+
+use crate::parse_pool;
+
+/// An output that would become unmined, and so unspendable until it is rescanned.
+pub(crate) struct AffectedOutput {
+    pub(crate) account_uuid: AccountUuid,
+    pub(crate) txid: TxId,
+    pub(crate) pool: PoolType,
+    pub(crate) output_index: u32,
+    pub(crate) value: Zatoshis,
+    pub(crate) mined_height: BlockHeight,
+}
+
+/// The effect of rewinding the wallet to a requested height.
+pub(crate) struct RewindPreview {
+    pub(crate) requested_height: BlockHeight,
+    /// The height the wallet would be rewound to, or `None` if there is no checkpoint at
+    /// or below the requested height.
+    pub(crate) rewind_height: Option<BlockHeight>,
+    /// The lowest height the wallet can be rewound to, if `rewind_height` is `None` and
+    /// the wallet has any checkpoints.
+    pub(crate) safe_rewind_height: Option<BlockHeight>,
+    /// The transactions mined above the rewind height, which would become unmined.
+    pub(crate) unmined_txids: Vec<TxId>,
+    /// The unspent outputs mined above the rewind height.
+    pub(crate) affected_outputs: Vec<AffectedOutput>,
+    /// The number of scanned blocks above the rewind height, which would need rescanning.
+    pub(crate) blocks_to_rescan: u64,
+}
+
+/// Returns the height `truncate_to_height` would rewind to for the given requested height.
+fn rewind_height(conn: &Connection, requested_height: BlockHeight) -> anyhow::Result<Option<u32>> {
+    Ok(conn.query_row(
+        "SELECT MAX(b.height)
+         FROM blocks b
+         JOIN sapling_tree_checkpoints sc ON sc.checkpoint_id = b.height
+         JOIN orchard_tree_checkpoints oc ON oc.checkpoint_id = b.height
+         WHERE b.height <= :requested_height",
+        named_params! {":requested_height": u32::from(requested_height)},
+        |row| row.get(0),
+    )?)
+}
+
+/// Returns the lowest height `truncate_to_height` can rewind to.
+fn min_checkpoint_height(conn: &Connection) -> anyhow::Result<Option<u32>> {
+    Ok(conn.query_row(
+        "SELECT MIN(sc.checkpoint_id)
+         FROM sapling_tree_checkpoints sc
+         JOIN orchard_tree_checkpoints oc ON oc.checkpoint_id = sc.checkpoint_id",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+fn unmined_txids(conn: &Connection, rewind_height: BlockHeight) -> anyhow::Result<Vec<TxId>> {
+    let mut stmt = conn.prepare(
+        "SELECT txid
+         FROM transactions
+         WHERE mined_height > :rewind_height
+         ORDER BY mined_height, tx_index",
+    )?;
+
+    stmt.query_and_then(
+        named_params! {":rewind_height": u32::from(rewind_height)},
+        |row| -> anyhow::Result<_> { Ok(TxId::from_bytes(row.get("txid")?)) },
+    )?
+    .collect()
+}
+
+fn affected_outputs(
+    conn: &Connection,
+    rewind_height: BlockHeight,
+) -> anyhow::Result<Vec<AffectedOutput>> {
+    // Outputs spent by a mined transaction are already unspendable. A spend of an output
+    // mined above the rewind height is itself mined above it, so those spends are undone.
+    let mut stmt = conn.prepare(
+        "SELECT a.uuid AS account_uuid, t.txid, ro.pool, ro.output_index, ro.value,
+                t.mined_height
+         FROM v_received_outputs ro
+         JOIN transactions t ON t.id_tx = ro.transaction_id
+         JOIN accounts a ON a.id = ro.account_id
+         WHERE t.mined_height > :rewind_height
+         AND NOT EXISTS (
+            SELECT 1
+            FROM v_received_output_spends s
+            JOIN transactions st ON st.id_tx = s.transaction_id
+            WHERE s.pool = ro.pool
+            AND s.received_output_id = ro.id_within_pool_table
+            AND st.mined_height <= :rewind_height
+         )
+         ORDER BY t.mined_height, t.tx_index, ro.pool, ro.output_index",
+    )?;
+
+    stmt.query_and_then(
+        named_params! {":rewind_height": u32::from(rewind_height)},
+        |row| -> anyhow::Result<_> {
+            let value: i64 = row.get("value")?;
+            Ok(AffectedOutput {
+                account_uuid: AccountUuid::from_uuid(Uuid::from_bytes(row.get("account_uuid")?)),
+                txid: TxId::from_bytes(row.get("txid")?),
+                pool: parse_pool(row.get("pool")?)?,
+                output_index: row.get("output_index")?,
+                value: Zatoshis::from_nonnegative_i64(value)
+                    .map_err(|_| anyhow!("Invalid output value {}", value))?,
+                mined_height: BlockHeight::from_u32(row.get("mined_height")?),
+            })
+        },
+    )?
+    .collect()
+}
+
+fn blocks_above(conn: &Connection, rewind_height: BlockHeight) -> anyhow::Result<u64> {
+    Ok(conn.query_row(
+        "SELECT COUNT(*) FROM blocks WHERE height > :rewind_height",
+        named_params! {":rewind_height": u32::from(rewind_height)},
+        |row| row.get(0),
+    )?)
+}
+
+/// Reports the effect of rewinding the wallet to the given height, without modifying it.
+pub(crate) fn preview(
+    conn: &Connection,
+    requested_height: BlockHeight,
+) -> anyhow::Result<RewindPreview> {
+    match rewind_height(conn, requested_height)?.map(BlockHeight::from_u32) {
+        Some(height) => Ok(RewindPreview {
+            requested_height,
+            rewind_height: Some(height),
+            safe_rewind_height: None,
+            unmined_txids: unmined_txids(conn, height)?,
+            affected_outputs: affected_outputs(conn, height)?,
+            blocks_to_rescan: blocks_above(conn, height)?,
+        }),
+        None => Ok(RewindPreview {
+            requested_height,
+            rewind_height: None,
+            safe_rewind_height: min_checkpoint_height(conn)?.map(BlockHeight::from_u32),
+            unmined_txids: vec![],
+            affected_outputs: vec![],
+            blocks_to_rescan: 0,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        add_account, insert_block, insert_sapling_note, insert_tx, spend_sapling_note, txid,
+        wallet_db,
+    };
+
+    fn insert_checkpoint(conn: &Connection, height: u32) {
+        for table in ["sapling_tree_checkpoints", "orchard_tree_checkpoints"] {
+            conn.execute(
+                &format!("INSERT INTO {table} (checkpoint_id) VALUES (:height)"),
+                named_params! {":height": height},
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn previews_rewind_to_checkpoint() {
+        let mut conn = wallet_db();
+        let account = add_account(&mut conn, 1, 3_000_000);
+        for height in 3_000_010..=3_000_020 {
+            insert_block(&conn, height, 0);
+        }
+        insert_checkpoint(&conn, 3_000_010);
+        insert_checkpoint(&conn, 3_000_015);
+        // A checkpoint in only one tree cannot be rewound to.
+        conn.execute(
+            "INSERT INTO sapling_tree_checkpoints (checkpoint_id) VALUES (3000016)",
+            [],
+        )
+        .unwrap();
+
+        let below = insert_tx(&conn, 1, Some(3_000_012), 3_000_050);
+        let below_note = insert_sapling_note(&conn, below, 0, account, 10_000, false, None);
+        let above = insert_tx(&conn, 2, Some(3_000_016), 3_000_050);
+        insert_sapling_note(&conn, above, 0, account, 20_000, false, None);
+        let spent_note = insert_sapling_note(&conn, above, 1, account, 30_000, false, None);
+        let spend = insert_tx(&conn, 3, Some(3_000_018), 3_000_050);
+        spend_sapling_note(&conn, below_note, spend);
+        spend_sapling_note(&conn, spent_note, spend);
+
+        let preview = preview(&conn, BlockHeight::from_u32(3_000_017)).unwrap();
+        assert_eq!(
+            preview.rewind_height,
+            Some(BlockHeight::from_u32(3_000_015))
+        );
+        assert_eq!(preview.safe_rewind_height, None);
+        assert_eq!(preview.unmined_txids, [txid(2), txid(3)]);
+        assert_eq!(preview.blocks_to_rescan, 5);
+
+        // The note mined below the rewind height stays spent by the unmined spend, and
+        // the spend of the note mined above it is undone.
+        let affected = preview
+            .affected_outputs
+            .iter()
+            .map(|o| {
+                assert_eq!(o.account_uuid, account);
+                assert_eq!(o.pool, PoolType::SAPLING);
+                (o.txid, o.output_index, u64::from(o.value))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(affected, [(txid(2), 0, 20_000), (txid(2), 1, 30_000)]);
+    }
+
+    #[test]
+    fn reports_safe_height_below_first_checkpoint() {
+        let conn = wallet_db();
+        insert_block(&conn, 3_000_010, 0);
+        insert_block(&conn, 3_000_011, 0);
+        insert_checkpoint(&conn, 3_000_010);
+
+        let preview = preview(&conn, BlockHeight::from_u32(3_000_005)).unwrap();
+        assert_eq!(preview.rewind_height, None);
+        assert_eq!(
+            preview.safe_rewind_height,
+            Some(BlockHeight::from_u32(3_000_010))
+        );
+        assert!(preview.unmined_txids.is_empty());
+        assert_eq!(preview.blocks_to_rescan, 0);
+    }
+}
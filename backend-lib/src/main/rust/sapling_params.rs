@@ -0,0 +1,291 @@
+//! Download over Tor and verification of the Sapling proving parameters.
+//!
+//! Direct downloads are made by the SDK's `SaplingParamTool`; this module lets the same
+//! files be fetched through the Tor client, which is only available from Rust.
+//!
+//! Each file is downloaded to a `.part` file next to its final location, so that an
+//! interrupted download can be resumed with an HTTP range request. Once complete, the
+//! file is checked against the size and BLAKE2b-512 hash that [`zcash_proofs`] expects,
+//! and only then renamed into place, so the provers never see a partial or corrupt file.
+
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::anyhow;
+use http::{StatusCode, Uri, header};
+use http_body_util::BodyExt;
+use hyper::body::Incoming;
+use tor_rtcompat::ToplevelBlockOn;
+use tracing::{debug, info};
+use zcash_client_backend::tor::{
+    self,
+    http::{HttpError, Retry},
+};
+
+use crate::tor::TorRuntime;
+
+/// The location the SDK's `SaplingParamTool` downloads the parameters from.
+const DOWNLOAD_URL: &str = "https://download.z.cash/downloads/";
+
+/// The maximum number of times a failed download over Tor is retried. Each retry resumes
+/// from the end of the partially downloaded file.
+const TOR_RETRY_LIMIT: u8 = 3;
+
+/// A Sapling parameter file.
+///
+/// `zcash_proofs` checks the same sizes and hashes when it loads the files, but only
+/// exports their names.
+pub(crate) struct ParamFile {
+    pub(crate) name: &'static str,
+    size: u64,
+    blake2b: &'static str,
+}
+
+pub(crate) const SPEND: ParamFile = ParamFile {
+    name: zcash_proofs::SAPLING_SPEND_NAME,
+    size: 47958396,
+    blake2b: "8270785a1a0d0bc77196f000ee6d221c9c9894f55307bd9357c3f0105d31ca63991ab91324160d8f53e2bbd3c2633a6eb8bdf5205d822e7f3f73edac51b2b70c",
+};
+
+pub(crate) const OUTPUT: ParamFile = ParamFile {
+    name: zcash_proofs::SAPLING_OUTPUT_NAME,
+    size: 3592860,
+    blake2b: "657e3d38dbb5cb5e7dd2970e8b03d69b4787dd907285b5a7f0790dcc8072f60bf593b32cc2d1c030e00ff5ae64bf84c5c3beb84ddc841d48264b4a171744d028",
+};
+
+/// The state of a parameter file on disk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Status {
+    Valid = 0,
+    Missing = 1,
+    /// The file exists, but has the wrong size or hash.
+    Corrupt = 2,
+}
+
+/// Returns the parameter files paired with the paths they are stored at.
+pub(crate) fn files<'a>(
+    spend_path: &'a Path,
+    output_path: &'a Path,
+) -> [(&'static ParamFile, &'a Path); 2] {
+    [(&SPEND, spend_path), (&OUTPUT, output_path)]
+}
+
+/// Checks the parameter file at the given path.
+pub(crate) fn verify(file: &ParamFile, path: &Path) -> io::Result<Status> {
+    let f = match File::open(path) {
+        Ok(f) => f,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Status::Missing),
+        Err(e) => return Err(e),
+    };
+
+    if is_valid(file, f)? {
+        Ok(Status::Valid)
+    } else {
+        Ok(Status::Corrupt)
+    }
+}
+
+fn is_valid(file: &ParamFile, mut f: File) -> io::Result<bool> {
+    if f.metadata()?.len() != file.size {
+        return Ok(false);
+    }
+
+    let mut state = blake2b_simd::State::new();
+    let mut buf = vec![0; 1 << 16];
+    loop {
+        match f.read(&mut buf)? {
+            0 => break,
+            n => state.update(&buf[..n]),
+        };
+    }
+    Ok(state.finalize().to_hex().as_str() == file.blake2b)
+}
+
+/// Downloads the parameter file to the given path over Tor, unless a valid copy is
+/// already there.
+///
+/// If a previous download was interrupted, this resumes it.
+pub(crate) fn download(
+    file: &ParamFile,
+    path: &Path,
+    tor_runtime: &TorRuntime,
+) -> anyhow::Result<()> {
+    if verify(file, path)? == Status::Valid {
+        return Ok(());
+    }
+
+    let part = part_path(path);
+    if part_len(&part, file.size)? < file.size {
+        let url = format!("{}{}", DOWNLOAD_URL, file.name)
+            .parse::<Uri>()
+            .map_err(|e| anyhow!("Invalid URL: {e}"))?;
+        let _span = tracing::info_span!("sapling_params::download", file = file.name).entered();
+        download_tor(tor_runtime, url, &part, file.size)?;
+    }
+
+    // Whatever went wrong, a file that fails verification can't be resumed from.
+    if !is_valid(file, File::open(&part)?)? {
+        fs::remove_file(&part)?;
+        return Err(anyhow!(
+            "Downloaded {} does not match its expected hash",
+            file.name
+        ));
+    }
+
+    fs::rename(&part, path)?;
+    info!("Downloaded {}", file.name);
+    Ok(())
+}
+
+fn part_path(path: &Path) -> PathBuf {
+    let mut part = OsString::from(path.as_os_str());
+    part.push(".part");
+    part.into()
+}
+
+/// Returns the length of the partially downloaded file, discarding it if it is too long
+/// to be part of a file of the given size.
+fn part_len(part: &Path, size: u64) -> io::Result<u64> {
+    match fs::metadata(part) {
+        Ok(m) if m.len() > size => {
+            fs::remove_file(part)?;
+            Ok(0)
+        }
+        Ok(m) => Ok(m.len()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(0),
+        Err(e) => Err(e),
+    }
+}
+
+fn with_range(builder: http::request::Builder, start: u64) -> http::request::Builder {
+    if start > 0 {
+        builder.header(header::RANGE, format!("bytes={}-", start))
+    } else {
+        builder
+    }
+}
+
+/// Appends a response body to the partially downloaded file.
+///
+/// Write errors are returned inside the result, so that they are not mistaken for
+/// network errors and retried.
+async fn append_body(mut body: Incoming, part: &Path) -> Result<io::Result<()>, HttpError> {
+    let mut f = match OpenOptions::new().create(true).append(true).open(part) {
+        Ok(f) => f,
+        Err(e) => return Ok(Err(e)),
+    };
+
+    while let Some(frame) = body.frame().await {
+        if let Ok(data) = frame?.into_data()
+            && let Err(e) = f.write_all(&data)
+        {
+            return Ok(Err(e));
+        }
+    }
+
+    Ok(f.sync_all())
+}
+
+/// Checks the status of a response whose body was appended to the file at `part`, which
+/// was `start` bytes long beforehand.
+fn finish_response(status: StatusCode, start: u64, part: &Path) -> anyhow::Result<()> {
+    match status {
+        StatusCode::PARTIAL_CONTENT => Ok(()),
+        StatusCode::OK if start == 0 => Ok(()),
+        StatusCode::OK => {
+            // The server ignored the range and sent the whole file.
+            debug!("Server does not support resuming downloads");
+            let tmp = {
+                let mut tmp = OsString::from(part.as_os_str());
+                tmp.push(".tmp");
+                PathBuf::from(tmp)
+            };
+            let mut src = File::open(part)?;
+            src.seek(SeekFrom::Start(start))?;
+            let mut dst = File::create(&tmp)?;
+            io::copy(&mut src, &mut dst)?;
+            dst.sync_all()?;
+            fs::rename(&tmp, part)?;
+            Ok(())
+        }
+        _ => {
+            // Drop the error page that was appended.
+            OpenOptions::new().write(true).open(part)?.set_len(start)?;
+            Err(anyhow!("Failed to download Sapling parameters: {}", status))
+        }
+    }
+}
+
+fn download_tor(tor_runtime: &TorRuntime, url: Uri, part: &Path, size: u64) -> anyhow::Result<()> {
+    // Each retry resumes from wherever the previous attempt stopped.
+    let start = AtomicU64::new(0);
+
+    let response = tor_runtime.runtime().block_on(async {
+        tor_runtime
+            .client()
+            .http_get(
+                url,
+                |builder| {
+                    let len = part_len(part, size).unwrap_or(0);
+                    start.store(len, Ordering::Relaxed);
+                    with_range(builder, len)
+                },
+                |body| async move { append_body(body, part).await.map_err(tor::Error::from) },
+                TOR_RETRY_LIMIT,
+                |res| res.is_err().then_some(Retry::Same),
+            )
+            .await
+    })?;
+
+    let (parts, body) = response.into_parts();
+    body?;
+    finish_response(parts.status, start.load(Ordering::Relaxed), part)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("sapling_params_{}_{}", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn verify_reports_missing_and_corrupt_files() {
+        let path = temp_path("verify");
+        assert_eq!(verify(&OUTPUT, &path).unwrap(), Status::Missing);
+
+        fs::write(&path, [0; 100]).unwrap();
+        assert_eq!(verify(&OUTPUT, &path).unwrap(), Status::Corrupt);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn full_response_to_range_request_replaces_partial_file() {
+        let part = temp_path("part");
+        fs::write(&part, b"abcabcdef").unwrap();
+
+        finish_response(StatusCode::OK, 3, &part).unwrap();
+        assert_eq!(fs::read(&part).unwrap(), b"abcdef");
+
+        fs::remove_file(&part).unwrap();
+    }
+
+    #[test]
+    fn error_response_is_not_kept() {
+        let part = temp_path("error");
+        fs::write(&part, b"abcNot Found").unwrap();
+
+        assert!(finish_response(StatusCode::NOT_FOUND, 3, &part).is_err());
+        assert_eq!(fs::read(&part).unwrap(), b"abc");
+
+        fs::remove_file(&part).unwrap();
+    }
+}
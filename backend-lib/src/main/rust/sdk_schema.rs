@@ -0,0 +1,16 @@
+//! Tables that this crate maintains in the wallet database, alongside those whose schema
+//! `zcash_client_sqlite` manages with its migrations.
+//!
+//! The tables are created once the wallet's own migrations have been applied, by
+//! `initDataDb` and when a backup is restored, so that the functions that use them can
+//! read them through a read-only connection.
+
+use rusqlite::Connection;
+
+use crate::{pool_migration, unmined};
+
+/// Creates any of this crate's tables that do not yet exist.
+pub(crate) fn init(conn: &Connection) -> rusqlite::Result<()> {
+    unmined::init_table(conn)?;
+    pool_migration::init_table(conn)
+}
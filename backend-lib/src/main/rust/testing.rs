@@ -0,0 +1,333 @@
+//! Helpers for testing this crate's queries against an initialized wallet database.
+//!
+//! Rows are inserted directly, with only the columns that the queries under test read,
+//! so that tests do not need to scan blocks or construct real notes.
+
+use pczt::{Pczt, roles::creator::Creator};
+use rand::{RngCore, rngs::OsRng};
+use rusqlite::{Connection, named_params};
+use transparent::{
+    address::{Script, TransparentAddress},
+    bundle::{OutPoint, TxOut},
+    keys::{AccountPrivKey, NonHardenedChildIndex, TransparentKeyScope},
+};
+use zcash_client_backend::{
+    data_api::{Account, AccountBirthday, AccountPurpose, WalletWrite, chain::ChainState},
+    encoding::AddressCodec,
+    keys::UnifiedSpendingKey,
+};
+use zcash_client_sqlite::{AccountUuid, WalletDb, util::SystemClock, wallet::init::init_wallet_db};
+use zcash_primitives::{
+    block::BlockHash,
+    transaction::{
+        TxId,
+        builder::{BuildConfig, Builder},
+        fees::zip317,
+    },
+};
+use zcash_protocol::{
+    consensus::{BlockHeight, Network},
+    value::Zatoshis,
+};
+
+use crate::{coin_control::Db, sdk_schema};
+
+pub(crate) const NETWORK: Network = Network::TestNetwork;
+
+fn init(mut conn: Connection) -> Connection {
+    rusqlite::vtab::array::load_module(&conn).unwrap();
+    init_wallet_db(
+        &mut WalletDb::from_connection(&mut conn, NETWORK, SystemClock, OsRng),
+        None,
+    )
+    .unwrap();
+    sdk_schema::init(&conn).unwrap();
+    conn
+}
+
+/// Returns an in-memory wallet database with this crate's tables.
+pub(crate) fn wallet_db() -> Connection {
+    init(Connection::open_in_memory().unwrap())
+}
+
+/// An in-memory wallet database with this crate's tables that, like the SDK's database
+/// file, can be opened by several connections at once. It lives as long as `conn`.
+pub(crate) struct SharedWalletDb {
+    uri: String,
+    pub(crate) conn: Connection,
+}
+
+impl SharedWalletDb {
+    pub(crate) fn new() -> Self {
+        let uri = format!(
+            "file:wallet-{:016x}?mode=memory&cache=shared",
+            OsRng.next_u64()
+        );
+        let conn = init(Connection::open(&uri).unwrap());
+        SharedWalletDb { uri, conn }
+    }
+
+    /// Opens another connection to the database, for `zcash_client_sqlite`.
+    pub(crate) fn wallet(&self) -> Db {
+        let conn = Connection::open(&self.uri).unwrap();
+        rusqlite::vtab::array::load_module(&conn).unwrap();
+        WalletDb::from_connection(conn, NETWORK, SystemClock, OsRng)
+    }
+}
+
+/// Adds an account derived from a seed of repeated `seed_byte`s, with its birthday at
+/// `birthday`.
+pub(crate) fn add_account(conn: &mut Connection, seed_byte: u8, birthday: u32) -> AccountUuid {
+    let usk =
+        UnifiedSpendingKey::from_seed(&NETWORK, &[seed_byte; 32], zip32::AccountId::ZERO).unwrap();
+    let birthday = AccountBirthday::from_parts(
+        ChainState::empty(BlockHeight::from_u32(birthday - 1), BlockHash([0; 32])),
+        None,
+    );
+    WalletDb::from_connection(conn, NETWORK, SystemClock, OsRng)
+        .import_account_ufvk(
+            &format!("account {}", seed_byte),
+            &usk.to_unified_full_viewing_key(),
+            &birthday,
+            AccountPurpose::ViewOnly,
+            None,
+        )
+        .unwrap()
+        .id()
+}
+
+pub(crate) fn txid(byte: u8) -> TxId {
+    TxId::from_bytes([byte; 32])
+}
+
+/// Adds a block at the given height and time.
+pub(crate) fn insert_block(conn: &Connection, height: u32, time: i64) {
+    conn.execute(
+        "INSERT INTO blocks (height, hash, time, sapling_tree)
+         VALUES (:height, :hash, :time, x'00')",
+        named_params! {
+            ":height": height,
+            ":hash": height.to_le_bytes(),
+            ":time": time,
+        },
+    )
+    .unwrap();
+}
+
+/// Records that every block from `from` up to and including `to` has been scanned, as
+/// the first range of the scan queue, and adds the block at `to`. Later ranges, such as
+/// the one ending at the chain tip, are kept.
+pub(crate) fn mark_scanned(conn: &Connection, from: u32, to: u32) {
+    let mut hash = [0; 32];
+    hash[..4].copy_from_slice(&to.to_le_bytes());
+    conn.execute(
+        "DELETE FROM scan_queue WHERE block_range_end <= :end",
+        named_params! {":end": to + 1},
+    )
+    .unwrap();
+    conn.execute(
+        "UPDATE scan_queue SET block_range_start = :end WHERE block_range_start < :end",
+        named_params! {":end": to + 1},
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO scan_queue (block_range_start, block_range_end, priority)
+         VALUES (:from, :end, 10)",
+        named_params! {":from": from, ":end": to + 1},
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO blocks
+            (height, hash, time, sapling_tree, sapling_commitment_tree_size,
+             orchard_commitment_tree_size)
+         VALUES (:height, :hash, 0, x'00', 0, 0)",
+        named_params! {":height": to, ":hash": hash},
+    )
+    .unwrap();
+}
+
+/// Adds a transaction created by the wallet, with the raw transaction `[0; 4]`. The
+/// transaction with ID `txid(n)` was created `n` seconds after the first.
+///
+/// Returns the transaction's row ID.
+pub(crate) fn insert_tx(
+    conn: &Connection,
+    txid_byte: u8,
+    mined_height: Option<u32>,
+    expiry_height: u32,
+) -> i64 {
+    conn.execute(
+        "INSERT INTO transactions (txid, created, mined_height, expiry_height, raw, min_observed_height)
+         VALUES (:txid, :created, :mined_height, :expiry_height, :raw, 1)",
+        named_params! {
+            ":txid": txid(txid_byte).as_ref(),
+            ":created": format!("2024-01-01 00:00:{:02}", txid_byte),
+            ":mined_height": mined_height,
+            ":expiry_height": expiry_height,
+            ":raw": [0u8; 4],
+        },
+    )
+    .unwrap();
+    conn.last_insert_rowid()
+}
+
+fn account_id(conn: &Connection, account: AccountUuid) -> i64 {
+    conn.query_row(
+        "SELECT id FROM accounts WHERE uuid = :uuid",
+        named_params! {":uuid": account.expose_uuid().as_bytes()},
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+/// Adds a Sapling note received by the account in the given transaction.
+///
+/// Returns the note's row ID.
+pub(crate) fn insert_sapling_note(
+    conn: &Connection,
+    tx: i64,
+    output_index: u32,
+    account: AccountUuid,
+    value: u64,
+    is_change: bool,
+    memo: Option<&[u8]>,
+) -> i64 {
+    conn.execute(
+        "INSERT INTO sapling_received_notes
+            (transaction_id, output_index, account_id, diversifier, value, rcm, is_change, memo)
+         VALUES (:tx, :output_index, :account_id, x'00', :value, x'00', :is_change, :memo)",
+        named_params! {
+            ":tx": tx,
+            ":output_index": output_index,
+            ":account_id": account_id(conn, account),
+            ":value": value,
+            ":is_change": is_change,
+            ":memo": memo,
+        },
+    )
+    .unwrap();
+    conn.last_insert_rowid()
+}
+
+/// Records that the given Sapling note was spent in the given transaction.
+pub(crate) fn spend_sapling_note(conn: &Connection, note: i64, tx: i64) {
+    conn.execute(
+        "INSERT INTO sapling_received_note_spends (sapling_received_note_id, transaction_id)
+         VALUES (:note, :tx)",
+        named_params! {":note": note, ":tx": tx},
+    )
+    .unwrap();
+}
+
+/// Adds a Sapling output sent by the account to an external address in the given
+/// transaction.
+pub(crate) fn insert_sent_note(
+    conn: &Connection,
+    tx: i64,
+    output_index: u32,
+    account: AccountUuid,
+    to_address: &str,
+    value: u64,
+) {
+    conn.execute(
+        "INSERT INTO sent_notes
+            (transaction_id, output_pool, output_index, from_account_id, to_address, value)
+         VALUES (:tx, 2, :output_index, :account_id, :to_address, :value)",
+        named_params! {
+            ":tx": tx,
+            ":output_index": output_index,
+            ":account_id": account_id(conn, account),
+            ":to_address": to_address,
+            ":value": value,
+        },
+    )
+    .unwrap();
+}
+
+/// Returns a PCZT that spends a transparent output of the external address at index 0 of
+/// the account derived from a seed of repeated `seed_byte`s, paying `value` to `to` and
+/// the rest of the input to the ZIP 317 fee.
+pub(crate) fn transparent_pczt(seed_byte: u8, to: &TransparentAddress, value: u64) -> Pczt {
+    let pubkey = AccountPrivKey::from_seed(&NETWORK, &[seed_byte; 32], zip32::AccountId::ZERO)
+        .unwrap()
+        .to_account_pubkey()
+        .derive_address_pubkey(TransparentKeyScope::EXTERNAL, NonHardenedChildIndex::ZERO)
+        .unwrap();
+    let from = TransparentAddress::from_pubkey(&pubkey);
+
+    let mut builder = Builder::new(
+        NETWORK,
+        BlockHeight::from_u32(3_000_000),
+        BuildConfig::Standard {
+            sapling_anchor: None,
+            orchard_anchor: None,
+        },
+    );
+    builder
+        .add_transparent_input(
+            pubkey,
+            OutPoint::new([seed_byte; 32], 0),
+            TxOut::new(
+                Zatoshis::from_u64(value + 10_000).unwrap(),
+                from.script().into(),
+            ),
+        )
+        .unwrap();
+    builder
+        .add_transparent_output(to, Zatoshis::from_u64(value).unwrap())
+        .unwrap();
+    let result = builder
+        .build_for_pczt(OsRng, &zip317::FeeRule::standard())
+        .unwrap();
+    Creator::build_from_parts(result.pczt_parts).unwrap()
+}
+
+/// Adds a transparent output received by the account at the given address in the given
+/// transaction.
+///
+/// If `address` is one of the account's transparent addresses, the output is linked to it
+/// and has its script, so that the wallet can spend it; otherwise it is linked to the
+/// account's first address.
+///
+/// Returns the output's row ID.
+pub(crate) fn insert_utxo(
+    conn: &Connection,
+    tx: i64,
+    output_index: u32,
+    account: AccountUuid,
+    address: &str,
+    value: u64,
+) -> i64 {
+    let account_id = account_id(conn, account);
+    let script = TransparentAddress::decode(&NETWORK, address)
+        .map_or(vec![0], |addr| Script::from(addr.script()).0.0);
+    conn.execute(
+        "INSERT INTO transparent_received_outputs
+            (transaction_id, output_index, account_id, address, script, value_zat, address_id)
+         SELECT :tx, :output_index, :account_id, :address, :script, :value, id
+         FROM addresses WHERE account_id = :account_id
+         ORDER BY cached_transparent_receiver_address IS :address DESC, id
+         LIMIT 1",
+        named_params! {
+            ":tx": tx,
+            ":output_index": output_index,
+            ":account_id": account_id,
+            ":address": address,
+            ":script": script,
+            ":value": value,
+        },
+    )
+    .unwrap();
+    conn.last_insert_rowid()
+}
+
+/// Records that the given transparent output was spent in the given transaction.
+pub(crate) fn spend_utxo(conn: &Connection, utxo: i64, tx: i64) {
+    conn.execute(
+        "INSERT INTO transparent_received_output_spends
+            (transparent_received_output_id, transaction_id)
+         VALUES (:utxo, :tx)",
+        named_params! {":utxo": utxo, ":tx": tx},
+    )
+    .unwrap();
+}
@@ -13,7 +13,10 @@ use transparent::{
 };
 use zcash_client_backend::{
     encoding::AddressCodec,
-    proto::service::{self, compact_tx_streamer_client::CompactTxStreamerClient},
+    proto::{
+        compact_formats::CompactBlock,
+        service::{self, compact_tx_streamer_client::CompactTxStreamerClient},
+    },
     tor::{Client, DormantMode},
     wallet::WalletTransparentOutput,
 };
@@ -250,6 +253,35 @@ impl LwdConn {
         })
     }
 
+    /// Fetches the compact blocks in the given range of heights, inclusive of both ends.
+    pub(crate) fn get_block_range(
+        &mut self,
+        start: BlockHeight,
+        end: BlockHeight,
+    ) -> anyhow::Result<Vec<CompactBlock>> {
+        let request = service::BlockRange {
+            start: Some(service::BlockId {
+                height: u32::from(start).into(),
+                ..Default::default()
+            }),
+            end: Some(service::BlockId {
+                height: u32::from(end).into(),
+                ..Default::default()
+            }),
+        };
+
+        self.runtime.clone().block_on(async {
+            let mut stream = self.conn.get_block_range(request).await?.into_inner();
+
+            let mut blocks = vec![];
+            while let Some(block) = stream.message().await? {
+                blocks.push(block);
+            }
+
+            Ok(blocks)
+        })
+    }
+
     /// Fetches the note commitment tree state corresponding to the given block.
     pub(crate) fn get_tree_state(
         &mut self,
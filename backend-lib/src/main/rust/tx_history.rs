@@ -0,0 +1,554 @@
+//! Transaction history and detail queries.
+//!
+//! These read the `v_transactions` and `v_tx_outputs` views that `zcash_client_sqlite`
+//! maintains across its migrations, so that callers do not need to depend upon the
+//! layout of the underlying wallet tables.
+
+use anyhow::anyhow;
+use rusqlite::{Connection, OptionalExtension, named_params};
+use zcash_client_sqlite::AccountUuid;
+use zcash_primitives::transaction::TxId;
+use zcash_protocol::{PoolType, consensus::BlockHeight, memo::MemoBytes, value::Zatoshis};
+
+use crate::{parse_pool, pool_code};
+
+/// The direction of value flow for a transaction, from the perspective of an account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Direction {
+    /// The account's balance decreased, or the transaction only moved funds within it.
+    Sent,
+    /// The account's balance increased.
+    Received,
+    /// The transaction shielded transparent funds belonging to the account.
+    Shielding,
+}
+
+/// The mining status of a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Status {
+    /// The transaction has not been mined, and has not expired.
+    Pending,
+    /// The transaction has been mined.
+    Mined,
+    /// The transaction was not mined before its expiry height.
+    Expired,
+}
+
+/// The position of a transaction within the history, used as a pagination cursor.
+///
+/// History is ordered from most to least recent; a page starting from a cursor contains
+/// only the transactions that sort strictly after it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Cursor {
+    sort_height: u32,
+    sort_index: i64,
+    txid: [u8; 32],
+    account_uuid: [u8; 16],
+}
+
+impl Cursor {
+    const ENCODED_LEN: usize = 4 + 8 + 32 + 16;
+
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::ENCODED_LEN);
+        bytes.extend_from_slice(&self.sort_height.to_be_bytes());
+        bytes.extend_from_slice(&self.sort_index.to_be_bytes());
+        bytes.extend_from_slice(&self.txid);
+        bytes.extend_from_slice(&self.account_uuid);
+        bytes
+    }
+
+    pub(crate) fn decode(bytes: &[u8]) -> anyhow::Result<Self> {
+        if bytes.len() != Self::ENCODED_LEN {
+            return Err(anyhow!("Invalid transaction history cursor"));
+        }
+        let (sort_height, rest) = bytes.split_at(4);
+        let (sort_index, rest) = rest.split_at(8);
+        let (txid, account_uuid) = rest.split_at(32);
+        Ok(Cursor {
+            sort_height: u32::from_be_bytes(sort_height.try_into()?),
+            sort_index: i64::from_be_bytes(sort_index.try_into()?),
+            txid: txid.try_into()?,
+            account_uuid: account_uuid.try_into()?,
+        })
+    }
+}
+
+/// Criteria for selecting transactions from the history. Every criterion that is set
+/// must match.
+#[derive(Default)]
+pub(crate) struct HistoryFilter {
+    pub(crate) account_uuid: Option<AccountUuid>,
+    /// Inclusive range of mined heights. Unmined transactions never match.
+    pub(crate) from_height: Option<BlockHeight>,
+    pub(crate) to_height: Option<BlockHeight>,
+    /// Inclusive range of block times, in seconds since the Unix epoch. Unmined
+    /// transactions never match.
+    pub(crate) from_time: Option<i64>,
+    pub(crate) to_time: Option<i64>,
+    pub(crate) direction: Option<Direction>,
+    /// Matches transactions in which the account sent or received an output in this pool.
+    pub(crate) pool: Option<PoolType>,
+    /// Matches transactions with a text memo containing this string.
+    pub(crate) memo_text: Option<String>,
+    pub(crate) status: Option<Status>,
+}
+
+/// A non-change output sent by a transaction.
+pub(crate) struct Recipient {
+    pub(crate) pool: PoolType,
+    /// The recipient's address, if known.
+    pub(crate) address: Option<String>,
+    /// The receiving account, if the output was sent to an account in this wallet.
+    pub(crate) account_uuid: Option<[u8; 16]>,
+    pub(crate) value: i64,
+}
+
+/// A transaction in the history of an account.
+pub(crate) struct HistoryEntry {
+    pub(crate) txid: TxId,
+    pub(crate) account_uuid: [u8; 16],
+    pub(crate) mined_height: Option<BlockHeight>,
+    pub(crate) expiry_height: Option<BlockHeight>,
+    pub(crate) block_time: Option<i64>,
+    pub(crate) net_value: i64,
+    pub(crate) fee: Option<i64>,
+    pub(crate) direction: Direction,
+    pub(crate) status: Status,
+    pub(crate) recipients: Vec<Recipient>,
+}
+
+/// A page of history entries, with the cursor for the next page if there is one.
+pub(crate) struct HistoryPage {
+    pub(crate) entries: Vec<HistoryEntry>,
+    pub(crate) next_cursor: Option<Cursor>,
+}
+
+/// Returns up to `limit` transactions matching `filter`, most recent first, starting
+/// after `cursor` if one is given.
+pub(crate) fn query_history(
+    conn: &Connection,
+    filter: &HistoryFilter,
+    cursor: Option<&Cursor>,
+    limit: u32,
+) -> anyhow::Result<HistoryPage> {
+    let mut stmt = conn.prepare(
+        "SELECT txid, account_uuid, mined_height, expiry_height, block_time,
+                account_balance_delta, fee_paid, is_shielding, expired_unmined,
+                sort_height, sort_index
+         FROM (
+            SELECT v_transactions.*,
+                   IFNULL(mined_height, 4294967295) AS sort_height,
+                   IFNULL(tx_index, -1) AS sort_index
+            FROM v_transactions
+         ) t
+         WHERE (:account_uuid IS NULL OR t.account_uuid = :account_uuid)
+         AND (:from_height IS NULL OR t.mined_height >= :from_height)
+         AND (:to_height IS NULL OR t.mined_height <= :to_height)
+         AND (:from_time IS NULL OR t.block_time >= :from_time)
+         AND (:to_time IS NULL OR t.block_time <= :to_time)
+         AND (
+            :direction IS NULL
+            OR (:direction = 'sent' AND NOT t.is_shielding AND t.account_balance_delta <= 0)
+            OR (:direction = 'received' AND NOT t.is_shielding AND t.account_balance_delta > 0)
+            OR (:direction = 'shielding' AND t.is_shielding)
+         )
+         AND (
+            :status IS NULL
+            OR (:status = 'pending' AND t.mined_height IS NULL AND NOT t.expired_unmined)
+            OR (:status = 'mined' AND t.mined_height IS NOT NULL)
+            OR (:status = 'expired' AND t.expired_unmined)
+         )
+         AND (
+            :pool IS NULL
+            OR EXISTS (
+                SELECT 1 FROM v_tx_outputs o
+                WHERE o.txid = t.txid
+                AND o.output_pool = :pool
+                AND (o.from_account_uuid = t.account_uuid OR o.to_account_uuid = t.account_uuid)
+            )
+         )
+         AND (
+            :memo_text IS NULL
+            OR EXISTS (
+                SELECT 1 FROM v_tx_outputs o
+                WHERE o.txid = t.txid
+                AND (o.from_account_uuid = t.account_uuid OR o.to_account_uuid = t.account_uuid)
+                AND o.memo IS NOT NULL
+                AND substr(o.memo, 1, 1) < X'F5'
+                AND instr(o.memo, CAST(:memo_text AS BLOB)) > 0
+            )
+         )
+         AND (
+            :cursor_height IS NULL
+            OR (t.sort_height, t.sort_index, t.txid, t.account_uuid)
+                < (:cursor_height, :cursor_index, :cursor_txid, :cursor_account_uuid)
+         )
+         ORDER BY t.sort_height DESC, t.sort_index DESC, t.txid DESC, t.account_uuid DESC
+         LIMIT :limit",
+    )?;
+
+    let rows = stmt.query_map(
+        named_params! {
+            ":account_uuid": filter.account_uuid.map(|a| *a.expose_uuid().as_bytes()),
+            ":from_height": filter.from_height.map(u32::from),
+            ":to_height": filter.to_height.map(u32::from),
+            ":from_time": filter.from_time,
+            ":to_time": filter.to_time,
+            ":direction": filter.direction.map(|d| match d {
+                Direction::Sent => "sent",
+                Direction::Received => "received",
+                Direction::Shielding => "shielding",
+            }),
+            ":status": filter.status.map(|s| match s {
+                Status::Pending => "pending",
+                Status::Mined => "mined",
+                Status::Expired => "expired",
+            }),
+            ":pool": filter.pool.map(pool_code),
+            ":memo_text": filter.memo_text.as_deref(),
+            ":cursor_height": cursor.map(|c| c.sort_height),
+            ":cursor_index": cursor.map(|c| c.sort_index),
+            ":cursor_txid": cursor.map(|c| c.txid),
+            ":cursor_account_uuid": cursor.map(|c| c.account_uuid),
+            // Fetch one extra row to determine whether there is another page.
+            ":limit": i64::from(limit) + 1,
+        },
+        |row| {
+            let is_shielding: bool = row.get("is_shielding")?;
+            let net_value: i64 = row.get("account_balance_delta")?;
+            let mined_height: Option<u32> = row.get("mined_height")?;
+            let expired: bool = row.get("expired_unmined")?;
+            Ok((
+                HistoryEntry {
+                    txid: TxId::from_bytes(row.get("txid")?),
+                    account_uuid: row.get("account_uuid")?,
+                    mined_height: mined_height.map(BlockHeight::from_u32),
+                    expiry_height: row
+                        .get::<_, Option<u32>>("expiry_height")?
+                        .filter(|h| *h != 0)
+                        .map(BlockHeight::from_u32),
+                    block_time: row.get("block_time")?,
+                    net_value,
+                    fee: row.get("fee_paid")?,
+                    direction: if is_shielding {
+                        Direction::Shielding
+                    } else if net_value > 0 {
+                        Direction::Received
+                    } else {
+                        Direction::Sent
+                    },
+                    status: if mined_height.is_some() {
+                        Status::Mined
+                    } else if expired {
+                        Status::Expired
+                    } else {
+                        Status::Pending
+                    },
+                    recipients: vec![],
+                },
+                row.get::<_, u32>("sort_height")?,
+                row.get::<_, i64>("sort_index")?,
+            ))
+        },
+    )?;
+
+    let mut rows = rows.collect::<Result<Vec<_>, _>>()?;
+    let next_cursor = if rows.len() > limit as usize {
+        rows.truncate(limit as usize);
+        rows.last().map(|(entry, sort_height, sort_index)| Cursor {
+            sort_height: *sort_height,
+            sort_index: *sort_index,
+            txid: *entry.txid.as_ref(),
+            account_uuid: entry.account_uuid,
+        })
+    } else {
+        None
+    };
+    let mut entries = rows
+        .into_iter()
+        .map(|(entry, _, _)| entry)
+        .collect::<Vec<_>>();
+
+    let mut recipients_stmt = conn.prepare(
+        "SELECT output_pool, to_address, to_account_uuid, value
+         FROM v_tx_outputs
+         WHERE txid = :txid
+         AND from_account_uuid = :account_uuid
+         AND NOT is_change
+         ORDER BY output_pool, output_index",
+    )?;
+    for entry in &mut entries {
+        entry.recipients = recipients_stmt
+            .query_and_then(
+                named_params! {
+                    ":txid": entry.txid.as_ref(),
+                    ":account_uuid": entry.account_uuid,
+                },
+                |row| {
+                    Ok(Recipient {
+                        pool: parse_pool(row.get("output_pool")?)?,
+                        address: row.get("to_address")?,
+                        account_uuid: row.get("to_account_uuid")?,
+                        value: row.get("value")?,
+                    })
+                },
+            )?
+            .collect::<anyhow::Result<_>>()?;
+    }
+
+    Ok(HistoryPage {
+        entries,
+        next_cursor,
+    })
+}
+
+/// An output of a transaction that was sent or received by the wallet.
+pub(crate) struct TxOutput {
+    pub(crate) pool: PoolType,
+    pub(crate) output_index: u32,
+    /// The account that sent the output, if it was sent by this wallet.
+    pub(crate) from_account_uuid: Option<[u8; 16]>,
+    /// The account that received the output, if it was received by this wallet.
+    pub(crate) to_account_uuid: Option<[u8; 16]>,
+    /// The recipient's address. For outputs sent by this wallet, this is the address
+    /// that the payment was made to, which may be a Unified Address.
+    pub(crate) recipient: Option<String>,
+    pub(crate) value: Zatoshis,
+    pub(crate) is_change: bool,
+    pub(crate) memo: Option<MemoBytes>,
+}
+
+/// Returns every output of the given transaction that was sent or received by the wallet.
+///
+/// Returns an error if the transaction is not known to the wallet.
+pub(crate) fn transaction_outputs(conn: &Connection, txid: &TxId) -> anyhow::Result<Vec<TxOutput>> {
+    let known = conn
+        .query_row(
+            "SELECT 1 FROM transactions WHERE txid = :txid",
+            named_params! {":txid": txid.as_ref()},
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some();
+    if !known {
+        return Err(anyhow!("Transaction {} not found in the wallet", txid));
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT output_pool, output_index, from_account_uuid, to_account_uuid, to_address,
+                value, is_change, memo
+         FROM v_tx_outputs
+         WHERE txid = :txid
+         ORDER BY output_pool, output_index",
+    )?;
+
+    stmt.query_and_then(named_params! {":txid": txid.as_ref()}, |row| {
+        let value: i64 = row.get("value")?;
+        Ok(TxOutput {
+            pool: parse_pool(row.get("output_pool")?)?,
+            output_index: row.get("output_index")?,
+            from_account_uuid: row.get("from_account_uuid")?,
+            to_account_uuid: row.get("to_account_uuid")?,
+            recipient: row.get("to_address")?,
+            value: Zatoshis::from_nonnegative_i64(value)
+                .map_err(|_| anyhow!("Invalid output value {}", value))?,
+            is_change: row.get("is_change")?,
+            memo: row
+                .get::<_, Option<Vec<u8>>>("memo")?
+                .map(|memo| MemoBytes::from_bytes(&memo))
+                .transpose()
+                .map_err(|e| anyhow!("Invalid memo: {}", e))?,
+        })
+    })?
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        add_account, insert_block, insert_sapling_note, insert_sent_note, insert_tx,
+        spend_sapling_note, txid, wallet_db,
+    };
+
+    /// An account that received a note with a memo at height 101, spent it at height 102
+    /// paying an external address, and has a pending incoming transaction.
+    fn history_db() -> (Connection, AccountUuid) {
+        let mut conn = wallet_db();
+        let account = add_account(&mut conn, 1, 100);
+        insert_block(&conn, 101, 1_000);
+        insert_block(&conn, 102, 2_000);
+
+        let mut memo = [0; 512];
+        memo[..5].copy_from_slice(b"hello");
+        let received = insert_tx(&conn, 1, Some(101), 0);
+        let note = insert_sapling_note(&conn, received, 0, account, 50_000, false, Some(&memo));
+
+        let sent = insert_tx(&conn, 2, Some(102), 0);
+        spend_sapling_note(&conn, note, sent);
+        insert_sent_note(&conn, sent, 0, account, "external", 20_000);
+        insert_sapling_note(&conn, sent, 1, account, 20_000, true, None);
+
+        let pending = insert_tx(&conn, 3, None, 0);
+        insert_sapling_note(&conn, pending, 0, account, 10_000, false, None);
+
+        (conn, account)
+    }
+
+    fn txids(page: &HistoryPage) -> Vec<TxId> {
+        page.entries.iter().map(|e| e.txid).collect()
+    }
+
+    #[test]
+    fn history_is_most_recent_first() {
+        let (conn, account) = history_db();
+        let filter = HistoryFilter {
+            account_uuid: Some(account),
+            ..Default::default()
+        };
+        let page = query_history(&conn, &filter, None, 10).unwrap();
+        assert_eq!(txids(&page), [txid(3), txid(2), txid(1)]);
+        assert!(page.next_cursor.is_none());
+
+        let [pending, sent, received] = &page.entries[..] else {
+            unreachable!()
+        };
+        assert_eq!(pending.status, Status::Pending);
+        assert_eq!(pending.direction, Direction::Received);
+        assert_eq!(pending.block_time, None);
+
+        assert_eq!(sent.status, Status::Mined);
+        assert_eq!(sent.direction, Direction::Sent);
+        assert_eq!(sent.net_value, -30_000);
+        assert_eq!(sent.block_time, Some(2_000));
+        assert_eq!(sent.recipients.len(), 1);
+        assert_eq!(sent.recipients[0].address.as_deref(), Some("external"));
+        assert_eq!(sent.recipients[0].value, 20_000);
+
+        assert_eq!(received.direction, Direction::Received);
+        assert_eq!(received.net_value, 50_000);
+        assert_eq!(received.mined_height, Some(BlockHeight::from_u32(101)));
+    }
+
+    #[test]
+    fn history_pages_resume_from_cursor() {
+        let (conn, _) = history_db();
+        let filter = HistoryFilter::default();
+
+        let mut cursor = None;
+        let mut seen = vec![];
+        loop {
+            let page = query_history(&conn, &filter, cursor.as_ref(), 1).unwrap();
+            seen.extend(txids(&page));
+            match page.next_cursor {
+                Some(next) => {
+                    cursor = Some(Cursor::decode(&next.encode()).unwrap());
+                }
+                None => break,
+            }
+        }
+        assert_eq!(seen, [txid(3), txid(2), txid(1)]);
+        assert!(Cursor::decode(&[0; 3]).is_err());
+    }
+
+    #[test]
+    fn history_filters_combine() {
+        let (conn, _) = history_db();
+        let query =
+            |filter: HistoryFilter| txids(&query_history(&conn, &filter, None, 10).unwrap());
+
+        assert_eq!(
+            query(HistoryFilter {
+                direction: Some(Direction::Received),
+                ..Default::default()
+            }),
+            [txid(3), txid(1)]
+        );
+        assert_eq!(
+            query(HistoryFilter {
+                memo_text: Some("hello".into()),
+                ..Default::default()
+            }),
+            [txid(1)]
+        );
+        assert_eq!(
+            query(HistoryFilter {
+                from_height: Some(BlockHeight::from_u32(102)),
+                ..Default::default()
+            }),
+            [txid(2)]
+        );
+        assert_eq!(
+            query(HistoryFilter {
+                from_time: Some(500),
+                to_time: Some(1_500),
+                ..Default::default()
+            }),
+            [txid(1)]
+        );
+        assert_eq!(
+            query(HistoryFilter {
+                status: Some(Status::Pending),
+                pool: Some(PoolType::SAPLING),
+                ..Default::default()
+            }),
+            [txid(3)]
+        );
+        assert!(
+            query(HistoryFilter {
+                pool: Some(PoolType::ORCHARD),
+                ..Default::default()
+            })
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn transaction_outputs_include_sent_and_change() {
+        let (conn, account) = history_db();
+        let outputs = transaction_outputs(&conn, &txid(2)).unwrap();
+        assert_eq!(outputs.len(), 2);
+        let account = Some(*account.expose_uuid().as_bytes());
+
+        assert_eq!(outputs[0].output_index, 0);
+        assert_eq!(outputs[0].from_account_uuid, account);
+        assert_eq!(outputs[0].to_account_uuid, None);
+        assert_eq!(outputs[0].recipient.as_deref(), Some("external"));
+        assert_eq!(u64::from(outputs[0].value), 20_000);
+
+        assert_eq!(outputs[1].output_index, 1);
+        assert_eq!(outputs[1].to_account_uuid, account);
+        assert!(outputs[1].is_change);
+
+        assert!(transaction_outputs(&conn, &txid(9)).is_err());
+    }
+
+    #[test]
+    fn transaction_outputs_return_raw_memos() {
+        let (conn, account) = history_db();
+        let outputs = transaction_outputs(&conn, &txid(1)).unwrap();
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].pool, PoolType::SAPLING);
+        assert_eq!(
+            outputs[0].to_account_uuid,
+            Some(*account.expose_uuid().as_bytes())
+        );
+        assert!(!outputs[0].is_change);
+        let memo = outputs[0].memo.as_ref().unwrap().as_array();
+        assert_eq!(&memo[..5], b"hello");
+        assert!(memo[5..].iter().all(|b| *b == 0));
+
+        let outputs = transaction_outputs(&conn, &txid(3)).unwrap();
+        assert_eq!(outputs.len(), 1);
+        assert!(outputs[0].memo.is_none());
+
+        assert_eq!(
+            transaction_outputs(&conn, &txid(9))
+                .err()
+                .unwrap()
+                .to_string(),
+            format!("Transaction {} not found in the wallet", txid(9))
+        );
+    }
+}
@@ -0,0 +1,223 @@
+//! Management of transactions that the wallet created but that have not been mined.
+//!
+//! `zcash_client_sqlite` stops treating the notes spent by an unmined transaction as
+//! spent once the transaction has expired, but keeps the transaction itself. We record
+//! the transactions that the user has chosen to abandon in a table of our own, so that
+//! they are no longer offered for resubmission.
+
+use anyhow::anyhow;
+use rusqlite::{Connection, OptionalExtension, named_params};
+use zcash_primitives::transaction::TxId;
+use zcash_protocol::consensus::BlockHeight;
+
+/// A transaction created by the wallet that has not been mined.
+pub(crate) struct UnminedTx {
+    pub(crate) txid: TxId,
+    /// The height after which the transaction can no longer be mined, if it expires.
+    pub(crate) expiry_height: Option<BlockHeight>,
+    /// Whether the wallet has scanned every block up to the expiry height without
+    /// finding the transaction, so that it can never be mined and the notes it spends
+    /// are spendable again.
+    pub(crate) is_expired: bool,
+    /// Whether the raw transaction is available for resubmission.
+    pub(crate) has_raw: bool,
+}
+
+/// Creates the table of abandoned transactions. This is called by [`sdk_schema::init`].
+///
+/// [`sdk_schema::init`]: crate::sdk_schema::init
+pub(crate) fn init_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS sdk_abandoned_transactions (
+            txid BLOB PRIMARY KEY NOT NULL,
+            abandoned_at INTEGER NOT NULL
+        )",
+    )
+}
+
+/// Returns whether a transaction with the given expiry height that the wallet has not
+/// seen mined has expired, given the height up to which the wallet has scanned every
+/// block.
+///
+/// The chain tip is not enough: a block at or below the expiry height that the wallet
+/// has not scanned yet may contain the transaction.
+pub(crate) fn is_expired(
+    expiry_height: Option<BlockHeight>,
+    fully_scanned: Option<BlockHeight>,
+) -> bool {
+    // A transaction may be mined in any block up to and including its expiry height.
+    matches!((expiry_height, fully_scanned), (Some(expiry), Some(scanned)) if expiry <= scanned)
+}
+
+/// Returns the transactions created by the wallet that have not been mined and have not
+/// been abandoned, most recently created first.
+///
+/// `fully_scanned` is the height up to which the wallet has scanned every block.
+pub(crate) fn list_unmined(
+    conn: &Connection,
+    fully_scanned: Option<BlockHeight>,
+) -> anyhow::Result<Vec<UnminedTx>> {
+    let mut stmt = conn.prepare(
+        "SELECT t.txid, t.expiry_height, t.raw IS NOT NULL AS has_raw
+         FROM transactions t
+         LEFT JOIN sdk_abandoned_transactions a ON a.txid = t.txid
+         WHERE t.mined_height IS NULL
+         AND t.created IS NOT NULL
+         AND a.txid IS NULL
+         ORDER BY t.created DESC",
+    )?;
+
+    stmt.query_and_then([], |row| {
+        let expiry_height = row
+            .get::<_, Option<u32>>("expiry_height")?
+            .filter(|h| *h != 0)
+            .map(BlockHeight::from_u32);
+        Ok(UnminedTx {
+            txid: TxId::from_bytes(row.get("txid")?),
+            expiry_height,
+            is_expired: is_expired(expiry_height, fully_scanned),
+            has_raw: row.get("has_raw")?,
+        })
+    })?
+    .collect()
+}
+
+/// Returns the raw bytes of the given transaction, if the wallet has them.
+pub(crate) fn raw_transaction(conn: &Connection, txid: &TxId) -> anyhow::Result<Option<Vec<u8>>> {
+    Ok(conn
+        .query_row(
+            "SELECT raw FROM transactions WHERE txid = :txid",
+            named_params! {":txid": txid.as_ref()},
+            |row| row.get::<_, Option<Vec<u8>>>(0),
+        )
+        .optional()?
+        .flatten())
+}
+
+/// Marks an expired, unmined transaction as abandoned, so that it is no longer returned
+/// by [`list_unmined`].
+///
+/// Returns an error if the transaction has been mined or has not yet expired as of
+/// `fully_scanned`, the height up to which the wallet has scanned every block.
+pub(crate) fn abandon(
+    conn: &mut Connection,
+    txid: &TxId,
+    fully_scanned: Option<BlockHeight>,
+    now: i64,
+) -> anyhow::Result<()> {
+    let tx = conn.transaction()?;
+
+    let (mined_height, expiry_height) = tx
+        .query_row(
+            "SELECT mined_height, expiry_height FROM transactions WHERE txid = :txid",
+            named_params! {":txid": txid.as_ref()},
+            |row| {
+                Ok((
+                    row.get::<_, Option<u32>>(0)?,
+                    row.get::<_, Option<u32>>(1)?
+                        .filter(|h| *h != 0)
+                        .map(BlockHeight::from_u32),
+                ))
+            },
+        )
+        .optional()?
+        .ok_or_else(|| anyhow!("Transaction {} not found in the wallet", txid))?;
+
+    if mined_height.is_some() {
+        return Err(anyhow!("Transaction {} has been mined", txid));
+    }
+    if !is_expired(expiry_height, fully_scanned) {
+        return Err(anyhow!(
+            "Transaction {} has not expired and may still be mined",
+            txid
+        ));
+    }
+
+    tx.execute(
+        "INSERT OR IGNORE INTO sdk_abandoned_transactions (txid, abandoned_at)
+         VALUES (:txid, :abandoned_at)",
+        named_params! {":txid": txid.as_ref(), ":abandoned_at": now},
+    )?;
+    tx.commit()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use zcash_client_backend::data_api::{WalletRead, WalletWrite};
+
+    use super::*;
+    use crate::testing::{SharedWalletDb, add_account, insert_tx, mark_scanned, txid, wallet_db};
+
+    #[test]
+    fn lists_unmined_transactions_newest_first() {
+        let conn = wallet_db();
+        insert_tx(&conn, 1, None, 100);
+        insert_tx(&conn, 2, None, 0);
+        insert_tx(&conn, 3, Some(50), 100);
+
+        let unmined = list_unmined(&conn, Some(BlockHeight::from_u32(100))).unwrap();
+        assert_eq!(
+            unmined.iter().map(|tx| tx.txid).collect::<Vec<_>>(),
+            [txid(2), txid(1)]
+        );
+        assert_eq!(unmined[0].expiry_height, None);
+        assert!(!unmined[0].is_expired);
+        assert!(unmined[1].is_expired);
+        assert!(unmined.iter().all(|tx| tx.has_raw));
+        assert_eq!(raw_transaction(&conn, &txid(1)).unwrap(), Some(vec![0; 4]));
+    }
+
+    #[test]
+    fn only_expired_transactions_can_be_abandoned() {
+        let mut conn = wallet_db();
+        insert_tx(&conn, 1, None, 100);
+        insert_tx(&conn, 2, Some(50), 100);
+        let tip = Some(BlockHeight::from_u32(99));
+
+        assert!(abandon(&mut conn, &txid(1), tip, 0).is_err());
+        assert!(abandon(&mut conn, &txid(2), tip, 0).is_err());
+        assert!(abandon(&mut conn, &txid(3), tip, 0).is_err());
+
+        let tip = Some(BlockHeight::from_u32(100));
+        abandon(&mut conn, &txid(1), tip, 0).unwrap();
+        assert!(list_unmined(&conn, tip).unwrap().is_empty());
+    }
+
+    #[test]
+    fn expiry_is_judged_against_the_fully_scanned_height() {
+        let mut db = SharedWalletDb::new();
+        add_account(&mut db.conn, 1, 3_000_000);
+        insert_tx(&db.conn, 1, None, 3_000_060);
+        let mut db_data = db.wallet();
+        db_data
+            .update_chain_tip(BlockHeight::from_u32(3_000_100))
+            .unwrap();
+        mark_scanned(&db.conn, 3_000_000, 3_000_040);
+
+        // The chain tip is past the expiry height, but the transaction may be in a block
+        // that has not been scanned.
+        assert_eq!(
+            db_data.chain_height().unwrap(),
+            Some(BlockHeight::from_u32(3_000_100))
+        );
+        let fully_scanned = crate::fully_scanned_height(&db_data).unwrap();
+        assert_eq!(fully_scanned, Some(BlockHeight::from_u32(3_000_040)));
+        assert!(!list_unmined(&db.conn, fully_scanned).unwrap()[0].is_expired);
+        assert_eq!(
+            abandon(&mut db.conn, &txid(1), fully_scanned, 0)
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "Transaction {} has not expired and may still be mined",
+                txid(1)
+            )
+        );
+
+        mark_scanned(&db.conn, 3_000_000, 3_000_060);
+        let fully_scanned = crate::fully_scanned_height(&db_data).unwrap();
+        assert!(list_unmined(&db.conn, fully_scanned).unwrap()[0].is_expired);
+        abandon(&mut db.conn, &txid(1), fully_scanned, 0).unwrap();
+    }
+}
@@ -0,0 +1,759 @@
+//! Backup and restore of the wallet database.
+//!
+//! A backup is a snapshot taken with SQLite's online backup API, so it is consistent even
+//! if the SDK writes to the wallet database while it is taken. An uncompressed,
+//! unencrypted backup is the snapshot itself. Otherwise the snapshot is wrapped in a
+//! container:
+//!
+//! ```text
+//! magic (8 bytes) || version (1 byte) || flags (1 byte) || [nonce prefix (19 bytes)] || body
+//! ```
+//!
+//! where the body is the snapshot, gzip-compressed if [`FLAG_COMPRESSED`] is set, and then
+//! encrypted if [`FLAG_ENCRYPTED`] is set. Encryption uses the STREAM construction (with
+//! 32-bit big-endian counters) over XChaCha20Poly1305, in chunks of [`CHUNK_LENGTH`] bytes
+//! of plaintext, each with the preceding header as associated data. The container is
+//! streamed between files, so neither backup nor restore holds the database in memory.
+
+use std::collections::BTreeSet;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::anyhow;
+use chacha20poly1305::{
+    XChaCha20Poly1305,
+    aead::{
+        KeyInit, Payload,
+        generic_array::GenericArray,
+        stream::{DecryptorBE32, EncryptorBE32},
+    },
+};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use rand::{RngCore, rngs::OsRng};
+use rusqlite::{Connection, OpenFlags, backup::Backup};
+use secrecy::{ExposeSecret, SecretVec};
+use tracing::info;
+use zcash_address::unified::{self, Encoding};
+use zcash_client_sqlite::{WalletDb, util::SystemClock, wallet::init::init_wallet_db};
+use zcash_protocol::consensus::{Network, Parameters};
+use zip32::fingerprint::SeedFingerprint;
+
+use crate::sdk_schema;
+
+const MAGIC: &[u8; 8] = b"ZWALLETB";
+const VERSION: u8 = 1;
+const FLAG_COMPRESSED: u8 = 0b01;
+const FLAG_ENCRYPTED: u8 = 0b10;
+const HEADER_LENGTH: usize = MAGIC.len() + 2;
+
+/// The length of the STREAM nonce prefix; the remaining 5 bytes of the XChaCha20Poly1305
+/// nonce hold the chunk counter and last-chunk flag.
+const NONCE_LENGTH: usize = 19;
+
+/// The number of bytes of plaintext in each encrypted chunk but the last.
+const CHUNK_LENGTH: usize = 64 * 1024;
+
+/// The length of the authentication tag appended to each encrypted chunk.
+const TAG_LENGTH: usize = 16;
+
+/// The length of a backup encryption key.
+pub(crate) const KEY_LENGTH: usize = 32;
+
+/// The header of every SQLite database file.
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// The number of pages copied in each step of the online backup.
+const BACKUP_PAGES_PER_STEP: std::ffi::c_int = 1024;
+
+/// The pause between steps of the online backup, during which the SDK may write.
+const BACKUP_STEP_PAUSE: Duration = Duration::from_millis(10);
+
+/// Returns the path of a file alongside the given one, named with the given suffix.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        res => res,
+    }
+}
+
+/// Reads until `buf` is full or the reader is exhausted, and returns the number of bytes
+/// read.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+fn cipher(key: &SecretVec<u8>) -> anyhow::Result<XChaCha20Poly1305> {
+    XChaCha20Poly1305::new_from_slice(key.expose_secret()).map_err(|_| {
+        anyhow!(
+            "Backup key must be {} bytes, got {}",
+            KEY_LENGTH,
+            key.expose_secret().len()
+        )
+    })
+}
+
+fn undecryptable() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "Wallet backup could not be decrypted with the given seed",
+    )
+}
+
+/// Encrypts everything written to it in chunks, and writes the ciphertext to the inner
+/// writer. [`EncryptingWriter::finish`] must be called to write the last chunk.
+struct EncryptingWriter<W: Write> {
+    encryptor: EncryptorBE32<XChaCha20Poly1305>,
+    aad: [u8; HEADER_LENGTH],
+    chunk: Vec<u8>,
+    inner: W,
+}
+
+impl<W: Write> EncryptingWriter<W> {
+    fn new(
+        cipher: XChaCha20Poly1305,
+        nonce: &[u8; NONCE_LENGTH],
+        aad: [u8; HEADER_LENGTH],
+        inner: W,
+    ) -> Self {
+        EncryptingWriter {
+            encryptor: EncryptorBE32::from_aead(cipher, GenericArray::from_slice(nonce)),
+            aad,
+            chunk: Vec::with_capacity(CHUNK_LENGTH),
+            inner,
+        }
+    }
+
+    fn finish(mut self) -> io::Result<W> {
+        let ciphertext = self
+            .encryptor
+            .encrypt_last(Payload {
+                msg: &self.chunk,
+                aad: &self.aad,
+            })
+            .map_err(|_| io::Error::other("Failed to encrypt backup"))?;
+        self.inner.write_all(&ciphertext)?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for EncryptingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // A full chunk is only encrypted once more plaintext follows it, because the last
+        // chunk is encrypted differently.
+        if self.chunk.len() == CHUNK_LENGTH && !buf.is_empty() {
+            let ciphertext = self
+                .encryptor
+                .encrypt_next(Payload {
+                    msg: &self.chunk,
+                    aad: &self.aad,
+                })
+                .map_err(|_| io::Error::other("Failed to encrypt backup"))?;
+            self.inner.write_all(&ciphertext)?;
+            self.chunk.clear();
+        }
+        let n = buf.len().min(CHUNK_LENGTH - self.chunk.len());
+        self.chunk.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Decrypts the chunks read from the inner reader. Reaching the end of the plaintext
+/// authenticates the whole stream, including that it was not truncated.
+struct DecryptingReader<R: Read> {
+    decryptor: Option<DecryptorBE32<XChaCha20Poly1305>>,
+    aad: [u8; HEADER_LENGTH],
+    ciphertext: Vec<u8>,
+    plaintext: Vec<u8>,
+    pos: usize,
+    inner: R,
+}
+
+impl<R: Read> DecryptingReader<R> {
+    fn new(
+        cipher: XChaCha20Poly1305,
+        nonce: &[u8; NONCE_LENGTH],
+        aad: [u8; HEADER_LENGTH],
+        inner: R,
+    ) -> Self {
+        DecryptingReader {
+            decryptor: Some(DecryptorBE32::from_aead(
+                cipher,
+                GenericArray::from_slice(nonce),
+            )),
+            aad,
+            ciphertext: Vec::with_capacity(CHUNK_LENGTH + TAG_LENGTH + 1),
+            plaintext: vec![],
+            pos: 0,
+            inner,
+        }
+    }
+
+    /// Decrypts the next chunk into `plaintext`, or does nothing once the last chunk has
+    /// been decrypted.
+    fn next_chunk(&mut self) -> io::Result<()> {
+        let Some(decryptor) = self.decryptor.as_mut() else {
+            return Ok(());
+        };
+        // One byte beyond a full chunk is read, to tell whether this chunk is the last.
+        let have = self.ciphertext.len();
+        self.ciphertext.resize(CHUNK_LENGTH + TAG_LENGTH + 1, 0);
+        let filled = have + read_full(&mut self.inner, &mut self.ciphertext[have..])?;
+        self.ciphertext.truncate(filled);
+
+        if filled > CHUNK_LENGTH + TAG_LENGTH {
+            let next = self.ciphertext.split_off(CHUNK_LENGTH + TAG_LENGTH);
+            self.plaintext = decryptor
+                .decrypt_next(Payload {
+                    msg: &self.ciphertext,
+                    aad: &self.aad,
+                })
+                .map_err(|_| undecryptable())?;
+            self.ciphertext = next;
+        } else {
+            let decryptor = self.decryptor.take().expect("checked above");
+            self.plaintext = decryptor
+                .decrypt_last(Payload {
+                    msg: &self.ciphertext,
+                    aad: &self.aad,
+                })
+                .map_err(|_| undecryptable())?;
+            self.ciphertext.clear();
+        }
+        self.pos = 0;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for DecryptingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.plaintext.len() {
+            if self.decryptor.is_none() || buf.is_empty() {
+                return Ok(0);
+            }
+            self.next_chunk()?;
+        }
+        let n = buf.len().min(self.plaintext.len() - self.pos);
+        buf[..n].copy_from_slice(&self.plaintext[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Copies `src` to `dst`, gzip-compressing it if `compress` is set.
+fn compress_into(src: &mut impl Read, dst: &mut impl Write, compress: bool) -> io::Result<()> {
+    if compress {
+        let mut encoder = GzEncoder::new(dst, Compression::default());
+        io::copy(src, &mut encoder)?;
+        encoder.finish()?;
+    } else {
+        io::copy(src, dst)?;
+    }
+    Ok(())
+}
+
+/// Copies `src` to `dst`, gzip-decompressing it if `compressed` is set.
+fn decompress_into(src: &mut impl Read, dst: &mut impl Write, compressed: bool) -> io::Result<()> {
+    if compressed {
+        io::copy(&mut GzDecoder::new(src), dst)?;
+    } else {
+        io::copy(src, dst)?;
+    }
+    Ok(())
+}
+
+/// Writes a backup container holding the snapshot read from `snapshot` to `sealed`.
+fn seal(
+    snapshot: &mut impl Read,
+    sealed: &mut impl Write,
+    compress: bool,
+    key: Option<&SecretVec<u8>>,
+) -> anyhow::Result<()> {
+    let mut flags = 0;
+    if compress {
+        flags |= FLAG_COMPRESSED;
+    }
+    if key.is_some() {
+        flags |= FLAG_ENCRYPTED;
+    }
+
+    let mut header = [0; HEADER_LENGTH];
+    header[..MAGIC.len()].copy_from_slice(MAGIC);
+    header[MAGIC.len()] = VERSION;
+    header[MAGIC.len() + 1] = flags;
+    sealed.write_all(&header)?;
+
+    match key {
+        Some(key) => {
+            let mut nonce = [0; NONCE_LENGTH];
+            OsRng.fill_bytes(&mut nonce);
+            sealed.write_all(&nonce)?;
+            let mut writer = EncryptingWriter::new(cipher(key)?, &nonce, header, sealed);
+            compress_into(snapshot, &mut writer, compress)?;
+            writer.finish()?;
+        }
+        None => compress_into(snapshot, sealed, compress)?,
+    }
+    Ok(())
+}
+
+/// Returns whether the given file starts with a backup container header, rather than
+/// being a bare snapshot.
+fn is_sealed(file: &mut File) -> io::Result<bool> {
+    let mut magic = [0; MAGIC.len()];
+    let read = read_full(file, &mut magic)?;
+    file.rewind()?;
+    Ok(read == magic.len() && &magic == MAGIC)
+}
+
+/// Writes the snapshot held in the backup container read from `sealed` to `snapshot`.
+fn open_sealed(
+    sealed: &mut impl Read,
+    snapshot: &mut impl Write,
+    key: Option<&SecretVec<u8>>,
+) -> anyhow::Result<()> {
+    let mut header = [0; HEADER_LENGTH];
+    if read_full(sealed, &mut header)? < HEADER_LENGTH || !header.starts_with(MAGIC) {
+        return Err(anyhow!("Not a wallet backup"));
+    }
+    let (version, flags) = (header[MAGIC.len()], header[MAGIC.len() + 1]);
+    if version != VERSION {
+        return Err(anyhow!("Unsupported wallet backup version {}", version));
+    }
+    let compressed = flags & FLAG_COMPRESSED != 0;
+
+    if flags & FLAG_ENCRYPTED != 0 {
+        let key = key.ok_or_else(|| anyhow!("Wallet backup is encrypted; a seed is required"))?;
+        let mut nonce = [0; NONCE_LENGTH];
+        if read_full(sealed, &mut nonce)? < NONCE_LENGTH {
+            return Err(anyhow!("Wallet backup is truncated"));
+        }
+        let mut reader = DecryptingReader::new(cipher(key)?, &nonce, header, sealed);
+        decompress_into(&mut reader, snapshot, compressed)?;
+        // The decompressor may stop before the last chunk, which must still be
+        // authenticated.
+        io::copy(&mut reader, &mut io::sink())?;
+    } else {
+        decompress_into(sealed, snapshot, compressed)?;
+    }
+    Ok(())
+}
+
+/// Writes a consistent snapshot of the wallet database at `db_path` to `backup_path`,
+/// optionally compressed and encrypted with `key`, and returns the size of the backup.
+pub(crate) fn backup(
+    db_path: &Path,
+    backup_path: &Path,
+    compress: bool,
+    key: Option<&SecretVec<u8>>,
+) -> anyhow::Result<u64> {
+    let snapshot_path = sibling(backup_path, ".snapshot");
+    let partial_path = sibling(backup_path, ".partial");
+    remove_if_exists(&snapshot_path)?;
+    remove_if_exists(&partial_path)?;
+
+    let res = (|| -> anyhow::Result<u64> {
+        let src = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let mut dst = Connection::open(&snapshot_path)?;
+        Backup::new(&src, &mut dst)?.run_to_completion(
+            BACKUP_PAGES_PER_STEP,
+            BACKUP_STEP_PAUSE,
+            None,
+        )?;
+        dst.close().map_err(|(_, e)| e)?;
+
+        if compress || key.is_some() {
+            let mut writer = io::BufWriter::new(File::create(&partial_path)?);
+            seal(
+                &mut io::BufReader::new(File::open(&snapshot_path)?),
+                &mut writer,
+                compress,
+                key,
+            )?;
+            writer
+                .into_inner()
+                .map_err(|e| e.into_error())?
+                .sync_all()?;
+            fs::remove_file(&snapshot_path)?;
+        } else {
+            File::open(&snapshot_path)?.sync_all()?;
+            fs::rename(&snapshot_path, &partial_path)?;
+        }
+        fs::rename(&partial_path, backup_path)?;
+        Ok(fs::metadata(backup_path)?.len())
+    })();
+
+    if res.is_err() {
+        let _ = remove_if_exists(&snapshot_path);
+        let _ = remove_if_exists(&partial_path);
+    }
+    res
+}
+
+/// Checks that a restored snapshot starts with the SQLite header, so that a file that is
+/// not a SQLite database is rejected before it is opened.
+fn ensure_sqlite_database(snapshot_path: &Path) -> anyhow::Result<()> {
+    let mut header = [0; SQLITE_HEADER.len()];
+    let read = read_full(&mut File::open(snapshot_path)?, &mut header)?;
+    if read < header.len() || &header != SQLITE_HEADER {
+        return Err(anyhow!("Wallet backup is not a SQLite database"));
+    }
+    Ok(())
+}
+
+fn applied_migrations(conn: &Connection) -> anyhow::Result<BTreeSet<Vec<u8>>> {
+    let mut stmt = conn.prepare("SELECT CAST(id AS BLOB) FROM schemer_migrations")?;
+    stmt.query_and_then([], |row| -> anyhow::Result<_> { Ok(row.get(0)?) })?
+        .collect()
+}
+
+/// Checks that a restored snapshot is intact, belongs to the given network (and to the
+/// given seed, if any), and has a schema this version of the SDK can migrate.
+fn validate(
+    conn: &Connection,
+    network: Network,
+    seed: Option<&SecretVec<u8>>,
+) -> anyhow::Result<()> {
+    let integrity: String = conn.query_row("PRAGMA integrity_check", [], |row| row.get(0))?;
+    if integrity != "ok" {
+        return Err(anyhow!("Wallet backup is corrupt: {}", integrity));
+    }
+
+    // Initializing an empty database records every migration this version knows. The
+    // database is shared in memory between the connection that `init_wallet_db` consumes
+    // and the one it is read through, and lives as long as the latter.
+    let reference_uri = format!(
+        "file:wallet-schema-{:016x}?mode=memory&cache=shared",
+        OsRng.next_u64()
+    );
+    let reference = Connection::open(&reference_uri)?;
+    let mut reference_db = WalletDb::from_connection(
+        Connection::open(&reference_uri)?,
+        network,
+        SystemClock,
+        OsRng,
+    );
+    init_wallet_db(&mut reference_db, None)
+        .map_err(|e| anyhow!("Error while initializing reference schema: {}", e))?;
+    drop(reference_db);
+    if !applied_migrations(conn)?.is_subset(&applied_migrations(&reference)?) {
+        return Err(anyhow!(
+            "Wallet backup was created by a newer version of the SDK"
+        ));
+    }
+
+    let mut stmt = conn.prepare("SELECT uivk, hd_seed_fingerprint FROM accounts")?;
+    let accounts = stmt
+        .query_and_then([], |row| -> anyhow::Result<_> {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<[u8; 32]>>(1)?))
+        })?
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    if accounts.is_empty() {
+        return Err(anyhow!("Wallet backup has no accounts"));
+    }
+
+    let seed_fingerprint = seed
+        .map(|seed| {
+            SeedFingerprint::from_seed(seed.expose_secret())
+                .ok_or_else(|| anyhow!("Invalid seed length"))
+        })
+        .transpose()?;
+    for (uivk, fingerprint) in &accounts {
+        let (net, _) = unified::Uivk::decode(uivk).map_err(|e| anyhow!("{e}"))?;
+        if net != network.network_type() {
+            return Err(anyhow!(
+                "Wallet backup is for network {:?} but we expected {:?}",
+                net,
+                network.network_type(),
+            ));
+        }
+        if let (Some(expected), Some(fingerprint)) = (&seed_fingerprint, fingerprint)
+            && expected.to_bytes() != *fingerprint
+        {
+            return Err(anyhow!(
+                "Wallet backup contains accounts derived from a different seed"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the wallet database at `db_path` with the backup at `backup_path`, after
+/// checking that the backup is valid for the given network and seed and migrating it to
+/// the current schema.
+///
+/// No other connection to the wallet database may be open.
+pub(crate) fn restore(
+    backup_path: &Path,
+    db_path: &Path,
+    network: Network,
+    seed: Option<SecretVec<u8>>,
+    key: Option<&SecretVec<u8>>,
+) -> anyhow::Result<()> {
+    let restoring_path = sibling(db_path, ".restoring");
+    remove_if_exists(&restoring_path)?;
+
+    let res = (|| -> anyhow::Result<()> {
+        let mut backup = File::open(backup_path)?;
+        let mut writer = io::BufWriter::new(File::create(&restoring_path)?);
+        if is_sealed(&mut backup)? {
+            open_sealed(&mut io::BufReader::new(backup), &mut writer, key)?;
+        } else {
+            io::copy(&mut backup, &mut writer)?;
+        }
+        writer
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .sync_all()?;
+        ensure_sqlite_database(&restoring_path)?;
+
+        let mut conn = Connection::open(&restoring_path)?;
+        validate(&conn, network, seed.as_ref())?;
+
+        init_wallet_db(
+            &mut WalletDb::from_connection(&mut conn, network, SystemClock, OsRng),
+            seed,
+        )
+        .map_err(|e| anyhow!("Error while migrating wallet backup: {}", e))?;
+        sdk_schema::init(&conn)?;
+        drop(conn);
+
+        // The journal files of the replaced database must not be applied to the restored one.
+        remove_if_exists(&sibling(db_path, "-wal"))?;
+        remove_if_exists(&sibling(db_path, "-shm"))?;
+        fs::rename(&restoring_path, db_path)?;
+        Ok(())
+    })();
+
+    match res {
+        Ok(()) => {
+            info!("Restored wallet database from backup");
+            Ok(())
+        }
+        Err(e) => {
+            let _ = remove_if_exists(&restoring_path);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::named_params;
+    use zcash_client_backend::data_api::{AccountBirthday, WalletWrite, chain::ChainState};
+    use zcash_primitives::block::BlockHash;
+    use zcash_protocol::consensus::BlockHeight;
+
+    use super::*;
+    use crate::testing::NETWORK;
+
+    /// A temporary directory, which is removed when dropped.
+    struct TestDir(PathBuf);
+
+    impl TestDir {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("wallet-backup-{:016x}", OsRng.next_u64()));
+            fs::create_dir(&dir).unwrap();
+            TestDir(dir)
+        }
+
+        fn path(&self, name: &str) -> PathBuf {
+            self.0.join(name)
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn key(byte: u8) -> SecretVec<u8> {
+        SecretVec::new(vec![byte; KEY_LENGTH])
+    }
+
+    fn seed(byte: u8) -> SecretVec<u8> {
+        SecretVec::new(vec![byte; 32])
+    }
+
+    /// Creates a wallet database at `path` with this crate's tables, and an account
+    /// derived from a seed of repeated `seed_byte`s, unless `seed_byte` is `None`.
+    fn create_wallet(path: &Path, seed_byte: Option<u8>) {
+        let mut conn = Connection::open(path).unwrap();
+        rusqlite::vtab::array::load_module(&conn).unwrap();
+        let mut db_data = WalletDb::from_connection(&mut conn, NETWORK, SystemClock, OsRng);
+        init_wallet_db(&mut db_data, None).unwrap();
+        if let Some(seed_byte) = seed_byte {
+            let birthday = AccountBirthday::from_parts(
+                ChainState::empty(BlockHeight::from_u32(2_999_999), BlockHash([0; 32])),
+                None,
+            );
+            db_data
+                .create_account("account", &seed(seed_byte), &birthday, None)
+                .unwrap();
+        }
+        sdk_schema::init(&conn).unwrap();
+    }
+
+    fn account_count(path: &Path) -> u32 {
+        Connection::open(path)
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM accounts", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    fn seal_bytes(snapshot: &[u8], compress: bool, key: Option<&SecretVec<u8>>) -> Vec<u8> {
+        let mut sealed = vec![];
+        seal(&mut &snapshot[..], &mut sealed, compress, key).unwrap();
+        sealed
+    }
+
+    fn open_bytes(sealed: &[u8], key: Option<&SecretVec<u8>>) -> anyhow::Result<Vec<u8>> {
+        let mut snapshot = vec![];
+        open_sealed(&mut &sealed[..], &mut snapshot, key)?;
+        Ok(snapshot)
+    }
+
+    #[test]
+    fn sealed_backup_round_trips() {
+        // Empty, single-chunk, exactly-chunked and multi-chunk snapshots.
+        for len in [0, 100, CHUNK_LENGTH, 2 * CHUNK_LENGTH, 2 * CHUNK_LENGTH + 1] {
+            let snapshot = (0..len).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+            for (compress, key) in [
+                (false, None),
+                (true, None),
+                (false, Some(key(1))),
+                (true, Some(key(1))),
+            ] {
+                let sealed = seal_bytes(&snapshot, compress, key.as_ref());
+                assert!(sealed.starts_with(MAGIC));
+                assert_eq!(open_bytes(&sealed, key.as_ref()).unwrap(), snapshot);
+            }
+        }
+    }
+
+    #[test]
+    fn encrypted_backup_requires_the_right_key() {
+        let snapshot = vec![7; 2 * CHUNK_LENGTH + 1];
+        let sealed = seal_bytes(&snapshot, true, Some(&key(1)));
+        assert!(open_bytes(&sealed, None).is_err());
+        assert!(open_bytes(&sealed, Some(&key(2))).is_err());
+
+        let mut tampered = sealed.clone();
+        tampered[MAGIC.len() + 1] &= !FLAG_COMPRESSED;
+        assert!(open_bytes(&tampered, Some(&key(1))).is_err());
+
+        // Dropping the last chunk, or everything after the first, is detected.
+        let sealed = seal_bytes(&snapshot, false, Some(&key(1)));
+        let first_chunk_end = HEADER_LENGTH + NONCE_LENGTH + CHUNK_LENGTH + TAG_LENGTH;
+        for len in [sealed.len() - 1 - TAG_LENGTH, first_chunk_end] {
+            assert!(open_bytes(&sealed[..len], Some(&key(1))).is_err());
+        }
+    }
+
+    #[test]
+    fn bare_snapshot_is_not_sealed() {
+        let dir = TestDir::new();
+        let path = dir.path("snapshot");
+        fs::write(&path, SQLITE_HEADER).unwrap();
+        assert!(!is_sealed(&mut File::open(&path).unwrap()).unwrap());
+        ensure_sqlite_database(&path).unwrap();
+
+        fs::write(&path, [0x5a; 32]).unwrap();
+        assert_eq!(
+            ensure_sqlite_database(&path).unwrap_err().to_string(),
+            "Wallet backup is not a SQLite database"
+        );
+    }
+
+    #[test]
+    fn backup_of_a_wallet_restores() {
+        let dir = TestDir::new();
+        let (db_path, backup_path) = (dir.path("data.sqlite"), dir.path("backup"));
+        create_wallet(&db_path, Some(1));
+        let restored_path = dir.path("restored.sqlite");
+        create_wallet(&restored_path, None);
+
+        for (compress, key) in [(false, None), (true, Some(key(1)))] {
+            // The online backup reads the database while another connection has it open.
+            let conn = Connection::open(&db_path).unwrap();
+            let size = backup(&db_path, &backup_path, compress, key.as_ref()).unwrap();
+            drop(conn);
+            assert_eq!(size, fs::metadata(&backup_path).unwrap().len());
+
+            restore(
+                &backup_path,
+                &restored_path,
+                NETWORK,
+                Some(seed(1)),
+                key.as_ref(),
+            )
+            .unwrap();
+            assert_eq!(account_count(&restored_path), 1);
+            fs::write(&restored_path, []).unwrap();
+        }
+        assert!(!sibling(&restored_path, ".restoring").exists());
+    }
+
+    #[test]
+    fn invalid_backups_leave_the_wallet_untouched() {
+        let dir = TestDir::new();
+        let (db_path, backup_path) = (dir.path("data.sqlite"), dir.path("backup"));
+        let target_path = dir.path("target.sqlite");
+        create_wallet(&target_path, Some(3));
+        let target = fs::read(&target_path).unwrap();
+
+        let assert_rejected = |network: Network, seed_byte: Option<u8>, expected: &str| {
+            backup(&db_path, &backup_path, false, None).unwrap();
+            let err = restore(
+                &backup_path,
+                &target_path,
+                network,
+                seed_byte.map(seed),
+                None,
+            )
+            .unwrap_err();
+            assert!(err.to_string().contains(expected), "{}", err);
+            assert_eq!(fs::read(&target_path).unwrap(), target);
+            assert!(!sibling(&target_path, ".restoring").exists());
+        };
+
+        create_wallet(&db_path, None);
+        assert_rejected(NETWORK, None, "Wallet backup has no accounts");
+        fs::remove_file(&db_path).unwrap();
+
+        create_wallet(&db_path, Some(1));
+        assert_rejected(Network::MainNetwork, None, "Wallet backup is for network");
+        assert_rejected(NETWORK, Some(2), "derived from a different seed");
+
+        Connection::open(&db_path)
+            .unwrap()
+            .execute(
+                "INSERT INTO schemer_migrations (id) VALUES (:id)",
+                named_params! {":id": [0xffu8; 16]},
+            )
+            .unwrap();
+        assert_rejected(NETWORK, Some(1), "created by a newer version of the SDK");
+    }
+}
@@ -0,0 +1,138 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import kotlin.test.Test
+import kotlin.test.assertFailsWith
+import kotlin.test.assertFalse
+import kotlin.test.assertIs
+import kotlin.test.assertTrue
+
+class JniBlockCacheReportTest {
+    @Test
+    fun attributes_within_constraints() {
+        val instance =
+            JniBlockCacheReport(
+                blockCount = 3,
+                minHeight = 10,
+                maxHeight = UInt.MAX_VALUE.toLong(),
+                missingHeights = longArrayOf(),
+                corruptHeights = longArrayOf(),
+                holeStartHeights = longArrayOf(11),
+                holeEndHeights = longArrayOf(12),
+                totalBytes = 1024
+            )
+        assertIs<JniBlockCacheReport>(instance)
+        assertTrue(instance.isIntact)
+    }
+
+    @Test
+    fun empty_cache_allowed() {
+        val instance =
+            JniBlockCacheReport(
+                blockCount = 0,
+                minHeight = -1,
+                maxHeight = -1,
+                missingHeights = longArrayOf(),
+                corruptHeights = longArrayOf(),
+                holeStartHeights = longArrayOf(),
+                holeEndHeights = longArrayOf(),
+                totalBytes = 0
+            )
+        assertIs<JniBlockCacheReport>(instance)
+    }
+
+    @Test
+    fun damaged_cache_is_not_intact() {
+        val instance =
+            JniBlockCacheReport(
+                blockCount = 2,
+                minHeight = 10,
+                maxHeight = 11,
+                missingHeights = longArrayOf(),
+                corruptHeights = longArrayOf(11),
+                holeStartHeights = longArrayOf(),
+                holeEndHeights = longArrayOf(),
+                totalBytes = 0
+            )
+        assertFalse(instance.isIntact)
+    }
+
+    @Test
+    fun block_count_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniBlockCacheReport(
+                blockCount = -1,
+                minHeight = -1,
+                maxHeight = -1,
+                missingHeights = longArrayOf(),
+                corruptHeights = longArrayOf(),
+                holeStartHeights = longArrayOf(),
+                holeEndHeights = longArrayOf(),
+                totalBytes = 0
+            )
+        }
+    }
+
+    @Test
+    fun height_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniBlockCacheReport(
+                blockCount = 1,
+                minHeight = 1,
+                maxHeight = UInt.MAX_VALUE.toLong() + 1,
+                missingHeights = longArrayOf(),
+                corruptHeights = longArrayOf(),
+                holeStartHeights = longArrayOf(),
+                holeEndHeights = longArrayOf(),
+                totalBytes = 0
+            )
+        }
+    }
+
+    @Test
+    fun max_height_below_min_height_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniBlockCacheReport(
+                blockCount = 2,
+                minHeight = 11,
+                maxHeight = 10,
+                missingHeights = longArrayOf(),
+                corruptHeights = longArrayOf(),
+                holeStartHeights = longArrayOf(),
+                holeEndHeights = longArrayOf(),
+                totalBytes = 0
+            )
+        }
+    }
+
+    @Test
+    fun holes_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniBlockCacheReport(
+                blockCount = 2,
+                minHeight = 10,
+                maxHeight = 12,
+                missingHeights = longArrayOf(),
+                corruptHeights = longArrayOf(),
+                holeStartHeights = longArrayOf(11),
+                holeEndHeights = longArrayOf(),
+                totalBytes = 0
+            )
+        }
+    }
+
+    @Test
+    fun total_bytes_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniBlockCacheReport(
+                blockCount = 0,
+                minHeight = -1,
+                maxHeight = -1,
+                missingHeights = longArrayOf(),
+                corruptHeights = longArrayOf(),
+                holeStartHeights = longArrayOf(),
+                holeEndHeights = longArrayOf(),
+                totalBytes = -1
+            )
+        }
+    }
+}
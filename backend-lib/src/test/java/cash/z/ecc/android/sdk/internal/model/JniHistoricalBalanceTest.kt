@@ -0,0 +1,98 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import kotlin.test.Test
+import kotlin.test.assertFailsWith
+import kotlin.test.assertIs
+
+class JniHistoricalBalanceTest {
+    @Test
+    fun attributes_within_constraints() {
+        val instance =
+            JniHistoricalBalance(
+                height = UInt.MAX_VALUE.toLong(),
+                blockTime = UInt.MAX_VALUE.toLong(),
+                transparent = 1,
+                sapling = 2,
+                orchard = 3
+            )
+        assertIs<JniHistoricalBalance>(instance)
+    }
+
+    @Test
+    fun unscanned_block_time_allowed() {
+        val instance =
+            JniHistoricalBalance(
+                height = 1,
+                blockTime = -1,
+                transparent = 0,
+                sapling = 0,
+                orchard = 0
+            )
+        assertIs<JniHistoricalBalance>(instance)
+    }
+
+    @Test
+    fun height_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniHistoricalBalance(
+                height = -1,
+                blockTime = 0,
+                transparent = 0,
+                sapling = 0,
+                orchard = 0
+            )
+        }
+    }
+
+    @Test
+    fun block_time_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniHistoricalBalance(
+                height = 1,
+                blockTime = -2,
+                transparent = 0,
+                sapling = 0,
+                orchard = 0
+            )
+        }
+    }
+
+    @Test
+    fun transparent_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniHistoricalBalance(
+                height = 1,
+                blockTime = 0,
+                transparent = -1,
+                sapling = 0,
+                orchard = 0
+            )
+        }
+    }
+
+    @Test
+    fun sapling_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniHistoricalBalance(
+                height = 1,
+                blockTime = 0,
+                transparent = 0,
+                sapling = -1,
+                orchard = 0
+            )
+        }
+    }
+
+    @Test
+    fun orchard_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniHistoricalBalance(
+                height = 1,
+                blockTime = 0,
+                transparent = 0,
+                sapling = 0,
+                orchard = -1
+            )
+        }
+    }
+}
@@ -0,0 +1,111 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import kotlin.test.Test
+import kotlin.test.assertFailsWith
+import kotlin.test.assertIs
+
+class JniMigrationStepTest {
+    @Test
+    fun attributes_within_constraints() {
+        val instance =
+            JniMigrationStep(
+                stepIndex = 0,
+                value = 100_000,
+                dueHeight = UInt.MAX_VALUE.toLong(),
+                txid = ByteArray(32),
+                status = JniMigrationStep.STATUS_MINED
+            )
+        assertIs<JniMigrationStep>(instance)
+    }
+
+    @Test
+    fun pending_step_without_txid_allowed() {
+        val instance =
+            JniMigrationStep(
+                stepIndex = 1,
+                value = 100_000,
+                dueHeight = 1,
+                txid = null,
+                status = JniMigrationStep.STATUS_PENDING
+            )
+        assertIs<JniMigrationStep>(instance)
+    }
+
+    @Test
+    fun step_index_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniMigrationStep(
+                stepIndex = -1,
+                value = 100_000,
+                dueHeight = 1,
+                txid = null,
+                status = JniMigrationStep.STATUS_PENDING
+            )
+        }
+    }
+
+    @Test
+    fun value_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniMigrationStep(
+                stepIndex = 0,
+                value = 0,
+                dueHeight = 1,
+                txid = null,
+                status = JniMigrationStep.STATUS_PENDING
+            )
+        }
+    }
+
+    @Test
+    fun due_height_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniMigrationStep(
+                stepIndex = 0,
+                value = 100_000,
+                dueHeight = UInt.MAX_VALUE.toLong() + 1,
+                txid = null,
+                status = JniMigrationStep.STATUS_PENDING
+            )
+        }
+    }
+
+    @Test
+    fun txid_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniMigrationStep(
+                stepIndex = 0,
+                value = 100_000,
+                dueHeight = 1,
+                txid = ByteArray(31),
+                status = JniMigrationStep.STATUS_SUBMITTED
+            )
+        }
+    }
+
+    @Test
+    fun status_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniMigrationStep(
+                stepIndex = 0,
+                value = 100_000,
+                dueHeight = 1,
+                txid = ByteArray(32),
+                status = 3
+            )
+        }
+    }
+
+    @Test
+    fun submitted_step_without_txid_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniMigrationStep(
+                stepIndex = 0,
+                value = 100_000,
+                dueHeight = 1,
+                txid = null,
+                status = JniMigrationStep.STATUS_SUBMITTED
+            )
+        }
+    }
+}
@@ -0,0 +1,112 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import kotlin.test.Test
+import kotlin.test.assertFailsWith
+import kotlin.test.assertIs
+
+class JniPcztOutputTest {
+    @Test
+    fun attributes_within_constraints() {
+        val instance =
+            JniPcztOutput(
+                poolType = ZcashProtocol.ORCHARD.poolCode,
+                index = 0,
+                kind = JniPcztOutput.KIND_EXTERNAL,
+                address = "u1address",
+                value = 1,
+                memo = ByteArray(512),
+                missingProof = false
+            )
+        assertIs<JniPcztOutput>(instance)
+    }
+
+    @Test
+    fun unknown_recipient_allowed() {
+        val instance =
+            JniPcztOutput(
+                poolType = ZcashProtocol.TRANSPARENT.poolCode,
+                index = 1,
+                kind = JniPcztOutput.KIND_UNKNOWN,
+                address = null,
+                value = -1,
+                memo = null,
+                missingProof = false
+            )
+        assertIs<JniPcztOutput>(instance)
+    }
+
+    @Test
+    fun pool_type_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniPcztOutput(
+                poolType = 1,
+                index = 0,
+                kind = JniPcztOutput.KIND_EXTERNAL,
+                address = null,
+                value = 1,
+                memo = null,
+                missingProof = false
+            )
+        }
+    }
+
+    @Test
+    fun index_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniPcztOutput(
+                poolType = ZcashProtocol.SAPLING.poolCode,
+                index = -1,
+                kind = JniPcztOutput.KIND_EXTERNAL,
+                address = null,
+                value = 1,
+                memo = null,
+                missingProof = true
+            )
+        }
+    }
+
+    @Test
+    fun kind_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniPcztOutput(
+                poolType = ZcashProtocol.SAPLING.poolCode,
+                index = 0,
+                kind = JniPcztOutput.KIND_UNKNOWN + 1,
+                address = null,
+                value = 1,
+                memo = null,
+                missingProof = true
+            )
+        }
+    }
+
+    @Test
+    fun value_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniPcztOutput(
+                poolType = ZcashProtocol.SAPLING.poolCode,
+                index = 0,
+                kind = JniPcztOutput.KIND_INTERNAL,
+                address = null,
+                value = -2,
+                memo = null,
+                missingProof = true
+            )
+        }
+    }
+
+    @Test
+    fun memo_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniPcztOutput(
+                poolType = ZcashProtocol.SAPLING.poolCode,
+                index = 0,
+                kind = JniPcztOutput.KIND_EXTERNAL,
+                address = null,
+                value = 1,
+                memo = ByteArray(511),
+                missingProof = true
+            )
+        }
+    }
+}
@@ -0,0 +1,72 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import kotlin.test.Test
+import kotlin.test.assertFailsWith
+import kotlin.test.assertIs
+
+class JniPcztSpendTest {
+    @Test
+    fun attributes_within_constraints() {
+        val instance =
+            JniPcztSpend(
+                poolType = ZcashProtocol.SAPLING.poolCode,
+                index = 0,
+                value = 1,
+                missingSignature = true,
+                missingProof = true
+            )
+        assertIs<JniPcztSpend>(instance)
+    }
+
+    @Test
+    fun redacted_value_allowed() {
+        val instance =
+            JniPcztSpend(
+                poolType = ZcashProtocol.TRANSPARENT.poolCode,
+                index = 1,
+                value = -1,
+                missingSignature = false,
+                missingProof = false
+            )
+        assertIs<JniPcztSpend>(instance)
+    }
+
+    @Test
+    fun pool_type_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniPcztSpend(
+                poolType = 1,
+                index = 0,
+                value = 1,
+                missingSignature = false,
+                missingProof = false
+            )
+        }
+    }
+
+    @Test
+    fun index_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniPcztSpend(
+                poolType = ZcashProtocol.ORCHARD.poolCode,
+                index = -1,
+                value = 1,
+                missingSignature = false,
+                missingProof = false
+            )
+        }
+    }
+
+    @Test
+    fun value_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniPcztSpend(
+                poolType = ZcashProtocol.ORCHARD.poolCode,
+                index = 0,
+                value = -2,
+                missingSignature = false,
+                missingProof = false
+            )
+        }
+    }
+}
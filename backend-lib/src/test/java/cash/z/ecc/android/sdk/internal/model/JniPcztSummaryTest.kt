@@ -0,0 +1,80 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import kotlin.test.Test
+import kotlin.test.assertFailsWith
+import kotlin.test.assertFalse
+import kotlin.test.assertIs
+import kotlin.test.assertTrue
+
+class JniPcztSummaryTest {
+    private fun spend(
+        missingSignature: Boolean = false,
+        missingProof: Boolean = false
+    ) = JniPcztSpend(
+        poolType = ZcashProtocol.SAPLING.poolCode,
+        index = 0,
+        value = 2,
+        missingSignature = missingSignature,
+        missingProof = missingProof
+    )
+
+    private fun output(missingProof: Boolean = false) =
+        JniPcztOutput(
+            poolType = ZcashProtocol.SAPLING.poolCode,
+            index = 0,
+            kind = JniPcztOutput.KIND_EXTERNAL,
+            address = null,
+            value = 1,
+            memo = null,
+            missingProof = missingProof
+        )
+
+    private fun summary(
+        spends: Array<JniPcztSpend> = arrayOf(spend()),
+        outputs: Array<JniPcztOutput> = arrayOf(output()),
+        fee: Long = 1,
+        expiryHeight: Long = UInt.MAX_VALUE.toLong(),
+        consensusBranchId: Long = UInt.MAX_VALUE.toLong()
+    ) = JniPcztSummary(
+        spends = spends,
+        outputs = outputs,
+        fee = fee,
+        expiryHeight = expiryHeight,
+        consensusBranchId = consensusBranchId
+    )
+
+    @Test
+    fun attributes_within_constraints() {
+        assertIs<JniPcztSummary>(summary())
+        assertIs<JniPcztSummary>(summary(spends = emptyArray(), outputs = emptyArray(), fee = -1, expiryHeight = 0))
+    }
+
+    @Test
+    fun fee_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            summary(fee = -2)
+        }
+    }
+
+    @Test
+    fun expiry_height_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            summary(expiryHeight = -1)
+        }
+    }
+
+    @Test
+    fun consensus_branch_id_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            summary(consensusBranchId = -1)
+        }
+    }
+
+    @Test
+    fun incomplete_while_any_signature_or_proof_is_missing() {
+        assertFalse(summary().isIncomplete)
+        assertTrue(summary(spends = arrayOf(spend(), spend(missingSignature = true))).isIncomplete)
+        assertTrue(summary(spends = arrayOf(spend(missingProof = true))).isIncomplete)
+        assertTrue(summary(outputs = arrayOf(output(missingProof = true))).isIncomplete)
+    }
+}
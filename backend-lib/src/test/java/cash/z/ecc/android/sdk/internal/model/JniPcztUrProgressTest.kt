@@ -0,0 +1,72 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import kotlin.test.Test
+import kotlin.test.assertFailsWith
+import kotlin.test.assertFalse
+import kotlin.test.assertIs
+import kotlin.test.assertTrue
+
+class JniPcztUrProgressTest {
+    @Test
+    fun attributes_within_constraints() {
+        val instance =
+            JniPcztUrProgress(
+                receivedParts = 3,
+                expectedParts = 5,
+                pczt = null
+            )
+        assertIs<JniPcztUrProgress>(instance)
+        assertFalse(instance.isComplete)
+    }
+
+    @Test
+    fun no_parts_received_allowed() {
+        val instance =
+            JniPcztUrProgress(
+                receivedParts = 0,
+                expectedParts = -1,
+                pczt = null
+            )
+        assertIs<JniPcztUrProgress>(instance)
+    }
+
+    @Test
+    fun complete_when_pczt_reassembled() {
+        val instance =
+            JniPcztUrProgress(
+                receivedParts = 5,
+                expectedParts = 5,
+                pczt = ByteArray(10)
+            )
+        assertTrue(instance.isComplete)
+    }
+
+    @Test
+    fun received_parts_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniPcztUrProgress(
+                receivedParts = -1,
+                expectedParts = 5,
+                pczt = null
+            )
+        }
+        assertFailsWith(IllegalArgumentException::class) {
+            JniPcztUrProgress(
+                receivedParts = 6,
+                expectedParts = 5,
+                pczt = null
+            )
+        }
+    }
+
+    @Test
+    fun expected_parts_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniPcztUrProgress(
+                receivedParts = 0,
+                expectedParts = 0,
+                pczt = null
+            )
+        }
+    }
+}
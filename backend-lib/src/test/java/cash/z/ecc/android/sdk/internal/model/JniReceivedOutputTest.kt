@@ -0,0 +1,115 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import kotlin.test.Test
+import kotlin.test.assertFailsWith
+import kotlin.test.assertIs
+
+class JniReceivedOutputTest {
+    @Test
+    fun attributes_within_constraints() {
+        val instance =
+            JniReceivedOutput(
+                txid = ByteArray(32),
+                poolType = ZcashProtocol.SAPLING.poolCode,
+                outputIndex = 0,
+                value = 1,
+                minedHeight = UInt.MAX_VALUE.toLong(),
+                confirmations = 10,
+                spendStatus = JniReceivedOutput.SPEND_STATUS_UNSPENT,
+                hasMemo = true,
+                isChange = false,
+                isSpendable = true
+            )
+        assertIs<JniReceivedOutput>(instance)
+    }
+
+    @Test
+    fun unmined_height_allowed() {
+        val instance =
+            JniReceivedOutput(
+                txid = ByteArray(32),
+                poolType = ZcashProtocol.TRANSPARENT.poolCode,
+                outputIndex = 1,
+                value = 0,
+                minedHeight = -1,
+                confirmations = 0,
+                spendStatus = JniReceivedOutput.SPEND_STATUS_PENDING_SPEND,
+                hasMemo = false,
+                isChange = true,
+                isSpendable = false
+            )
+        assertIs<JniReceivedOutput>(instance)
+    }
+
+    @Test
+    fun txid_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniReceivedOutput(
+                txid = ByteArray(31),
+                poolType = ZcashProtocol.ORCHARD.poolCode,
+                outputIndex = 0,
+                value = 1,
+                minedHeight = 1,
+                confirmations = 1,
+                spendStatus = JniReceivedOutput.SPEND_STATUS_SPENT,
+                hasMemo = false,
+                isChange = false,
+                isSpendable = false
+            )
+        }
+    }
+
+    @Test
+    fun pool_type_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniReceivedOutput(
+                txid = ByteArray(32),
+                poolType = 1,
+                outputIndex = 0,
+                value = 1,
+                minedHeight = 1,
+                confirmations = 1,
+                spendStatus = JniReceivedOutput.SPEND_STATUS_UNSPENT,
+                hasMemo = false,
+                isChange = false,
+                isSpendable = true
+            )
+        }
+    }
+
+    @Test
+    fun confirmations_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniReceivedOutput(
+                txid = ByteArray(32),
+                poolType = ZcashProtocol.ORCHARD.poolCode,
+                outputIndex = 0,
+                value = 1,
+                minedHeight = 1,
+                confirmations = -1,
+                spendStatus = JniReceivedOutput.SPEND_STATUS_UNSPENT,
+                hasMemo = false,
+                isChange = false,
+                isSpendable = true
+            )
+        }
+    }
+
+    @Test
+    fun spend_status_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniReceivedOutput(
+                txid = ByteArray(32),
+                poolType = ZcashProtocol.ORCHARD.poolCode,
+                outputIndex = 0,
+                value = 1,
+                minedHeight = 1,
+                confirmations = 1,
+                spendStatus = 3,
+                hasMemo = false,
+                isChange = false,
+                isSpendable = true
+            )
+        }
+    }
+}
@@ -0,0 +1,105 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import kotlin.test.Test
+import kotlin.test.assertFailsWith
+import kotlin.test.assertIs
+
+class JniRewindAffectedOutputTest {
+    @Test
+    fun attributes_within_constraints() {
+        val instance =
+            JniRewindAffectedOutput(
+                accountUuid = ByteArray(16),
+                txid = ByteArray(32),
+                poolType = ZcashProtocol.ORCHARD.poolCode,
+                outputIndex = 0,
+                value = 1,
+                minedHeight = UInt.MAX_VALUE.toLong()
+            )
+        assertIs<JniRewindAffectedOutput>(instance)
+    }
+
+    @Test
+    fun account_uuid_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniRewindAffectedOutput(
+                accountUuid = ByteArray(15),
+                txid = ByteArray(32),
+                poolType = ZcashProtocol.SAPLING.poolCode,
+                outputIndex = 0,
+                value = 1,
+                minedHeight = 1
+            )
+        }
+    }
+
+    @Test
+    fun txid_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniRewindAffectedOutput(
+                accountUuid = ByteArray(16),
+                txid = ByteArray(31),
+                poolType = ZcashProtocol.SAPLING.poolCode,
+                outputIndex = 0,
+                value = 1,
+                minedHeight = 1
+            )
+        }
+    }
+
+    @Test
+    fun pool_type_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniRewindAffectedOutput(
+                accountUuid = ByteArray(16),
+                txid = ByteArray(32),
+                poolType = 1,
+                outputIndex = 0,
+                value = 1,
+                minedHeight = 1
+            )
+        }
+    }
+
+    @Test
+    fun output_index_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniRewindAffectedOutput(
+                accountUuid = ByteArray(16),
+                txid = ByteArray(32),
+                poolType = ZcashProtocol.TRANSPARENT.poolCode,
+                outputIndex = -1,
+                value = 1,
+                minedHeight = 1
+            )
+        }
+    }
+
+    @Test
+    fun value_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniRewindAffectedOutput(
+                accountUuid = ByteArray(16),
+                txid = ByteArray(32),
+                poolType = ZcashProtocol.TRANSPARENT.poolCode,
+                outputIndex = 0,
+                value = -1,
+                minedHeight = 1
+            )
+        }
+    }
+
+    @Test
+    fun mined_height_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniRewindAffectedOutput(
+                accountUuid = ByteArray(16),
+                txid = ByteArray(32),
+                poolType = ZcashProtocol.ORCHARD.poolCode,
+                outputIndex = 0,
+                value = 1,
+                minedHeight = -1
+            )
+        }
+    }
+}
@@ -0,0 +1,118 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import kotlin.test.Test
+import kotlin.test.assertFailsWith
+import kotlin.test.assertFalse
+import kotlin.test.assertIs
+import kotlin.test.assertTrue
+
+class JniRewindPreviewTest {
+    private val affectedOutput =
+        JniRewindAffectedOutput(
+            accountUuid = ByteArray(16),
+            txid = ByteArray(32),
+            poolType = ZcashProtocol.SAPLING.poolCode,
+            outputIndex = 0,
+            value = 1,
+            minedHeight = 15
+        )
+
+    @Test
+    fun attributes_within_constraints() {
+        val instance =
+            JniRewindPreview(
+                requestedHeight = 17,
+                rewindHeight = 15,
+                safeRewindHeight = -1,
+                unminedTxids = arrayOf(ByteArray(32)),
+                affectedOutputs = arrayOf(affectedOutput),
+                blocksToRescan = 5
+            )
+        assertIs<JniRewindPreview>(instance)
+        assertTrue(instance.isValid)
+    }
+
+    @Test
+    fun invalid_rewind_allowed() {
+        val instance =
+            JniRewindPreview(
+                requestedHeight = 5,
+                rewindHeight = -1,
+                safeRewindHeight = 10,
+                unminedTxids = arrayOf(),
+                affectedOutputs = arrayOf(),
+                blocksToRescan = 0
+            )
+        assertFalse(instance.isValid)
+    }
+
+    @Test
+    fun requested_height_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniRewindPreview(
+                requestedHeight = -1,
+                rewindHeight = -1,
+                safeRewindHeight = -1,
+                unminedTxids = arrayOf(),
+                affectedOutputs = arrayOf(),
+                blocksToRescan = 0
+            )
+        }
+    }
+
+    @Test
+    fun rewind_height_above_requested_height_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniRewindPreview(
+                requestedHeight = 15,
+                rewindHeight = 16,
+                safeRewindHeight = -1,
+                unminedTxids = arrayOf(),
+                affectedOutputs = arrayOf(),
+                blocksToRescan = 0
+            )
+        }
+    }
+
+    @Test
+    fun safe_rewind_height_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniRewindPreview(
+                requestedHeight = 5,
+                rewindHeight = -1,
+                safeRewindHeight = UInt.MAX_VALUE.toLong() + 1,
+                unminedTxids = arrayOf(),
+                affectedOutputs = arrayOf(),
+                blocksToRescan = 0
+            )
+        }
+    }
+
+    @Test
+    fun txid_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniRewindPreview(
+                requestedHeight = 17,
+                rewindHeight = 15,
+                safeRewindHeight = -1,
+                unminedTxids = arrayOf(ByteArray(31)),
+                affectedOutputs = arrayOf(),
+                blocksToRescan = 1
+            )
+        }
+    }
+
+    @Test
+    fun blocks_to_rescan_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniRewindPreview(
+                requestedHeight = 17,
+                rewindHeight = 15,
+                safeRewindHeight = -1,
+                unminedTxids = arrayOf(),
+                affectedOutputs = arrayOf(),
+                blocksToRescan = -1
+            )
+        }
+    }
+}
@@ -0,0 +1,92 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import kotlin.test.Test
+import kotlin.test.assertFailsWith
+import kotlin.test.assertIs
+
+class JniScanSummaryTest {
+    @Test
+    fun attributes_within_constraints() {
+        val instance =
+            JniScanSummary(
+                startHeight = 1,
+                endHeight = UInt.MAX_VALUE.toLong(),
+                spentSaplingNoteCount = 1,
+                receivedSaplingNoteCount = 2,
+                spentOrchardNoteCount = 3,
+                receivedOrchardNoteCount = 4,
+                blocksProcessed = 100,
+                readAndScanMillis = 0,
+                dbWriteMillis = 0
+            )
+        assertIs<JniScanSummary>(instance)
+    }
+
+    @Test
+    fun height_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniScanSummary(
+                startHeight = -1,
+                endHeight = 1,
+                spentSaplingNoteCount = 0,
+                receivedSaplingNoteCount = 0,
+                spentOrchardNoteCount = 0,
+                receivedOrchardNoteCount = 0,
+                blocksProcessed = 0,
+                readAndScanMillis = 0,
+                dbWriteMillis = 0
+            )
+        }
+    }
+
+    @Test
+    fun end_height_below_start_height_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniScanSummary(
+                startHeight = 2,
+                endHeight = 1,
+                spentSaplingNoteCount = 0,
+                receivedSaplingNoteCount = 0,
+                spentOrchardNoteCount = 0,
+                receivedOrchardNoteCount = 0,
+                blocksProcessed = 0,
+                readAndScanMillis = 0,
+                dbWriteMillis = 0
+            )
+        }
+    }
+
+    @Test
+    fun read_and_scan_duration_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniScanSummary(
+                startHeight = 1,
+                endHeight = 2,
+                spentSaplingNoteCount = 0,
+                receivedSaplingNoteCount = 0,
+                spentOrchardNoteCount = 0,
+                receivedOrchardNoteCount = 0,
+                blocksProcessed = 1,
+                readAndScanMillis = -1,
+                dbWriteMillis = 0
+            )
+        }
+    }
+
+    @Test
+    fun db_write_duration_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniScanSummary(
+                startHeight = 1,
+                endHeight = 2,
+                spentSaplingNoteCount = 0,
+                receivedSaplingNoteCount = 0,
+                spentOrchardNoteCount = 0,
+                receivedOrchardNoteCount = 0,
+                blocksProcessed = 1,
+                readAndScanMillis = 0,
+                dbWriteMillis = -1
+            )
+        }
+    }
+}
@@ -0,0 +1,59 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import kotlin.test.Test
+import kotlin.test.assertFailsWith
+import kotlin.test.assertIs
+
+class JniSpendableInputTest {
+    @Test
+    fun attributes_within_constraints() {
+        val instance =
+            JniSpendableInput(
+                txid = ByteArray(32),
+                poolType = ZcashProtocol.ORCHARD.poolCode,
+                outputIndex = 0,
+                value = 1,
+                minedHeight = UInt.MAX_VALUE.toLong()
+            )
+        assertIs<JniSpendableInput>(instance)
+    }
+
+    @Test
+    fun unmined_height_allowed() {
+        val instance =
+            JniSpendableInput(
+                txid = ByteArray(32),
+                poolType = ZcashProtocol.TRANSPARENT.poolCode,
+                outputIndex = 1,
+                value = 0,
+                minedHeight = -1
+            )
+        assertIs<JniSpendableInput>(instance)
+    }
+
+    @Test
+    fun txid_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniSpendableInput(
+                txid = ByteArray(31),
+                poolType = ZcashProtocol.SAPLING.poolCode,
+                outputIndex = 0,
+                value = 1,
+                minedHeight = 1
+            )
+        }
+    }
+
+    @Test
+    fun pool_type_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniSpendableInput(
+                txid = ByteArray(32),
+                poolType = 1,
+                outputIndex = 0,
+                value = 1,
+                minedHeight = 1
+            )
+        }
+    }
+}
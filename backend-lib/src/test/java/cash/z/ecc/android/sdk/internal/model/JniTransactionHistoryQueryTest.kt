@@ -0,0 +1,45 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import kotlin.test.Test
+import kotlin.test.assertFailsWith
+import kotlin.test.assertIs
+
+class JniTransactionHistoryQueryTest {
+    @Test
+    fun attributes_within_constraints() {
+        val instance =
+            JniTransactionHistoryQuery(
+                accountUuid = ByteArray(16),
+                fromHeight = 0,
+                toHeight = UInt.MAX_VALUE.toLong(),
+                direction = JniTransactionHistoryEntry.DIRECTION_SHIELDING,
+                poolType = ZcashProtocol.SAPLING.poolCode,
+                status = JniTransactionHistoryEntry.STATUS_EXPIRED,
+                limit = 1
+            )
+        assertIs<JniTransactionHistoryQuery>(instance)
+    }
+
+    @Test
+    fun unset_criteria_allowed() {
+        val instance = JniTransactionHistoryQuery(limit = 20)
+        assertIs<JniTransactionHistoryQuery>(instance)
+    }
+
+    @Test
+    fun direction_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniTransactionHistoryQuery(
+                direction = JniTransactionHistoryEntry.DIRECTION_SHIELDING + 1,
+                limit = 20
+            )
+        }
+    }
+
+    @Test
+    fun limit_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniTransactionHistoryQuery(limit = 0)
+        }
+    }
+}
@@ -0,0 +1,169 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import kotlin.test.Test
+import kotlin.test.assertFailsWith
+import kotlin.test.assertIs
+
+class JniTransactionOutputTest {
+    @Test
+    fun attributes_within_constraints() {
+        val instance =
+            JniTransactionOutput(
+                poolType = ZcashProtocol.SAPLING.poolCode,
+                outputIndex = 0,
+                fromAccountUuid = ByteArray(16),
+                toAccountUuid = null,
+                recipient = "recipient",
+                value = 1,
+                isChange = false,
+                memo = ByteArray(512),
+                memoKind = JniTransactionOutput.MEMO_KIND_TEXT
+            )
+        assertIs<JniTransactionOutput>(instance)
+    }
+
+    @Test
+    fun output_without_memo_allowed() {
+        val instance =
+            JniTransactionOutput(
+                poolType = ZcashProtocol.TRANSPARENT.poolCode,
+                outputIndex = 1,
+                fromAccountUuid = null,
+                toAccountUuid = ByteArray(16),
+                recipient = null,
+                value = 0,
+                isChange = true,
+                memo = null,
+                memoKind = -1
+            )
+        assertIs<JniTransactionOutput>(instance)
+    }
+
+    @Test
+    fun pool_type_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniTransactionOutput(
+                poolType = 1,
+                outputIndex = 0,
+                fromAccountUuid = null,
+                toAccountUuid = null,
+                recipient = null,
+                value = 1,
+                isChange = false,
+                memo = null,
+                memoKind = -1
+            )
+        }
+    }
+
+    @Test
+    fun output_index_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniTransactionOutput(
+                poolType = ZcashProtocol.ORCHARD.poolCode,
+                outputIndex = -1,
+                fromAccountUuid = null,
+                toAccountUuid = null,
+                recipient = null,
+                value = 1,
+                isChange = false,
+                memo = null,
+                memoKind = -1
+            )
+        }
+    }
+
+    @Test
+    fun account_uuid_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniTransactionOutput(
+                poolType = ZcashProtocol.ORCHARD.poolCode,
+                outputIndex = 0,
+                fromAccountUuid = ByteArray(15),
+                toAccountUuid = null,
+                recipient = null,
+                value = 1,
+                isChange = false,
+                memo = null,
+                memoKind = -1
+            )
+        }
+        assertFailsWith(IllegalArgumentException::class) {
+            JniTransactionOutput(
+                poolType = ZcashProtocol.ORCHARD.poolCode,
+                outputIndex = 0,
+                fromAccountUuid = null,
+                toAccountUuid = ByteArray(17),
+                recipient = null,
+                value = 1,
+                isChange = false,
+                memo = null,
+                memoKind = -1
+            )
+        }
+    }
+
+    @Test
+    fun value_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniTransactionOutput(
+                poolType = ZcashProtocol.ORCHARD.poolCode,
+                outputIndex = 0,
+                fromAccountUuid = null,
+                toAccountUuid = null,
+                recipient = null,
+                value = -1,
+                isChange = false,
+                memo = null,
+                memoKind = -1
+            )
+        }
+    }
+
+    @Test
+    fun memo_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniTransactionOutput(
+                poolType = ZcashProtocol.ORCHARD.poolCode,
+                outputIndex = 0,
+                fromAccountUuid = null,
+                toAccountUuid = null,
+                recipient = null,
+                value = 1,
+                isChange = false,
+                memo = ByteArray(511),
+                memoKind = JniTransactionOutput.MEMO_KIND_EMPTY
+            )
+        }
+    }
+
+    @Test
+    fun memo_kind_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniTransactionOutput(
+                poolType = ZcashProtocol.ORCHARD.poolCode,
+                outputIndex = 0,
+                fromAccountUuid = null,
+                toAccountUuid = null,
+                recipient = null,
+                value = 1,
+                isChange = false,
+                memo = ByteArray(512),
+                memoKind = 4
+            )
+        }
+        assertFailsWith(IllegalArgumentException::class) {
+            JniTransactionOutput(
+                poolType = ZcashProtocol.ORCHARD.poolCode,
+                outputIndex = 0,
+                fromAccountUuid = null,
+                toAccountUuid = null,
+                recipient = null,
+                value = 1,
+                isChange = false,
+                memo = null,
+                memoKind = JniTransactionOutput.MEMO_KIND_TEXT
+            )
+        }
+    }
+}
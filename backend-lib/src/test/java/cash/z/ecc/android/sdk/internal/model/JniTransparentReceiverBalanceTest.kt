@@ -0,0 +1,105 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import kotlin.test.Test
+import kotlin.test.assertFailsWith
+import kotlin.test.assertIs
+
+class JniTransparentReceiverBalanceTest {
+    @Test
+    fun attributes_within_constraints() {
+        val instance =
+            JniTransparentReceiverBalance(
+                address = "tmAddress",
+                scope = JniTransparentReceiverBalance.SCOPE_EPHEMERAL,
+                spendable = 1,
+                pending = 0,
+                immature = 0,
+                utxoCount = 1
+            )
+        assertIs<JniTransparentReceiverBalance>(instance)
+    }
+
+    @Test
+    fun unknown_scope_allowed() {
+        val instance =
+            JniTransparentReceiverBalance(
+                address = "tmAddress",
+                scope = -1,
+                spendable = 0,
+                pending = 0,
+                immature = 0,
+                utxoCount = 0
+            )
+        assertIs<JniTransparentReceiverBalance>(instance)
+    }
+
+    @Test
+    fun scope_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniTransparentReceiverBalance(
+                address = "tmAddress",
+                scope = 3,
+                spendable = 0,
+                pending = 0,
+                immature = 0,
+                utxoCount = 0
+            )
+        }
+    }
+
+    @Test
+    fun spendable_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniTransparentReceiverBalance(
+                address = "tmAddress",
+                scope = JniTransparentReceiverBalance.SCOPE_EXTERNAL,
+                spendable = -1,
+                pending = 0,
+                immature = 0,
+                utxoCount = 0
+            )
+        }
+    }
+
+    @Test
+    fun pending_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniTransparentReceiverBalance(
+                address = "tmAddress",
+                scope = JniTransparentReceiverBalance.SCOPE_EXTERNAL,
+                spendable = 0,
+                pending = -1,
+                immature = 0,
+                utxoCount = 0
+            )
+        }
+    }
+
+    @Test
+    fun immature_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniTransparentReceiverBalance(
+                address = "tmAddress",
+                scope = JniTransparentReceiverBalance.SCOPE_INTERNAL,
+                spendable = 0,
+                pending = 0,
+                immature = -1,
+                utxoCount = 0
+            )
+        }
+    }
+
+    @Test
+    fun utxo_count_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniTransparentReceiverBalance(
+                address = "tmAddress",
+                scope = JniTransparentReceiverBalance.SCOPE_INTERNAL,
+                spendable = 0,
+                pending = 0,
+                immature = 0,
+                utxoCount = -1
+            )
+        }
+    }
+}
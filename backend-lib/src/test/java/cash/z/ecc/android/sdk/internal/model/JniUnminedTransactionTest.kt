@@ -0,0 +1,67 @@
+package cash.z.ecc.android.sdk.internal.model
+
+import kotlin.test.Test
+import kotlin.test.assertFailsWith
+import kotlin.test.assertIs
+
+class JniUnminedTransactionTest {
+    @Test
+    fun attributes_within_constraints() {
+        val instance =
+            JniUnminedTransaction(
+                txid = ByteArray(32),
+                expiryHeight = UInt.MAX_VALUE.toLong(),
+                isExpired = true,
+                hasRawTransaction = true
+            )
+        assertIs<JniUnminedTransaction>(instance)
+    }
+
+    @Test
+    fun no_expiry_height_allowed() {
+        val instance =
+            JniUnminedTransaction(
+                txid = ByteArray(32),
+                expiryHeight = -1,
+                isExpired = false,
+                hasRawTransaction = false
+            )
+        assertIs<JniUnminedTransaction>(instance)
+    }
+
+    @Test
+    fun txid_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniUnminedTransaction(
+                txid = ByteArray(31),
+                expiryHeight = 1,
+                isExpired = false,
+                hasRawTransaction = true
+            )
+        }
+    }
+
+    @Test
+    fun height_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniUnminedTransaction(
+                txid = ByteArray(32),
+                expiryHeight = -2,
+                isExpired = false,
+                hasRawTransaction = true
+            )
+        }
+    }
+
+    @Test
+    fun expired_without_expiry_height_not_in_constraints() {
+        assertFailsWith(IllegalArgumentException::class) {
+            JniUnminedTransaction(
+                txid = ByteArray(32),
+                expiryHeight = -1,
+                isExpired = true,
+                hasRawTransaction = true
+            )
+        }
+    }
+}
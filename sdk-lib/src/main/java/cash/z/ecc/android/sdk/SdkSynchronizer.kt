@@ -1032,11 +1032,12 @@ class SdkSynchronizer private constructor(
     override suspend fun addProofsToPczt(pczt: Pczt) = txManager.addProofsToPczt(pczt)
 
     override suspend fun createTransactionFromPczt(
+        originalPczt: Pczt,
         pcztWithProofs: Pczt,
         pcztWithSignatures: Pczt
     ): Flow<TransactionSubmitResult> {
         // Internally, this logic submits and checks the newly stored and encoded transaction
-        return flowOf(txManager.extractAndStoreTxFromPczt(pcztWithProofs, pcztWithSignatures))
+        return flowOf(txManager.extractAndStoreTxFromPczt(originalPczt, pcztWithProofs, pcztWithSignatures))
             .map { transaction -> txManager.submit(transaction) }
     }
 
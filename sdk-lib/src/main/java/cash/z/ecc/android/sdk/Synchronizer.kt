@@ -407,6 +407,10 @@ interface Synchronizer {
      * Takes a PCZT that has been separately proven and signed, finalizes it, and stores
      * it in the wallet. Internally, this logic also submits and checks the newly stored and encoded transaction.
      *
+     * The transaction is only stored if combining the proven and signed PCZTs leaves every effect of the
+     * transaction described by [originalPczt] unchanged.
+     *
+     * @param originalPczt The PCZT returned by [createPcztFromProposal].
      * @param pcztWithProofs
      * @param pcztWithSignatures
      *
@@ -416,6 +420,7 @@ interface Synchronizer {
      */
     @Throws(PcztException.ExtractAndStoreTxFromPcztException::class)
     suspend fun createTransactionFromPczt(
+        originalPczt: Pczt,
         pcztWithProofs: Pczt,
         pcztWithSignatures: Pczt,
     ): Flow<TransactionSubmitResult>
@@ -1975,7 +1975,7 @@ class CompactBlockProcessor internal constructor(
             runCatching {
                 backend.scanBlocks(batch.range.start, fromState, batch.range.length())
             }.onSuccess {
-                Twig.verbose { "Successfully scanned batch $batch" }
+                Twig.verbose { "Successfully scanned batch $batch: $it" }
             }.onFailure {
                 Twig.error { "Failed while scanning batch $batch with $it" }
             }.fold(
@@ -123,13 +123,14 @@ internal interface TypesafeBackend {
 
     /**
      * Takes a PCZT that has been separately proven and signed, finalizes it, and stores
-     * it in the wallet.
+     * it in the wallet, if it has the same effects as [originalPczt].
      *
      * @return the txid of the completed transaction.
      *
      * @throws RuntimeException as a common indicator of the operation failure
      */
     suspend fun extractAndStoreTxFromPczt(
+        originalPczt: Pczt,
         pcztWithProofs: Pczt,
         pcztWithSignatures: Pczt,
     ): FirstClassByteArray
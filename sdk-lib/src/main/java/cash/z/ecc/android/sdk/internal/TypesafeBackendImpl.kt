@@ -168,11 +168,13 @@ internal class TypesafeBackendImpl(
     override suspend fun addProofsToPczt(pczt: Pczt): Pczt = Pczt(backend.addProofsToPczt(pczt.toByteArray()))
 
     override suspend fun extractAndStoreTxFromPczt(
+        originalPczt: Pczt,
         pcztWithProofs: Pczt,
         pcztWithSignatures: Pczt
     ): FirstClassByteArray =
         FirstClassByteArray(
             backend.extractAndStoreTxFromPczt(
+                originalPczt.toByteArray(),
                 pcztWithProofs.toByteArray(),
                 pcztWithSignatures.toByteArray()
             )
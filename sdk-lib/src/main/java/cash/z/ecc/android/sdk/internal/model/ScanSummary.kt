@@ -1,11 +1,21 @@
 package cash.z.ecc.android.sdk.internal.model
 
 import cash.z.ecc.android.sdk.model.BlockHeight
+import kotlin.time.Duration
+import kotlin.time.Duration.Companion.milliseconds
 
+/**
+ * The results of scanning a range of blocks. See [JniScanSummary] for what is and is not measured.
+ */
 internal data class ScanSummary(
     val scannedRange: ClosedRange<BlockHeight>,
     val spentSaplingNoteCount: Long,
-    val receivedSaplingNoteCount: Long
+    val receivedSaplingNoteCount: Long,
+    val spentOrchardNoteCount: Long,
+    val receivedOrchardNoteCount: Long,
+    val blocksProcessed: Long,
+    val readAndScanTime: Duration,
+    val dbWriteTime: Duration
 ) {
     companion object {
         /**
@@ -17,7 +27,12 @@ internal data class ScanSummary(
             ScanSummary(
                 scannedRange = BlockHeight.new(jni.startHeight)..(BlockHeight.new(jni.endHeight) - 1),
                 spentSaplingNoteCount = jni.spentSaplingNoteCount,
-                receivedSaplingNoteCount = jni.receivedSaplingNoteCount
+                receivedSaplingNoteCount = jni.receivedSaplingNoteCount,
+                spentOrchardNoteCount = jni.spentOrchardNoteCount,
+                receivedOrchardNoteCount = jni.receivedOrchardNoteCount,
+                blocksProcessed = jni.blocksProcessed,
+                readAndScanTime = jni.readAndScanMillis.milliseconds,
+                dbWriteTime = jni.dbWriteMillis.milliseconds
             )
     }
 }
@@ -107,6 +107,7 @@ internal interface OutboundTransactionManager {
     suspend fun addProofsToPczt(pczt: Pczt): Pczt
 
     suspend fun extractAndStoreTxFromPczt(
+        originalPczt: Pczt,
         pcztWithProofs: Pczt,
         pcztWithSignatures: Pczt
     ): EncodedTransaction
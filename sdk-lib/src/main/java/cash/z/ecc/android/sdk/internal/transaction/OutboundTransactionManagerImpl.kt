@@ -127,9 +127,10 @@ internal class OutboundTransactionManagerImpl(
     override suspend fun addProofsToPczt(pczt: Pczt) = encoder.addProofsToPczt(pczt)
 
     override suspend fun extractAndStoreTxFromPczt(
+        originalPczt: Pczt,
         pcztWithProofs: Pczt,
         pcztWithSignatures: Pczt
-    ) = encoder.extractAndStoreTxFromPczt(pcztWithProofs, pcztWithSignatures)
+    ) = encoder.extractAndStoreTxFromPczt(originalPczt, pcztWithProofs, pcztWithSignatures)
 
     override suspend fun isValidShieldedAddress(address: String) = encoder.isValidShieldedAddress(address)
 
@@ -204,12 +204,14 @@ internal class TransactionEncoderImpl(
         }
 
     override suspend fun extractAndStoreTxFromPczt(
+        originalPczt: Pczt,
         pcztWithProofs: Pczt,
         pcztWithSignatures: Pczt
     ): EncodedTransaction {
         val txId =
             runCatching {
                 backend.extractAndStoreTxFromPczt(
+                    originalPczt = originalPczt,
                     pcztWithProofs = pcztWithProofs,
                     pcztWithSignatures = pcztWithSignatures
                 )